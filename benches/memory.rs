@@ -0,0 +1,84 @@
+//! Memory use of RNG handles, measured with a counting allocator.
+//!
+//! Run with `cargo bench --bench memory`. Prints the heap bytes used per handle when cloning a
+//! node and when deriving children, and checks that an `RngPool` in steady state doesn't allocate.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use random_access_rng::RandomAccessRNG;
+use random_access_rng::pool::RngPool;
+use rand_core::RngCore;
+
+/// The system allocator, counting allocations and bytes currently allocated
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        LIVE_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// The heap bytes still held, and the allocations made, by the value `f` returns
+fn measure<T, F: FnOnce() -> T>(f: F) -> (T, usize, usize) {
+    let (bytes, allocations) = (LIVE_BYTES.load(Ordering::Relaxed), ALLOCATIONS.load(Ordering::Relaxed));
+    let value = f();
+
+    (value, LIVE_BYTES.load(Ordering::Relaxed) - bytes, ALLOCATIONS.load(Ordering::Relaxed) - allocations)
+}
+
+const HANDLES: usize = 1_000_000;
+
+fn main() {
+    let npcs = RandomAccessRNG::new("world_seed").get("npcs");
+
+    println!("size_of::<RandomAccessRNG>(): {} bytes", size_of::<RandomAccessRNG>());
+
+    // Clones share the node's hashing state, so only the handles themselves take memory
+    let (clones, bytes, _) = measure(|| {
+        let mut handles = Vec::with_capacity(HANDLES);
+        handles.extend((0..HANDLES as u128).map(|i| {
+            let mut handle = npcs.clone();
+            handle.set_position(i * 1000);
+            handle
+        }));
+        handles
+    });
+    println!("{} clones of one node: {} bytes per handle", HANDLES, bytes / HANDLES);
+    assert!(bytes / HANDLES <= size_of::<RandomAccessRNG>());
+    drop(black_box(clones));
+
+    // Every child holds its own hashing state
+    let (children, bytes, _) = measure(|| {
+        let mut handles = Vec::with_capacity(HANDLES);
+        handles.extend((0..HANDLES as u64).map(|i| npcs.get(i)));
+        handles
+    });
+    println!("{} distinct children: {} bytes per handle", HANDLES, bytes / HANDLES);
+    drop(black_box(children));
+
+    // A pool in steady state reuses released hashing states instead of allocating
+    let mut pool = RngPool::with_capacity(npcs.clone(), 64);
+    let (_, _, allocations) = measure(|| {
+        for _ in 0..HANDLES {
+            let mut rng = pool.acquire();
+            black_box(rng.next_u64());
+            pool.release(rng);
+        }
+    });
+    println!("{} pooled tasks: {} allocations", HANDLES, allocations);
+    assert_eq!(allocations, 0);
+}
@@ -0,0 +1,79 @@
+//! Opens chests across a dungeon with a weighted loot table and writes the drops as CSV, checking
+//! that every chest can be regenerated on its own and that the drop rates match the table.
+//!
+//! ```text
+//! cargo run --example loot_demo [output directory]
+//! ```
+//!
+//! The output directory defaults to `target/demo`.
+
+use random_access_rng::RandomAccessRNG;
+use random_access_rng::dist::{self, Dist};
+use std::{env, fs, io};
+use std::fmt::Write;
+use std::path::PathBuf;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Number of floors in the dungeon
+const FLOORS: u32 = 20;
+
+/// Number of chests on each floor
+const CHESTS: u32 = 500;
+
+/// The loot table, with weights out of 1000
+const TABLE: [(&str, u64); 4] = [("common", 700), ("uncommon", 220), ("rare", 70), ("legendary", 10)];
+
+/// The checksum of the CSV, recorded when the demo was written. Generation is covered by the
+/// crate's reproducibility guarantee, so this must never change.
+const EXPECTED_CHECKSUM: u64 = 0x8f3ee5c5d76cb5b6;
+
+/// The drop in one chest, derived only from its floor and number
+fn open(dungeon: &RandomAccessRNG, floor: u32, chest: u32) -> &'static str {
+    dist::weighted(TABLE).sample(&mut dungeon.get(floor).get(chest))
+}
+
+fn main() -> io::Result<()> {
+    let output = env::args().nth(1).map(PathBuf::from).unwrap_or_else(|| PathBuf::from("target/demo"));
+
+    let dungeon = RandomAccessRNG::new("loot_demo").get("dungeon");
+
+    let mut csv = String::from("floor,chest,drop\n");
+    let mut counts = [0u32; TABLE.len()];
+
+    for floor in 0..FLOORS {
+        for chest in 0..CHESTS {
+            let drop = open(&dungeon, floor, chest);
+
+            writeln!(csv, "{},{},{}", floor, chest, drop).unwrap();
+            counts[TABLE.iter().position(|&(name, _)| name == drop).unwrap()] += 1;
+        }
+    }
+
+    // Any chest regenerates on its own, from a fresh root, without opening the others
+    let fresh = RandomAccessRNG::new("loot_demo").path("dungeon");
+    for (floor, chest) in [(0, 0), (7, 123), (19, 499)] {
+        let line = format!("{},{},{}", floor, chest, open(&fresh, floor, chest));
+        assert!(csv.lines().any(|l| l == line), "chest {} on floor {} regenerated differently", chest, floor);
+    }
+
+    // Drop rates are within a few standard deviations of the table
+    let total = (FLOORS * CHESTS) as f64;
+    for (&(name, weight), &count) in TABLE.iter().zip(&counts) {
+        let p = weight as f64 / 1000.0;
+        let deviation = (count as f64 - total * p).abs() / (total * p * (1.0 - p)).sqrt();
+
+        println!("{:>9}: {:>5} ({:.2}%)", name, count, count as f64 / total * 100.0);
+        assert!(deviation < 5.0, "{} drops are {:.1} standard deviations from the table", name, deviation);
+    }
+
+    let checksum = xxh3_64(csv.as_bytes());
+    println!("loot checksum: {:#018x}", checksum);
+    assert_eq!(checksum, EXPECTED_CHECKSUM, "loot differs from the recorded drops");
+
+    fs::create_dir_all(&output)?;
+    let file = output.join("loot.csv");
+    fs::write(&file, csv)?;
+    println!("wrote {}", file.display());
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+//! Generates a heightmap with fixed-point noise and writes it as a PNG, checking along the way
+//! that generation is deterministic and independent of the order chunks are generated in.
+//!
+//! ```text
+//! cargo run --example terrain_demo [output directory]
+//! ```
+//!
+//! The output directory defaults to `target/demo`.
+
+use random_access_rng::RandomAccessRNG;
+use random_access_rng::noise::{DensityField, DensitySettings, ONE};
+use std::{env, fs, io};
+use std::path::PathBuf;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Width and height of the map in pixels
+const SIZE: usize = 256;
+
+/// Width and height of a chunk in pixels
+const CHUNK: usize = 32;
+
+/// The checksum of the heightmap, recorded when the demo was written. Generation is covered by
+/// the crate's reproducibility guarantee, so this must never change.
+const EXPECTED_CHECKSUM: u64 = 0x85143b8286725abc;
+
+/// Greyscale height of a pixel, from the noise at `y = 0` mapped from `[-ONE, ONE)` to `0..=255`
+fn height(field: &DensityField, x: usize, z: usize) -> u8 {
+    let density = field.density(x as i64, 0, z as i64).clamp(-ONE, ONE - 1);
+
+    ((density + ONE) * 128 / ONE) as u8
+}
+
+/// Generate the map one chunk at a time, visiting the chunks in the given order
+fn generate(field: &DensityField, chunks: impl Iterator<Item = (usize, usize)>) -> Vec<u8> {
+    let mut pixels = vec![0u8; SIZE * SIZE];
+
+    for (cx, cz) in chunks {
+        for z in cz * CHUNK..(cz + 1) * CHUNK {
+            for x in cx * CHUNK..(cx + 1) * CHUNK {
+                pixels[z * SIZE + x] = height(field, x, z);
+            }
+        }
+    }
+
+    pixels
+}
+
+/// CRC-32 as used by PNG chunks
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in bytes {
+        crc ^= byte as u32;
+
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb88320 } else { crc >> 1 };
+        }
+    }
+
+    !crc
+}
+
+/// Append a PNG chunk with its length and checksum
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Encode 8-bit greyscale pixels as a PNG, using uncompressed deflate blocks
+fn encode_png(pixels: &[u8], width: usize, height: usize) -> Vec<u8> {
+    // Each row is preceded by filter type 0
+    let raw: Vec<u8> = pixels.chunks(width).flat_map(|row| std::iter::once(0).chain(row.iter().copied())).collect();
+
+    let mut zlib = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = raw.chunks(65535).collect();
+
+    for (i, block) in blocks.iter().enumerate() {
+        zlib.push((i + 1 == blocks.len()) as u8);
+        zlib.extend_from_slice(&(block.len() as u16).to_le_bytes());
+        zlib.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+
+    let (a, b) = raw.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    zlib.extend_from_slice(&((b << 16) | a).to_be_bytes());
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    header.extend_from_slice(&[8, 0, 0, 0, 0]);
+
+    let mut png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &zlib);
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn main() -> io::Result<()> {
+    let output = env::args().nth(1).map(PathBuf::from).unwrap_or_else(|| PathBuf::from("target/demo"));
+
+    let world = RandomAccessRNG::new("terrain_demo");
+    let field = DensityField::new(&world.get("terrain"), DensitySettings::default());
+
+    let per_side = SIZE / CHUNK;
+    let forwards = generate(&field, (0..per_side).flat_map(|cz| (0..per_side).map(move |cx| (cx, cz))));
+
+    // Chunks generated in reverse order, from a freshly derived field, give the same map
+    let again = DensityField::new(&RandomAccessRNG::new("terrain_demo").path("terrain"), DensitySettings::default());
+    let backwards = generate(&again, (0..per_side).rev().flat_map(|cz| (0..per_side).rev().map(move |cx| (cx, cz))));
+
+    assert!(forwards == backwards, "chunk order changed the terrain");
+
+    let checksum = xxh3_64(&forwards);
+    println!("heightmap checksum: {:#018x}", checksum);
+    assert_eq!(checksum, EXPECTED_CHECKSUM, "terrain differs from the recorded heightmap");
+
+    fs::create_dir_all(&output)?;
+    let file = output.join("terrain.png");
+    fs::write(&file, encode_png(&forwards, SIZE, SIZE))?;
+    println!("wrote {}", file.display());
+
+    Ok(())
+}
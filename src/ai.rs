@@ -0,0 +1,142 @@
+//! Deterministic decision noise for game AI.
+//!
+//! Exploration noise in AI (softmax/Boltzmann action selection) usually relies on `f64::exp`,
+//! whose exact result is not guaranteed to match across platforms and math libraries. The
+//! helpers in this module use fixed-point integer arithmetic only, and draw their noise from a
+//! per-decision key rather than the position in a stream, so an agent's choices replay
+//! identically regardless of how many other decisions were made before it.
+//!
+//! Logits and temperatures are Q16.16 fixed-point numbers, see [`ONE`].
+
+use std::hash::Hash;
+use rand_core::RngCore;
+use crate::RandomAccessRNG;
+
+/// The value `1.0` in the Q16.16 fixed-point format used by this module.
+pub const ONE: i32 = 1 << 16;
+
+/// `log2(e)` in Q16.16
+const LOG2_E: i128 = 94548;
+
+/// Taylor coefficients of `2^f` (powers of `ln 2` over factorials) in Q2.30, highest order first
+const EXP2_COEFFICIENTS: [i64; 5] = [1431680, 10327387, 59597083, 257941248, 744261118];
+
+/// Weights below `2^-30` of the largest weight are treated as zero
+const MAX_SHIFT: i128 = 30;
+
+/// Fixed-point `2^f` for `f` in `[0, 1)` given in Q16, returned in Q2.30.
+fn exp2_fraction(f: i64) -> i64 {
+    let mut acc = 0;
+
+    for c in EXP2_COEFFICIENTS {
+        acc = c + ((acc * f) >> 16);
+    }
+
+    (1 << 30) + ((acc * f) >> 16)
+}
+
+/// Unnormalised softmax weight of a logit `diff` below the maximum (both Q16.16).
+fn softmax_weight(diff: i64, temperature: u32) -> u64 {
+    // diff / temperature in Q16, then converted to a power of two
+    let scaled = ((diff as i128) << 16) / temperature as i128;
+    let exponent = (scaled * LOG2_E) >> 16;
+
+    let whole = exponent >> 16;
+    let fraction = (exponent & 0xFFFF) as i64;
+
+    if whole < -MAX_SHIFT {
+        0
+    } else {
+        // whole is never positive, as diff is never positive
+        exp2_fraction(fraction) as u64 >> -whole
+    }
+}
+
+/// Sample an index from the softmax distribution of `logits` at the given `temperature`.
+///
+/// Both `logits` and `temperature` are Q16.16 fixed-point numbers (multiply by [`ONE`]). The
+/// random draw is derived from `rng.get(key)`, so the same decision key always produces the same
+/// choice for the same inputs, independent of any other sampling done with `rng`.
+///
+/// A temperature of zero is greedy: the largest logit is chosen, with ties broken
+/// deterministically by the key. Very large temperatures approach a uniform choice. Returns
+/// [`None`] if `logits` is empty.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::ai::{softmax_sample, ONE};
+///
+/// let brain = RandomAccessRNG::new("match_seed").get("goblin_ai");
+///
+/// // Attack, defend, flee
+/// let logits = [3 * ONE, ONE, -2 * ONE];
+///
+/// let action = softmax_sample(&brain, &logits, ONE as u32, ("goblin_3", "turn", 17)).unwrap();
+///
+/// // Replays identically for the same decision key
+/// assert_eq!(action, softmax_sample(&brain, &logits, ONE as u32, ("goblin_3", "turn", 17)).unwrap());
+/// ```
+///
+/// ## Temperature edge cases
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::ai::{softmax_sample, ONE};
+///
+/// let rng = RandomAccessRNG::new("seed");
+/// let logits = [ONE, 5 * ONE, 2 * ONE];
+///
+/// // Zero temperature always picks the maximum
+/// for decision in 0..100 {
+///     assert_eq!(softmax_sample(&rng, &logits, 0, decision), Some(1));
+/// }
+///
+/// // A tiny temperature is effectively greedy as well
+/// for decision in 0..100 {
+///     assert_eq!(softmax_sample(&rng, &logits, 1, decision), Some(1));
+/// }
+///
+/// // A huge temperature visits every option
+/// let mut seen = [false; 3];
+/// for decision in 0..100 {
+///     seen[softmax_sample(&rng, &logits, u32::MAX, decision).unwrap()] = true;
+/// }
+/// assert_eq!(seen, [true; 3]);
+///
+/// // Greedy ties are broken by the decision key, but only between the tied maxima
+/// let tied = [2 * ONE, 2 * ONE, ONE];
+/// for decision in 0..100 {
+///     assert_ne!(softmax_sample(&rng, &tied, 0, decision), Some(2));
+/// }
+///
+/// // Extreme logits do not overflow
+/// let extreme = [i32::MIN, i32::MAX];
+/// assert_eq!(softmax_sample(&rng, &extreme, ONE as u32, "extreme"), Some(1));
+///
+/// // Nothing to choose from
+/// assert_eq!(softmax_sample(&rng, &[], ONE as u32, "empty"), None);
+/// ```
+pub fn softmax_sample<H: Hash>(rng: &RandomAccessRNG, logits: &[i32], temperature: u32, key: H) -> Option<usize> {
+    let max = *logits.iter().max()?;
+
+    let weights: Vec<u64> = if temperature == 0 {
+        logits.iter().map(|&l| (l == max) as u64).collect()
+    } else {
+        logits.iter().map(|&l| softmax_weight(l as i64 - max as i64, temperature)).collect()
+    };
+
+    // The maximum logit always has a non-zero weight, so the total is never zero
+    let total: u64 = weights.iter().sum();
+    let mut target = ((rng.get(key).next_u64() as u128 * total as u128) >> 64) as u64;
+
+    weights.iter().position(|&w| {
+        if target < w {
+            true
+        } else {
+            target -= w;
+            false
+        }
+    })
+}
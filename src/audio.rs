@@ -0,0 +1,79 @@
+//! Choosing sound variations.
+//!
+//! Sound designers record several variations of common sounds, such as footsteps or impacts, and
+//! pick one each time the sound plays. Picking with a shared stream makes replays diverge as soon
+//! as one extra sound plays; keying the pick by the event instead, typically
+//! `(entity, sound, event_number)`, gives the same variation whenever the event is replayed.
+
+use std::hash::Hash;
+use crate::RandomAccessRNG;
+use crate::dist::Draws;
+
+/// A variation in `0..count` for `key`, independently of any stream position.
+///
+/// The variation is `dist::uniform(0..count).sample_at(rng, key)`.
+///
+/// # Panics
+///
+/// Panics if `count` is zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::audio::variation;
+///
+/// let sounds = RandomAccessRNG::new("replay_seed").get("sounds");
+///
+/// let step = variation(&sounds, ("player", "footstep", 17), 6);
+/// assert!(step < 6);
+///
+/// // The same step of a replay plays the same sound
+/// assert_eq!(variation(&sounds, ("player", "footstep", 17), 6), step);
+/// ```
+pub fn variation<H: Hash>(rng: &RandomAccessRNG, key: H, count: usize) -> usize {
+    assert!(count > 0, "cannot choose from zero variations");
+
+    Draws::new(&mut rng.get(key)).below(count as u64) as usize
+}
+
+/// A variation in `0..count` for `key` other than `previous`, so the same sound never plays twice
+/// in a row.
+///
+/// Each of the other `count - 1` variations is equally likely. With a single variation, or if
+/// `previous` is not in `0..count`, this is the same as [`variation`].
+/// Passing the variation chosen for the previous event makes a sequence of picks that never
+/// repeats immediately and is reproduced exactly by a replay.
+///
+/// # Panics
+///
+/// Panics if `count` is zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::audio::{variation, variation_avoiding};
+///
+/// let sounds = RandomAccessRNG::new("replay_seed").get("sounds");
+///
+/// let mut previous = variation(&sounds, ("player", "footstep", 0), 4);
+///
+/// for step in 1..100 {
+///     let next = variation_avoiding(&sounds, ("player", "footstep", step), 4, previous);
+///     assert_ne!(next, previous);
+///     previous = next;
+/// }
+/// ```
+pub fn variation_avoiding<H: Hash>(rng: &RandomAccessRNG, key: H, count: usize, previous: usize) -> usize {
+    assert!(count > 0, "cannot choose from zero variations");
+
+    if count == 1 || previous >= count {
+        return variation(rng, key, count);
+    }
+
+    // Choose among the others, then skip over the previous variation
+    let choice = Draws::new(&mut rng.get(key)).below(count as u64 - 1) as usize;
+
+    if choice >= previous { choice + 1 } else { choice }
+}
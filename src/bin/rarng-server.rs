@@ -0,0 +1,126 @@
+//! HTTP service exposing deterministic values for cross-service lookups.
+//!
+//! Services that need to agree on generated values (a matchmaker and a game server, or a web
+//! front end and a simulation backend) can query this server instead of embedding their own copy
+//! of the generation contract.
+//!
+//! ```text
+//! rarng-server [ADDRESS]          (default 127.0.0.1:8080)
+//!
+//! GET /v1/value?seed=world_seed&path=terrain/10/20&index=3
+//! {"seed":"world_seed","path":"terrain/10/20","index":3,"value":"1234567890"}
+//! ```
+//!
+//! The value is `RandomAccessRNG::new(seed).path(path).seek_u64(index)`, with the seed hashed as
+//! a string. `path` defaults to the root and `index` to zero. The value is sent as a decimal
+//! string, since JSON parsers that read numbers as doubles would round values above 2^53. A path
+//! that [`try_path`](random_access_rng::RandomAccessRNG::try_path) rejects is a 400 error.
+
+use random_access_rng::RandomAccessRNG;
+use tiny_http::{Header, Response, Server};
+
+/// Decode `%XX` escapes and `+` in a query string component
+fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+
+    while let Some(b) = iter.next() {
+        match b {
+            b'%' => {
+                let hex = [iter.next()?, iter.next()?];
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            },
+            b'+' => bytes.push(b' '),
+            b => bytes.push(b),
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+/// Escape a string for inclusion in a JSON document
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+/// Answer a `/v1/value` query, returning the status code and JSON body
+fn value(query: &str) -> (u16, String) {
+    let mut seed = None;
+    let mut path = String::new();
+    let mut index = 0u64;
+
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, raw) = pair.split_once('=').unwrap_or((pair, ""));
+
+        let Some(decoded) = percent_decode(raw) else {
+            return (400, format!("{{\"error\":{}}}", json_string("invalid percent encoding")));
+        };
+
+        match key {
+            "seed" => seed = Some(decoded),
+            "path" => path = decoded,
+            "index" => match decoded.parse() {
+                Ok(i) => index = i,
+                Err(_) => return (400, format!("{{\"error\":{}}}", json_string("index must be a u64"))),
+            },
+            _ => return (400, format!("{{\"error\":{}}}", json_string(&format!("unknown parameter {}", key)))),
+        }
+    }
+
+    let Some(seed) = seed else {
+        return (400, format!("{{\"error\":{}}}", json_string("missing seed")));
+    };
+
+    let value = match RandomAccessRNG::new(seed.as_str()).try_path(&path) {
+        Ok(mut rng) => rng.seek_u64(index),
+        Err(e) => return (400, format!("{{\"error\":{}}}", json_string(&e.to_string()))),
+    };
+
+    (200, format!(
+        "{{\"seed\":{},\"path\":{},\"index\":{},\"value\":\"{}\"}}",
+        json_string(&seed),
+        json_string(&path),
+        index,
+        value,
+    ))
+}
+
+fn main() {
+    let address = std::env::args().nth(1).unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let server = Server::http(&address).unwrap_or_else(|e| panic!("Failed to listen on {}: {}", address, e));
+
+    println!("Listening on http://{}", address);
+
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+
+    for request in server.incoming_requests() {
+        let url = request.url().to_string();
+        let (route, query) = url.split_once('?').unwrap_or((&url, ""));
+
+        let (status, body) = match route {
+            "/v1/value" => value(query),
+            _ => (404, format!("{{\"error\":{}}}", json_string("not found"))),
+        };
+
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(content_type.clone());
+
+        if let Err(e) = request.respond(response) {
+            eprintln!("Failed to respond to {}: {}", url, e);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! A block core for rand's buffered RNG infrastructure.
+//!
+//! [`RandomAccessBlockCore`] implements [`BlockRngCore`], generating 16 `u32`s at a time from
+//! four consecutive 128-bit values. Wrapped in [`BlockRng`](rand_core::block::BlockRng), every
+//! bit of each hash is used, so [`next_u32`](rand_core::RngCore::next_u32) and
+//! [`fill_bytes`](rand_core::RngCore::fill_bytes) need far fewer hashes than on
+//! [`RandomAccessRNG`] itself.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::block::RandomAccessBlockCore;
+//! use rand_core::RngCore;
+//! use rand_core::block::BlockRng;
+//!
+//! let mut plain = RandomAccessRNG::new("seed");
+//! let mut buffered = BlockRng::new(RandomAccessBlockCore::new(RandomAccessRNG::new("seed")));
+//!
+//! // Four 32-bit draws cover the 128-bit value at one position, low bits first
+//! let value = plain.next_u128();
+//! for i in 0..4 {
+//!     assert_eq!(buffered.next_u32(), (value >> (32 * i)) as u32);
+//! }
+//!
+//! // Seek the core and discard the buffer to continue from another position
+//! buffered.core.set_position(1000);
+//! buffered.reset();
+//! assert_eq!(buffered.next_u64(), plain.seek_u128(1000) as u64);
+//! ```
+
+use rand_core::SeedableRng;
+use rand_core::block::BlockRngCore;
+use crate::RandomAccessRNG;
+
+/// Number of stream positions consumed by one call to [`BlockRngCore::generate`]
+const BLOCK_POSITIONS: usize = 4;
+
+/// A [`BlockRngCore`] over the 128-bit values of a [`RandomAccessRNG`] stream.
+///
+/// Each block holds the values at the next four positions, split into `u32`s in little endian
+/// order. The stream can be moved with [`set_position`](RandomAccessBlockCore::set_position),
+/// after which a wrapping [`BlockRng`](rand_core::block::BlockRng) must be
+/// [`reset`](rand_core::block::BlockRng::reset) to discard what it has already buffered.
+#[derive(Clone)]
+pub struct RandomAccessBlockCore {
+    rng: RandomAccessRNG,
+}
+
+impl RandomAccessBlockCore {
+
+    /// A core generating blocks from `rng`, starting at its current position.
+    pub fn new(rng: RandomAccessRNG) -> Self {
+        Self { rng }
+    }
+
+    /// The position of the first value of the next block.
+    pub fn position(&self) -> u128 {
+        self.rng.position()
+    }
+
+    /// Make the next block start at `position`.
+    pub fn set_position(&mut self, position: u128) {
+        self.rng.set_position(position);
+    }
+
+    /// The underlying RNG, positioned after the last block generated.
+    pub fn into_inner(self) -> RandomAccessRNG {
+        self.rng
+    }
+}
+
+impl BlockRngCore for RandomAccessBlockCore {
+
+    type Item = u32;
+    type Results = [u32; 4 * BLOCK_POSITIONS];
+
+    fn generate(&mut self, results: &mut Self::Results) {
+        for chunk in results.chunks_mut(4) {
+            let value = self.rng.next_u128();
+
+            for (i, item) in chunk.iter_mut().enumerate() {
+                *item = (value >> (32 * i)) as u32;
+            }
+        }
+    }
+}
+
+impl SeedableRng for RandomAccessBlockCore {
+
+    type Seed = <RandomAccessRNG as SeedableRng>::Seed;
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(RandomAccessRNG::from_seed(seed))
+    }
+}
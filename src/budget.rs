@@ -0,0 +1,227 @@
+//! Draw budgets for catching runaway generation.
+//!
+//! A loop that draws more values than intended (a retry loop that never settles, a recursion that
+//! goes one level too deep) doesn't just waste time: every extra draw shifts the values seen by
+//! whatever uses the stream next, silently changing other systems. [`RandomAccessRNG::with_budget`]
+//! caps how many values a scope may draw and stops it with an error as soon as the cap is hit.
+//!
+//! Where a budget bounds the draws of a scope, [`RandomAccessRNG::assert_draws`] pins them
+//! exactly, so a change in how many values an operation consumes is caught where it happens
+//! rather than as a shift in everything generated after it.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use rand_core::RngCore;
+use crate::RandomAccessRNG;
+
+/// Error returned by [`RandomAccessRNG::with_budget`] when a scope draws too many values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BudgetExceeded {
+    /// The number of draws the scope was allowed
+    pub budget: u64,
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "draw budget of {} values exceeded", self.budget)
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+/// An RNG handed to a [`with_budget`](RandomAccessRNG::with_budget) scope, counting its draws.
+///
+/// Each call to [`next_u32`](RngCore::next_u32) or [`next_u64`](RngCore::next_u64) counts as one
+/// draw, and [`fill_bytes`](RngCore::fill_bytes) counts one draw per position it consumes, as
+/// [`draw_count`](RandomAccessRNG::draw_count) does. The bytes are those the wrapped RNG's own
+/// `fill_bytes` produces, so they follow its [`Scheme`](crate::Scheme).
+///
+/// ```rust
+/// use random_access_rng::{RandomAccessRNG, Scheme};
+/// use random_access_rng::budget::BudgetExceeded;
+/// use rand_core::RngCore;
+///
+/// let mut rng = RandomAccessRNG::with_scheme("seed", Scheme::V3);
+///
+/// // 40 bytes take three 16-byte positions
+/// let mut budgeted = [0u8; 40];
+/// let used = rng.clone().with_budget(3, |r| {
+///     r.fill_bytes(&mut budgeted);
+///     r.used()
+/// });
+/// assert_eq!(used, Ok(3));
+///
+/// let mut plain = [0u8; 40];
+/// rng.clone().fill_bytes(&mut plain);
+/// assert_eq!(budgeted, plain);
+///
+/// assert_eq!(rng.with_budget(2, |r| r.fill_bytes(&mut [0u8; 40])), Err(BudgetExceeded { budget: 2 }));
+/// ```
+pub struct Budgeted<'a> {
+    rng: &'a mut RandomAccessRNG,
+    budget: u64,
+    used: u64,
+}
+
+impl Budgeted<'_> {
+
+    /// Number of draws made so far in this scope.
+    pub fn used(&self) -> u64 {
+        self.used
+    }
+
+    /// Number of draws left before the budget is exceeded.
+    pub fn remaining(&self) -> u64 {
+        self.budget - self.used
+    }
+
+    /// Count one draw, unwinding out of the scope if the budget is exhausted
+    fn spend(&mut self) {
+        if self.used == self.budget {
+            // resume_unwind skips the panic hook, so nothing is printed
+            panic::resume_unwind(Box::new(BudgetExceeded { budget: self.budget }));
+        }
+
+        self.used += 1;
+    }
+}
+
+impl RngCore for Budgeted<'_> {
+
+    fn next_u32(&mut self) -> u32 {
+        self.spend();
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.spend();
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        // One position per chunk, so the budget stops the fill at the first position over it
+        for chunk in dst.chunks_mut(self.rng.bytes_per_position()) {
+            self.spend();
+            self.rng.fill_bytes(chunk);
+        }
+    }
+}
+
+impl RandomAccessRNG {
+
+    /// Run `f` with access to this RNG, allowing it at most `budget` draws.
+    ///
+    /// If `f` attempts draw number `budget + 1`, it is stopped immediately and
+    /// [`BudgetExceeded`] is returned. Because the RNG is deterministic, a scope that exceeds its
+    /// budget does so on every run, at the same draw. The RNG is left positioned after the last
+    /// draw that was allowed.
+    ///
+    /// The scope is stopped by unwinding, so this requires the default `panic = "unwind"` strategy.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::budget::BudgetExceeded;
+    /// use rand::Rng;
+    ///
+    /// let mut rng = RandomAccessRNG::new("dungeon_seed");
+    ///
+    /// // A well behaved generator stays within its budget
+    /// let rooms = rng.with_budget(100, |r| (0..10).map(|_| r.random_range(3..12)).collect::<Vec<u32>>());
+    /// assert_eq!(rooms.unwrap().len(), 10);
+    ///
+    /// // A runaway loop is caught instead of hanging or silently consuming the stream
+    /// let runaway = rng.with_budget(1000, |r| {
+    ///     loop {
+    ///         if r.random::<u64>() == 0 {
+    ///             break;
+    ///         }
+    ///     }
+    /// });
+    /// assert_eq!(runaway, Err(BudgetExceeded { budget: 1000 }));
+    /// ```
+    pub fn with_budget<T, F: FnOnce(&mut Budgeted<'_>) -> T>(&mut self, budget: u64, f: F) -> Result<T, BudgetExceeded> {
+        let mut scope = Budgeted {
+            rng: self,
+            budget,
+            used: 0,
+        };
+
+        match panic::catch_unwind(AssertUnwindSafe(|| f(&mut scope))) {
+            Ok(value) => Ok(value),
+            Err(payload) => match payload.downcast::<BudgetExceeded>() {
+                Ok(exceeded) => Err(*exceeded),
+                Err(payload) => panic::resume_unwind(payload),
+            },
+        }
+    }
+
+    /// The number of values drawn through this handle.
+    ///
+    /// Every position consumed by a draw counts once, including each position used by
+    /// [`fill_bytes`](RngCore::fill_bytes). Seeking, [`set_position`](RandomAccessRNG::set_position)
+    /// and [`advance`](RandomAccessRNG::advance) move the stream without drawing, so don't count.
+    /// New nodes start from zero, while a clone keeps the count of the handle it was cloned from.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// rng.next_u64();
+    /// rng.fill_bytes(&mut [0u8; 12]);
+    /// rng.set_position(1000);
+    ///
+    /// assert_eq!(rng.draw_count(), 3);
+    /// assert_eq!(rng.get("child").draw_count(), 0);
+    /// ```
+    pub fn draw_count(&self) -> u64 {
+        self.draws
+    }
+
+    /// Run `f` with this RNG, asserting that it draws exactly `expected` values.
+    ///
+    /// Pinning the draws of each operation in tests turns an accidental change, such as an extra
+    /// draw in a refactored loot roll, into a failure that names the operation, instead of a shift
+    /// in every value generated after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` draws a different number of values, as counted by
+    /// [`draw_count`](RandomAccessRNG::draw_count).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::dist::{self, Dist};
+    ///
+    /// let mut loot = RandomAccessRNG::new("world_seed").get("loot");
+    /// let table = dist::weighted([("gold", 9), ("gem", 1)]);
+    ///
+    /// // Each roll consumes exactly one value
+    /// let drop = loot.assert_draws(1, |rng| table.sample(rng));
+    /// ```
+    ///
+    /// ```should_panic
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// // Draws two values where one was expected
+    /// rng.assert_draws(1, |rng| rng.next_u64() ^ rng.next_u64());
+    /// ```
+    #[track_caller]
+    pub fn assert_draws<T, F: FnOnce(&mut Self) -> T>(&mut self, expected: u64, f: F) -> T {
+        let before = self.draws;
+        let value = f(self);
+        let drawn = self.draws.wrapping_sub(before);
+
+        assert!(drawn == expected, "expected {} draws but {} were made", expected, drawn);
+
+        value
+    }
+}
@@ -0,0 +1,111 @@
+//! Probability checks without the `rand` facade.
+//!
+//! `rand::Rng` provides `random_bool` and `random_ratio`, but pulling in `rand` just for a
+//! probability check is heavy, and its mapping from values to outcomes isn't part of this crate's
+//! reproducibility guarantee. The methods here each consume exactly one position with a documented
+//! mapping, and the `_at` variants check a given position without moving the stream, so a check can
+//! be tied to a coordinate or event index.
+
+use rand_core::RngCore;
+use crate::RandomAccessRNG;
+use crate::dist::Draws;
+
+/// `true` when the 64-bit value `v` falls below a fraction `p` of the 64-bit range
+fn below_probability(v: u64, p: f64) -> bool {
+    assert!((0.0..=1.0).contains(&p), "probability {} is not in [0, 1]", p);
+
+    // Scaling by a power of two is exact, and the cast truncates, so the threshold is the same on every platform
+    (v as u128) < (p * 18446744073709551616.0) as u128
+}
+
+impl RandomAccessRNG {
+
+    /// `true` with probability `p`, consuming one position.
+    ///
+    /// The result is `true` when the 64-bit value drawn is below `p * 2^64`, so `p = 0.0` is never
+    /// `true` and `p = 1.0` always is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in `[0, 1]`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// let crits = (0..10000).filter(|_| rng.gen_bool(0.25)).count();
+    /// assert!((2000..3000).contains(&crits));
+    ///
+    /// assert!(rng.gen_bool(1.0));
+    /// assert!(!rng.gen_bool(0.0));
+    /// ```
+    pub fn gen_bool(&mut self, p: f64) -> bool {
+        below_probability(self.next_u64(), p)
+    }
+
+    /// `true` with probability `numerator / denominator`, consuming one position.
+    ///
+    /// Exact for any ratio, and the same as sampling [`dist::bernoulli`](crate::dist::bernoulli).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denominator` is zero or smaller than `numerator`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::dist::{self, Dist};
+    ///
+    /// let mut a = RandomAccessRNG::new("seed");
+    /// let mut b = RandomAccessRNG::new("seed");
+    ///
+    /// for _ in 0..100 {
+    ///     assert_eq!(a.gen_ratio(1, 3), dist::bernoulli(1, 3).sample(&mut b));
+    /// }
+    /// ```
+    pub fn gen_ratio(&mut self, numerator: u64, denominator: u64) -> bool {
+        Draws::new(self).ratio(numerator, denominator)
+    }
+
+    /// The result [`gen_bool`](RandomAccessRNG::gen_bool) gives at position `index`, without moving
+    /// the stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in `[0, 1]`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let traps = RandomAccessRNG::new("world_seed").get("traps");
+    ///
+    /// // Whether tile 1234 is trapped, however the level was explored
+    /// let trapped = traps.bool_at(1234, 0.05);
+    ///
+    /// let mut stream = traps.clone();
+    /// stream.set_position(1234);
+    /// assert_eq!(stream.gen_bool(0.05), trapped);
+    /// ```
+    pub fn bool_at(&self, index: u64, p: f64) -> bool {
+        below_probability(self.peek_at(index), p)
+    }
+
+    /// The result [`gen_ratio`](RandomAccessRNG::gen_ratio) gives at position `index`, without
+    /// moving the stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denominator` is zero or smaller than `numerator`.
+    pub fn ratio_at(&self, index: u64, numerator: u64, denominator: u64) -> bool {
+        let mut at = self.clone();
+        at.set_position(index as u128);
+
+        at.gen_ratio(numerator, denominator)
+    }
+}
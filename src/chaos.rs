@@ -0,0 +1,244 @@
+//! Replayable fault injection for chaos testing.
+//!
+//! Chaos tests inject failures and delays into a system to check it copes with them, but when
+//! those faults come from a thread-local RNG a failing run is nearly impossible to reproduce.
+//! Here every fault decision is derived from the *site* making it (a key such as `"db/write"`)
+//! and how many times that site has been asked before, so the same seed injects exactly the
+//! same faults regardless of thread scheduling between sites.
+//!
+//! A [`Chaos`] instance records every decision it makes. Feeding that record into
+//! [`Chaos::replay`] checks that a later run makes the same decisions and reports the first one
+//! that differs, which usually points straight at the cause of a non-reproducible failure.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::chaos::Chaos;
+//! use std::time::Duration;
+//!
+//! fn run(chaos: &mut Chaos) -> usize {
+//!     let mut failures = 0;
+//!
+//!     for _ in 0..100 {
+//!         if chaos.should_fail("db/write", 0.1) {
+//!             failures += 1;
+//!         }
+//!
+//!         let _latency = chaos.delay("net/send", Duration::from_millis(50));
+//!     }
+//!
+//!     failures
+//! }
+//!
+//! let seed = RandomAccessRNG::new("chaos_run_17");
+//!
+//! let mut recording = Chaos::new(seed.clone());
+//! let failures = run(&mut recording);
+//!
+//! // Replaying the run injects the same faults and verifies every decision
+//! let mut replay = Chaos::replay(seed, recording.into_log());
+//! assert_eq!(run(&mut replay), failures);
+//! assert!(replay.verify().is_ok());
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::time::Duration;
+use crate::RandomAccessRNG;
+
+/// The outcome of a single fault decision.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// Result of [`Chaos::should_fail`]
+    Fail(bool),
+    /// Result of [`Chaos::delay`]
+    Delay(Duration),
+}
+
+/// A recorded fault decision.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decision {
+    /// Debug representation of the site key
+    pub site: String,
+    /// How many decisions the site had made before this one
+    pub index: u64,
+    /// What was decided
+    pub outcome: Outcome,
+}
+
+/// The first point at which a replayed run differed from its recording.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// Position of the decision in the run
+    pub position: usize,
+    /// The recorded decision, or [`None`] if the replay made more decisions than the recording
+    pub expected: Option<Decision>,
+    /// The replayed decision, or [`None`] if the replay made fewer decisions than the recording
+    pub actual: Option<Decision>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "chaos decision {} diverged: expected {:?}, got {:?}", self.position, self.expected, self.actual)
+    }
+}
+
+impl std::error::Error for Divergence {}
+
+/// Whether the `index`th decision at `site` fails, given a failure `probability` in `[0, 1]`.
+///
+/// This is the pure function behind [`Chaos::should_fail`], useful when the invocation index is
+/// already known (for example a request number).
+pub fn fails_at<H: Hash>(rng: &RandomAccessRNG, site: H, index: u64, probability: f64) -> bool {
+    let threshold = (probability.clamp(0.0, 1.0) * 18446744073709551616.0) as u128;
+
+    (rng.get(site).seek_u64(index) as u128) < threshold
+}
+
+/// The delay for the `index`th decision at `site`, uniform between zero and `max` inclusive.
+///
+/// This is the pure function behind [`Chaos::delay`]. Delays have nanosecond resolution and are
+/// capped at `u64::MAX - 1` nanoseconds.
+pub fn delay_at<H: Hash>(rng: &RandomAccessRNG, site: H, index: u64, max: Duration) -> Duration {
+    let span = max.as_nanos().min(u64::MAX as u128 - 1) + 1;
+    let nanos = (rng.get(site).seek_u64(index) as u128 * span) >> 64;
+
+    Duration::from_nanos(nanos as u64)
+}
+
+/// A source of fault decisions that counts invocations per site and records what it decides.
+pub struct Chaos {
+    rng: RandomAccessRNG,
+    counters: HashMap<u128, u64>,
+    log: Vec<Decision>,
+    expected: Option<Vec<Decision>>,
+    divergence: Option<Divergence>,
+}
+
+impl Chaos {
+
+    /// Create a recording instance rooted at `rng`.
+    pub fn new(rng: RandomAccessRNG) -> Self {
+        Self {
+            rng,
+            counters: HashMap::new(),
+            log: Vec::new(),
+            expected: None,
+            divergence: None,
+        }
+    }
+
+    /// Create an instance that checks its decisions against a previous recording.
+    ///
+    /// Decisions are still derived from `rng` as usual; call [`verify`](Chaos::verify) at the end
+    /// of the run to find out whether they matched.
+    pub fn replay(rng: RandomAccessRNG, recording: Vec<Decision>) -> Self {
+        Self {
+            expected: Some(recording),
+            ..Self::new(rng)
+        }
+    }
+
+    /// Claim the next invocation index of a site
+    fn next_index<H: Hash>(&mut self, site: &H) -> u64 {
+        let counter = self.counters.entry(self.rng.get(site).digest()).or_insert(0);
+        let index = *counter;
+
+        *counter += 1;
+
+        index
+    }
+
+    /// Log a decision, comparing it against the recording when replaying
+    fn record<H: fmt::Debug>(&mut self, site: &H, index: u64, outcome: Outcome) {
+        let decision = Decision {
+            site: format!("{:?}", site),
+            index,
+            outcome,
+        };
+
+        if let (Some(expected), None) = (&self.expected, &self.divergence) {
+            let position = self.log.len();
+            let recorded = expected.get(position);
+
+            if recorded != Some(&decision) {
+                self.divergence = Some(Divergence {
+                    position,
+                    expected: recorded.cloned(),
+                    actual: Some(decision.clone()),
+                });
+            }
+        }
+
+        self.log.push(decision);
+    }
+
+    /// Decide whether this invocation of `site` should fail, with the given `probability`.
+    pub fn should_fail<H: Hash + fmt::Debug>(&mut self, site: H, probability: f64) -> bool {
+        let index = self.next_index(&site);
+        let fail = fails_at(&self.rng, &site, index, probability);
+
+        self.record(&site, index, Outcome::Fail(fail));
+
+        fail
+    }
+
+    /// Decide how long this invocation of `site` should be delayed, up to `max`.
+    pub fn delay<H: Hash + fmt::Debug>(&mut self, site: H, max: Duration) -> Duration {
+        let index = self.next_index(&site);
+        let delay = delay_at(&self.rng, &site, index, max);
+
+        self.record(&site, index, Outcome::Delay(delay));
+
+        delay
+    }
+
+    /// Every decision made so far, in order.
+    pub fn log(&self) -> &[Decision] {
+        &self.log
+    }
+
+    /// Consume the instance, returning its recording.
+    pub fn into_log(self) -> Vec<Decision> {
+        self.log
+    }
+
+    /// Check a replayed run against its recording.
+    ///
+    /// Returns the first decision that differed, including the case where the replay made fewer
+    /// decisions than were recorded. Always succeeds for instances created with [`new`](Chaos::new).
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::chaos::Chaos;
+    ///
+    /// let seed = RandomAccessRNG::new("seed");
+    ///
+    /// let mut recording = Chaos::new(seed.clone());
+    /// recording.should_fail("cache/get", 0.5);
+    /// recording.should_fail("cache/put", 0.5);
+    ///
+    /// // The replay calls the sites in a different order
+    /// let mut replay = Chaos::replay(seed, recording.into_log());
+    /// replay.should_fail("cache/put", 0.5);
+    /// replay.should_fail("cache/get", 0.5);
+    ///
+    /// let divergence = replay.verify().unwrap_err();
+    /// assert_eq!(divergence.position, 0);
+    /// assert_eq!(divergence.expected.unwrap().site, "\"cache/get\"");
+    /// ```
+    pub fn verify(&self) -> Result<(), Divergence> {
+        if let Some(divergence) = &self.divergence {
+            return Err(divergence.clone());
+        }
+
+        match &self.expected {
+            Some(expected) if expected.len() > self.log.len() => Err(Divergence {
+                position: self.log.len(),
+                expected: Some(expected[self.log.len()].clone()),
+                actual: None,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
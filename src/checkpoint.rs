@@ -0,0 +1,231 @@
+//! Small tokens for saving and resuming in-flight generation.
+//!
+//! A node of the hierarchy is cheap to re-derive from its seed and path, so the only state worth
+//! saving is how far along its stream it has got. [`RandomAccessRNG::checkpoint`] captures that
+//! position along with the node's digest, and [`RandomAccessRNG::restore`] puts a freshly derived
+//! node back at the saved position, checking the digest so a checkpoint can't be applied to the
+//! wrong node by mistake.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::checkpoint::Checkpoint;
+//! use rand_core::RngCore;
+//!
+//! let world = RandomAccessRNG::new("world_seed");
+//!
+//! let mut rivers = world.path("region/4/rivers");
+//! rivers.next_u64();
+//! rivers.next_u64();
+//!
+//! let saved = rivers.checkpoint().with_label("after source placement").to_bytes();
+//! let expected = rivers.next_u64();
+//!
+//! // Later, in another session
+//! let checkpoint = Checkpoint::from_bytes(&saved).unwrap();
+//! let mut resumed = world.path("region/4/rivers");
+//! resumed.restore(&checkpoint).unwrap();
+//!
+//! assert_eq!(resumed.next_u64(), expected);
+//! assert_eq!(checkpoint.label(), Some("after source placement"));
+//!
+//! // Restoring onto a different node is an error
+//! assert!(world.path("region/5/rivers").restore(&checkpoint).is_err());
+//! ```
+//!
+//! The checkpoint also records the node's [`Scheme`], since nodes of different schemes can share
+//! a digest while producing different values at the same position.
+//!
+//! ```rust
+//! use random_access_rng::{RandomAccessRNG, Scheme};
+//!
+//! let v4 = RandomAccessRNG::with_scheme("seed", Scheme::V4);
+//! let mut v6 = RandomAccessRNG::with_scheme("seed", Scheme::V6);
+//! assert_eq!(v4.digest(), v6.digest());
+//!
+//! assert!(v6.restore(&v4.checkpoint()).is_err());
+//! ```
+//!
+//! # Encoding
+//!
+//! [`Checkpoint::to_bytes`] produces a version byte (currently `2`) and a scheme byte (`1` for
+//! [`Scheme::V1`], `2` for [`Scheme::V2`] and so on), followed by the digest and position as
+//! little endian `u128`s, then the label, if any, as UTF-8. Checkpoints encoded by this version
+//! will always decode in later versions.
+//!
+//! Version `1` had no scheme byte. Such checkpoints still decode, as checkpoints of
+//! [`Scheme::V1`] nodes:
+//!
+//! ```rust
+//! use random_access_rng::{RandomAccessRNG, Scheme};
+//! use random_access_rng::checkpoint::Checkpoint;
+//!
+//! let mut v1 = vec![1];
+//! v1.extend_from_slice(&RandomAccessRNG::new("seed").digest().to_le_bytes());
+//! v1.extend_from_slice(&1000u128.to_le_bytes());
+//!
+//! let checkpoint = Checkpoint::from_bytes(&v1).unwrap();
+//! assert_eq!(checkpoint.scheme(), Scheme::V1);
+//! assert_eq!(checkpoint.index(), 1000);
+//!
+//! let mut rng = RandomAccessRNG::new("seed");
+//! rng.restore(&checkpoint).unwrap();
+//! assert_eq!(rng.position(), 1000);
+//!
+//! // Re-encoding gives the current version
+//! assert_eq!(checkpoint.to_bytes()[..2], [2, 1]);
+//! ```
+
+use std::fmt;
+use crate::{RandomAccessRNG, Scheme};
+
+/// The current encoding version
+const VERSION: u8 = 2;
+
+/// Length of an encoded checkpoint without a label
+const HEADER_LEN: usize = 34;
+
+/// Length of the encoded digest and position
+const BODY_LEN: usize = 32;
+
+/// The scheme byte of the encoding
+fn scheme_byte(scheme: Scheme) -> u8 {
+    match scheme {
+        Scheme::V1 => 1,
+        Scheme::V2 => 2,
+        Scheme::V3 => 3,
+        Scheme::V4 => 4,
+        Scheme::V5 => 5,
+        Scheme::V6 => 6,
+    }
+}
+
+/// The scheme of a scheme byte, or `None` if it is unknown
+fn scheme_of_byte(byte: u8) -> Option<Scheme> {
+    Some(match byte {
+        1 => Scheme::V1,
+        2 => Scheme::V2,
+        3 => Scheme::V3,
+        4 => Scheme::V4,
+        5 => Scheme::V5,
+        6 => Scheme::V6,
+        _ => return None,
+    })
+}
+
+/// The saved position of an RNG in its stream, see the [module documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    digest: u128,
+    index: u128,
+    scheme: Scheme,
+    label: Option<String>,
+}
+
+impl Checkpoint {
+
+    /// Attach a label, for example describing which stage of generation was saved.
+    pub fn with_label<S: Into<String>>(mut self, label: S) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// The label attached with [`with_label`](Checkpoint::with_label), if any.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// The position in the stream that was saved.
+    pub fn index(&self) -> u128 {
+        self.index
+    }
+
+    /// The scheme of the RNG that was saved.
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+
+    /// Encode the checkpoint, see [Encoding](self#encoding).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.label.as_ref().map_or(0, String::len));
+
+        bytes.push(VERSION);
+        bytes.push(scheme_byte(self.scheme));
+        bytes.extend_from_slice(&self.digest.to_le_bytes());
+        bytes.extend_from_slice(&self.index.to_le_bytes());
+
+        if let Some(label) = &self.label {
+            bytes.extend_from_slice(label.as_bytes());
+        }
+
+        bytes
+    }
+
+    /// Decode a checkpoint produced by [`to_bytes`](Checkpoint::to_bytes).
+    ///
+    /// Also decodes version `1` checkpoints, as [`Scheme::V1`], see [Encoding](self#encoding).
+    ///
+    /// Returns [`None`] if `bytes` is too short, has an unknown version or scheme, or a label that
+    /// isn't valid UTF-8.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (scheme, body) = match bytes {
+            [1, body @ ..] => (Scheme::V1, body),
+            [VERSION, scheme, body @ ..] => (scheme_of_byte(*scheme)?, body),
+            _ => return None,
+        };
+
+        if body.len() < BODY_LEN {
+            return None;
+        }
+
+        let label = match &body[BODY_LEN..] {
+            [] => None,
+            label => Some(String::from_utf8(label.to_vec()).ok()?),
+        };
+
+        Some(Self {
+            digest: u128::from_le_bytes(body[..16].try_into().unwrap()),
+            index: u128::from_le_bytes(body[16..32].try_into().unwrap()),
+            scheme,
+            label,
+        })
+    }
+}
+
+/// Error returned by [`RandomAccessRNG::restore`] when a checkpoint was taken from a different node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CheckpointMismatch;
+
+impl fmt::Display for CheckpointMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "checkpoint was taken from a different RNG")
+    }
+}
+
+impl std::error::Error for CheckpointMismatch {}
+
+impl RandomAccessRNG {
+
+    /// Save the current position of this RNG, see [`checkpoint`](crate::checkpoint).
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            digest: self.digest(),
+            index: self.index,
+            scheme: self.scheme(),
+            label: None,
+        }
+    }
+
+    /// Move this RNG to the position saved in `checkpoint`.
+    ///
+    /// Fails, leaving the RNG unchanged, if the checkpoint was taken from a different node, or
+    /// from a node of another scheme.
+    pub fn restore(&mut self, checkpoint: &Checkpoint) -> Result<(), CheckpointMismatch> {
+        if checkpoint.digest != self.digest() || checkpoint.scheme != self.scheme() {
+            return Err(CheckpointMismatch);
+        }
+
+        self.index = checkpoint.index;
+
+        Ok(())
+    }
+}
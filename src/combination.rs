@@ -0,0 +1,164 @@
+//! Random access to the `k`-element subsets of `0..n`.
+//!
+//! Procedural systems often need an unordered selection: which 5 of 40 islands have ruins, which
+//! 3 of 12 perks a character starts with. Rather than generating or shuffling all candidates, the
+//! functions here map an index directly onto a combination (unranking), so a uniformly random
+//! subset costs a single draw and any subset can be revisited by its index.
+//!
+//! Combinations are numbered in colexicographic order and always returned sorted ascending.
+
+use rand_core::RngCore;
+use crate::RandomAccessRNG;
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+/// The number of `k`-element subsets of `0..n`, or [`None`] if it does not fit in a `u128`.
+///
+/// ```rust
+/// use random_access_rng::combination::combinations;
+///
+/// assert_eq!(combinations(5, 2), Some(10));
+/// assert_eq!(combinations(52, 5), Some(2598960));
+/// assert_eq!(combinations(3, 4), Some(0));
+/// assert_eq!(combinations(1000, 500), None);
+/// ```
+pub fn combinations(n: u64, k: u64) -> Option<u128> {
+    if k > n {
+        return Some(0);
+    }
+
+    let k = k.min(n - k) as u128;
+    let n = n as u128;
+    let mut result: u128 = 1;
+
+    for j in 0..k {
+        // result * (n - j) is always divisible by j + 1, dividing first keeps intermediates exact
+        let g = gcd(result, j + 1);
+        result = (result / g).checked_mul((n - j) / ((j + 1) / g))?;
+    }
+
+    Some(result)
+}
+
+/// The combination at position `index` among all `k`-element subsets of `0..n`.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::combination::{nth_combination, combinations};
+///
+/// assert_eq!(nth_combination(5, 3, 0), vec![0, 1, 2]);
+/// assert_eq!(nth_combination(5, 3, 1), vec![0, 1, 3]);
+/// assert_eq!(nth_combination(5, 3, 9), vec![2, 3, 4]);
+///
+/// // Every combination appears exactly once
+/// let mut all: Vec<Vec<u64>> = (0..combinations(6, 3).unwrap()).map(|i| nth_combination(6, 3, i)).collect();
+/// all.dedup();
+/// assert_eq!(all.len(), 20);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `index` is not less than [`combinations(n, k)`](combinations), or if that count does
+/// not fit in a `u128`.
+pub fn nth_combination(n: u64, k: u64, index: u128) -> Vec<u64> {
+    let total = combinations(n, k).expect("Number of combinations overflows u128");
+    assert!(index < total, "index {} out of range for {} combinations", index, total);
+
+    let mut remaining = index;
+    let mut upper = n;
+    let mut out = Vec::with_capacity(k as usize);
+
+    for i in (1..=k).rev() {
+        // Largest c below `upper` with C(c, i) <= remaining, C(i - 1, i) = 0 is always a candidate
+        let (mut low, mut high) = (i - 1, upper - 1);
+
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+
+            if combinations(mid, i).is_some_and(|c| c <= remaining) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+
+        remaining -= combinations(low, i).unwrap();
+        out.push(low);
+        upper = low;
+    }
+
+    out.reverse();
+    out
+}
+
+/// The position of a combination, the inverse of [`nth_combination`].
+///
+/// `elements` must be sorted ascending and contain no duplicates.
+///
+/// ```rust
+/// use random_access_rng::combination::{combination_index, nth_combination};
+///
+/// let hand = nth_combination(52, 5, 1_000_000);
+/// assert_eq!(combination_index(&hand), 1_000_000);
+/// ```
+pub fn combination_index(elements: &[u64]) -> u128 {
+    elements
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| combinations(c, i as u64 + 1).expect("Number of combinations overflows u128"))
+        .sum()
+}
+
+/// A uniformly random `k`-element subset of `0..n`, sorted ascending.
+///
+/// Draws from `rng`'s stream until an index below the number of combinations is found (usually
+/// once or twice), so the result can be reproduced by seeking back to the same position.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::combination::random_combination;
+///
+/// let mut islands = RandomAccessRNG::new("world_seed").get("ruins");
+///
+/// // 5 of the 40 islands have ruins
+/// let ruins = random_combination(&mut islands, 40, 5);
+/// assert_eq!(ruins.len(), 5);
+/// assert!(ruins.windows(2).all(|w| w[0] < w[1]));
+/// assert!(ruins.iter().all(|&i| i < 40));
+///
+/// // Choosing everything or nothing has only one outcome
+/// assert_eq!(random_combination(&mut islands, 3, 3), vec![0, 1, 2]);
+/// assert_eq!(random_combination(&mut islands, 3, 0), vec![]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `k > n` or if the number of combinations does not fit in a `u128`.
+pub fn random_combination(rng: &mut RandomAccessRNG, n: u64, k: u64) -> Vec<u64> {
+    let total = combinations(n, k).expect("Number of combinations overflows u128");
+    assert!(total > 0, "cannot choose {} elements from {}", k, n);
+
+    // Rejection sampling on the smallest power of two covering the range keeps the draw unbiased
+    let mask = u128::MAX.checked_shr((total - 1).leading_zeros()).unwrap_or(0);
+
+    loop {
+        let draw = if mask > u64::MAX as u128 {
+            ((rng.next_u64() as u128) << 64 | rng.next_u64() as u128) & mask
+        } else {
+            rng.next_u64() as u128 & mask
+        };
+
+        if draw < total {
+            return nth_combination(n, k, draw);
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! Helpers for migrating existing code from another RNG to [`RandomAccessRNG`].
+//!
+//! Switching the RNG behind an established pipeline changes every value it produces, and it is
+//! rarely obvious which of those changes matter. The tools in this module let you run a legacy
+//! RNG (typically `rand::rngs::StdRng`) and a [`RandomAccessRNG`] side by side and record both
+//! streams so the differences can be inspected before committing to the switch.
+//!
+//! # Migration guide
+//!
+//! 1. Make the code path you want to migrate generic over [`RngCore`] (or `rand::Rng`) if it
+//!    is not already.
+//! 2. Wrap the legacy RNG and the replacement in a [`ReplacementRng`]. It returns values from the
+//!    legacy RNG by default, so behaviour is unchanged, while logging what the replacement would
+//!    have produced for every draw.
+//! 3. Use [`compare`] to run the code path once on each stream and check whether the *outcomes*
+//!    differ, not just the raw values.
+//! 4. Once you are happy, call [`ReplacementRng::use_replacement`] (or drop the wrapper entirely)
+//!    to switch over.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::compat::compare;
+//! use rand::{Rng, SeedableRng};
+//! use rand::rngs::StdRng;
+//!
+//! // An existing pipeline that only cares about the sign of a draw
+//! fn coin_flips<R: Rng>(rng: &mut R) -> Vec<bool> {
+//!     (0..4).map(|_| rng.random::<bool>()).collect()
+//! }
+//!
+//! let comparison = compare(
+//!     StdRng::seed_from_u64(42),
+//!     RandomAccessRNG::new("world_seed"),
+//!     |rng| coin_flips(rng),
+//! );
+//!
+//! // Both runs consumed the same number of draws
+//! assert_eq!(comparison.legacy_log.len(), comparison.replacement_log.len());
+//!
+//! if !comparison.same_outcome() {
+//!     println!("legacy: {:?}, replacement: {:?}", comparison.legacy, comparison.replacement);
+//! }
+//! ```
+
+use rand_core::RngCore;
+use crate::RandomAccessRNG;
+
+/// A single draw recorded by a [`ReplacementRng`], holding the value produced by both streams.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Draw {
+    /// A call to [`next_u32`](RngCore::next_u32)
+    U32 { legacy: u32, replacement: u32 },
+    /// A call to [`next_u64`](RngCore::next_u64)
+    U64 { legacy: u64, replacement: u64 },
+    /// A call to [`fill_bytes`](RngCore::fill_bytes)
+    Bytes { legacy: Vec<u8>, replacement: Vec<u8> },
+}
+
+impl Draw {
+
+    /// Returns `true` if both streams produced the same value for this draw.
+    pub fn matches(&self) -> bool {
+        match self {
+            Draw::U32 { legacy, replacement } => legacy == replacement,
+            Draw::U64 { legacy, replacement } => legacy == replacement,
+            Draw::Bytes { legacy, replacement } => legacy == replacement,
+        }
+    }
+}
+
+/// Which of the two wrapped streams a [`ReplacementRng`] returns to its caller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Output {
+    /// Return values from the legacy RNG (the default)
+    Legacy,
+    /// Return values from the [`RandomAccessRNG`]
+    Replacement,
+}
+
+/// An [`RngCore`] adapter that drives a legacy RNG and a [`RandomAccessRNG`] in lockstep.
+///
+/// Every draw is taken from both RNGs and recorded in a log, and the value from the stream
+/// selected by [`Output`] is returned. This makes it possible to drop the adapter into an existing
+/// code path without changing its behaviour while collecting what the replacement would produce.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::compat::{ReplacementRng, Draw};
+/// use rand::SeedableRng;
+/// use rand::rngs::StdRng;
+/// use rand_core::RngCore;
+///
+/// let mut legacy = StdRng::seed_from_u64(7);
+/// let expected = legacy.next_u64();
+///
+/// let mut rng = ReplacementRng::new(StdRng::seed_from_u64(7), RandomAccessRNG::new("seed"));
+///
+/// // Values still come from the legacy RNG
+/// assert_eq!(rng.next_u64(), expected);
+///
+/// // But the replacement value was logged alongside it
+/// let replacement = RandomAccessRNG::new("seed").next_u64();
+/// assert_eq!(rng.log(), &[Draw::U64 { legacy: expected, replacement }]);
+/// ```
+pub struct ReplacementRng<L: RngCore> {
+    legacy: L,
+    replacement: RandomAccessRNG,
+    output: Output,
+    log: Vec<Draw>,
+}
+
+impl<L: RngCore> ReplacementRng<L> {
+
+    /// Wrap a legacy RNG and its replacement, returning legacy values.
+    pub fn new(legacy: L, replacement: RandomAccessRNG) -> Self {
+        Self {
+            legacy,
+            replacement,
+            output: Output::Legacy,
+            log: Vec::new(),
+        }
+    }
+
+    /// Select which stream is returned to the caller.
+    pub fn set_output(&mut self, output: Output) {
+        self.output = output;
+    }
+
+    /// Return values from the [`RandomAccessRNG`] from now on.
+    pub fn use_replacement(&mut self) {
+        self.set_output(Output::Replacement);
+    }
+
+    /// Return values from the legacy RNG from now on.
+    pub fn use_legacy(&mut self) {
+        self.set_output(Output::Legacy);
+    }
+
+    /// The stream currently returned to the caller.
+    pub fn output(&self) -> Output {
+        self.output
+    }
+
+    /// All draws recorded so far, in order.
+    pub fn log(&self) -> &[Draw] {
+        &self.log
+    }
+
+    /// Take the recorded draws, leaving the log empty.
+    pub fn take_log(&mut self) -> Vec<Draw> {
+        std::mem::take(&mut self.log)
+    }
+
+    /// Unwrap the adapter, returning both RNGs in their current state.
+    pub fn into_inner(self) -> (L, RandomAccessRNG) {
+        (self.legacy, self.replacement)
+    }
+
+    /// Pick the value to return from a legacy/replacement pair
+    fn select<T>(&self, legacy: T, replacement: T) -> T {
+        match self.output {
+            Output::Legacy => legacy,
+            Output::Replacement => replacement,
+        }
+    }
+}
+
+impl<L: RngCore> RngCore for ReplacementRng<L> {
+
+    fn next_u32(&mut self) -> u32 {
+        let legacy = self.legacy.next_u32();
+        let replacement = self.replacement.next_u32();
+
+        self.log.push(Draw::U32 { legacy, replacement });
+
+        self.select(legacy, replacement)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let legacy = self.legacy.next_u64();
+        let replacement = self.replacement.next_u64();
+
+        self.log.push(Draw::U64 { legacy, replacement });
+
+        self.select(legacy, replacement)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        let mut legacy = vec![0; dst.len()];
+        let mut replacement = vec![0; dst.len()];
+
+        self.legacy.fill_bytes(&mut legacy);
+        self.replacement.fill_bytes(&mut replacement);
+
+        dst.copy_from_slice(self.select(&legacy, &replacement));
+
+        self.log.push(Draw::Bytes { legacy, replacement });
+    }
+}
+
+/// The result of running the same code path on a legacy RNG and on a [`RandomAccessRNG`].
+#[derive(Clone, Debug)]
+pub struct Comparison<T> {
+    /// Outcome of the run driven by the legacy RNG
+    pub legacy: T,
+    /// Outcome of the run driven by the replacement RNG
+    pub replacement: T,
+    /// Draws made during the legacy run
+    pub legacy_log: Vec<Draw>,
+    /// Draws made during the replacement run
+    pub replacement_log: Vec<Draw>,
+}
+
+impl<T: PartialEq> Comparison<T> {
+
+    /// Returns `true` if switching RNGs did not change the outcome.
+    pub fn same_outcome(&self) -> bool {
+        self.legacy == self.replacement
+    }
+}
+
+impl<T> Comparison<T> {
+
+    /// Returns `true` if both runs consumed the same draws in the same order.
+    ///
+    /// A mismatch means the code path's control flow depends on the values drawn, which is
+    /// worth knowing before migrating since it affects how later draws line up.
+    pub fn same_draw_pattern(&self) -> bool {
+        self.legacy_log.len() == self.replacement_log.len() && self.legacy_log
+            .iter()
+            .zip(&self.replacement_log)
+            .all(|(a, b)| std::mem::discriminant(a) == std::mem::discriminant(b))
+    }
+}
+
+/// Run `f` once on the legacy stream and once on the replacement stream and collect both outcomes.
+///
+/// Each run gets a fresh [`ReplacementRng`] built from clones of the inputs, so both runs start
+/// from identical states. See the [module documentation](self) for an example.
+pub fn compare<L, T, F>(legacy: L, replacement: RandomAccessRNG, mut f: F) -> Comparison<T>
+where
+    L: RngCore + Clone,
+    F: FnMut(&mut ReplacementRng<L>) -> T,
+{
+    let mut legacy_run = ReplacementRng::new(legacy.clone(), replacement.clone());
+    let legacy_outcome = f(&mut legacy_run);
+
+    let mut replacement_run = ReplacementRng::new(legacy, replacement);
+    replacement_run.use_replacement();
+    let replacement_outcome = f(&mut replacement_run);
+
+    Comparison {
+        legacy: legacy_outcome,
+        replacement: replacement_outcome,
+        legacy_log: legacy_run.take_log(),
+        replacement_log: replacement_run.take_log(),
+    }
+}
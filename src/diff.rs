@@ -0,0 +1,98 @@
+//! Finding which parts of a world a generation change affects.
+//!
+//! Before shipping a tweak to generation parameters in a live game, it helps to know its blast
+//! radius: which dungeons, chunks or loot tables will come out differently for existing players.
+//! [`diff_namespace`] generates a set of paths under both the old and the new parameters and
+//! reports the ones whose output changed.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::diff::diff_namespace;
+//! use random_access_rng::dist::{self, Dist};
+//!
+//! struct LootParams { legendary_weight: u64 }
+//!
+//! fn chest(params: &LootParams, rng: &RandomAccessRNG) -> &'static str {
+//!     dist::weighted([("common", 90), ("rare", 9), ("legendary", params.legendary_weight)]).sample(&mut rng.clone())
+//! }
+//!
+//! let world = RandomAccessRNG::new("world_seed");
+//! let chests: Vec<String> = (0..1000).map(|i| format!("dungeon/{}/chest", i)).collect();
+//!
+//! // Doubling the legendary weight only changes a small fraction of existing chests
+//! let diff = diff_namespace(&world, &LootParams { legendary_weight: 1 }, &LootParams { legendary_weight: 2 }, &chests, chest);
+//!
+//! assert!(diff.blast_radius() < 0.1);
+//!
+//! for change in &diff.changed {
+//!     println!("{}: {} -> {}", change.path, change.old, change.new);
+//! }
+//! ```
+
+use crate::RandomAccessRNG;
+
+/// A path whose output differs between two sets of parameters.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Change<T> {
+    /// The path below the root
+    pub path: String,
+    /// The output under the old parameters
+    pub old: T,
+    /// The output under the new parameters
+    pub new: T,
+}
+
+/// The result of [`diff_namespace`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NamespaceDiff<T> {
+    /// Every path whose output changed, in the order they were given
+    pub changed: Vec<Change<T>>,
+    /// The number of paths whose output is the same
+    pub unchanged: usize,
+}
+
+impl<T> NamespaceDiff<T> {
+
+    /// The number of paths compared.
+    pub fn total(&self) -> usize {
+        self.changed.len() + self.unchanged
+    }
+
+    /// The fraction of paths whose output changed, or 0 if no paths were compared.
+    pub fn blast_radius(&self) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.changed.len() as f64 / total as f64,
+        }
+    }
+}
+
+/// Compare the output of `generate` under the `old` and `new` parameters at each of `paths` below
+/// `root`.
+///
+/// `generate` is called with the parameters and the node at the path, once per set of parameters,
+/// and must be deterministic. Both calls get the same node, so only the parameters differ.
+pub fn diff_namespace<P, T, S, I, F>(root: &RandomAccessRNG, old: &P, new: &P, paths: I, generate: F) -> NamespaceDiff<T>
+where
+    T: PartialEq,
+    S: AsRef<str>,
+    I: IntoIterator<Item = S>,
+    F: Fn(&P, &RandomAccessRNG) -> T,
+{
+    let mut diff = NamespaceDiff { changed: Vec::new(), unchanged: 0 };
+
+    for path in paths {
+        let path = path.as_ref();
+        let node = root.path(path);
+
+        let (before, after) = (generate(old, &node), generate(new, &node));
+
+        if before == after {
+            diff.unchanged += 1;
+        } else {
+            diff.changed.push(Change { path: path.to_string(), old: before, new: after });
+        }
+    }
+
+    diff
+}
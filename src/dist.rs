@@ -0,0 +1,668 @@
+//! Composable distributions with stable, documented semantics.
+//!
+//! Sampling code written inline (`if rng.random_range(0..100) < 30 { ... }`) is hard to reuse and
+//! hard to test, and its exact consumption of the stream depends on the version of `rand` in use.
+//! This module lets distributions be declared once, combined with [`map`](Dist::map),
+//! [`zip`](Dist::zip), [`and_then`](Dist::and_then) and [`weighted`](Dist::weighted), and
+//! evaluated against any RNG node.
+//! Distributions built only from bounded draws can also be enumerated exhaustively with
+//! [`enumerate_outcomes`].
+//!
+//! # Stability
+//!
+//! The mapping from stream values to samples is part of this crate's reproducibility guarantee:
+//!
+//! - Every bounded draw ([`uniform`], [`weighted`], [`bernoulli`]) consumes exactly one position
+//!   of the stream. A 64-bit value `v` is mapped to `0..n` as `(v * n) >> 64`.
+//! - [`bernoulli(a, b)`](bernoulli) is `true` when the draw mapped to `0..b` is below `a`.
+//! - [`constant`] consumes nothing.
+//! - [`zip`](Dist::zip) samples the left distribution and then the right, and
+//!   [`and_then`](Dist::and_then) samples the outer distribution before the one it returns.
+//! - [`a.weighted(x, b, y)`](Dist::weighted) makes one draw choosing between `a` and `b`, as
+//!   [`weighted`] over the weights `[x, y]`, then samples the chosen distribution.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::dist::{self, Dist};
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Rarity { Common, Rare, Legendary }
+//!
+//! let rarity = dist::weighted([(Rarity::Common, 90), (Rarity::Rare, 9), (Rarity::Legendary, 1)]);
+//! let level = dist::uniform(1..=20u32);
+//!
+//! // An item is a rarity and a level, with legendaries always at least level 10
+//! let item = rarity.zip(level).map(|(rarity, level)| {
+//!     let level = if rarity == Rarity::Legendary { level.max(10) } else { level };
+//!     (rarity, level)
+//! });
+//!
+//! let world = RandomAccessRNG::new("world_seed");
+//!
+//! // Evaluate against a path in the hierarchy
+//! let (rarity, level) = item.sample_path(&world, "dungeon/3/chest/7");
+//! assert!((1..=20).contains(&level));
+//!
+//! // Always the same result for the same path
+//! assert_eq!(item.sample_path(&world, "dungeon/3/chest/7"), (rarity, level));
+//! ```
+
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{Range, RangeInclusive};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use rand_core::RngCore;
+use crate::RandomAccessRNG;
+
+/// Maps a 64-bit value onto `0..n`
+fn scale(value: u64, n: u64) -> u64 {
+    ((value as u128 * n as u128) >> 64) as u64
+}
+
+/// The set of branches of a bounded draw
+enum Branches {
+    /// `0..n`, all equally likely
+    Uniform(u64),
+    /// Indices chosen in proportion to their weights
+    Weighted(Vec<u64>),
+}
+
+impl Branches {
+
+    /// The first possible branch
+    fn first(&self) -> u64 {
+        self.next(None).unwrap()
+    }
+
+    /// The next possible branch after `choice`
+    fn next(&self, choice: Option<u64>) -> Option<u64> {
+        let start = choice.map_or(0, |c| c + 1);
+
+        match self {
+            Branches::Uniform(n) => (start < *n).then_some(start),
+            Branches::Weighted(weights) => (start..weights.len() as u64).find(|&i| weights[i as usize] > 0),
+        }
+    }
+
+    /// The probability of taking `choice`
+    fn probability(&self, choice: u64) -> f64 {
+        match self {
+            Branches::Uniform(n) => 1.0 / *n as f64,
+            Branches::Weighted(weights) => weights[choice as usize] as f64 / weights.iter().sum::<u64>() as f64,
+        }
+    }
+}
+
+/// A sequence of bounded draws being enumerated depth first
+#[derive(Default)]
+struct Script {
+    decisions: Vec<(Branches, u64)>,
+    position: usize,
+}
+
+impl Script {
+
+    /// Replay the recorded choice at the current position, or take the first branch of a new draw
+    fn choose(&mut self, branches: Branches) -> u64 {
+        let choice = match self.decisions.get(self.position) {
+            Some(&(_, choice)) => choice,
+            None => {
+                let choice = branches.first();
+                self.decisions.push((branches, choice));
+                choice
+            }
+        };
+
+        self.position += 1;
+
+        choice
+    }
+
+    /// Move on to the next unexplored sequence of choices, returning `false` once all are done
+    fn advance(&mut self) -> bool {
+        self.decisions.truncate(self.position);
+        self.position = 0;
+
+        while let Some((branches, choice)) = self.decisions.last_mut() {
+            if let Some(next) = branches.next(Some(*choice)) {
+                *choice = next;
+                return true;
+            }
+
+            self.decisions.pop();
+        }
+
+        false
+    }
+
+    /// The probability of the current sequence of choices
+    fn probability(&self) -> f64 {
+        self.decisions[..self.position]
+            .iter()
+            .map(|(branches, choice)| branches.probability(*choice))
+            .product()
+    }
+}
+
+/// Marker unwound out of a generator that makes an unbounded draw while being enumerated
+struct UnboundedDraw;
+
+/// Where a [`Draws`] gets its values from
+enum Source<'a> {
+    Stream(&'a mut RandomAccessRNG),
+    Script(&'a mut Script),
+}
+
+/// The source of randomness handed to [`Dist::generate`].
+///
+/// Custom distributions should make all of their random decisions through these methods, which
+/// follow the mapping documented in the [module documentation](self).
+pub struct Draws<'a> {
+    source: Source<'a>,
+}
+
+impl<'a> Draws<'a> {
+
+    /// Draw from the stream of `rng`.
+    pub fn new(rng: &'a mut RandomAccessRNG) -> Self {
+        Self { source: Source::Stream(rng) }
+    }
+
+    /// A uniform value in `0..n`, consuming one position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "cannot draw below zero");
+
+        match &mut self.source {
+            Source::Stream(rng) => scale(rng.next_u64(), n),
+            Source::Script(script) => script.choose(Branches::Uniform(n)),
+        }
+    }
+
+    /// An index into `weights` chosen with probability proportional to its weight, consuming one position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the weights sum to zero or overflow a `u64`.
+    pub fn weighted_index(&mut self, weights: &[u64]) -> usize {
+        let total = weights
+            .iter()
+            .try_fold(0u64, |total, &w| total.checked_add(w))
+            .expect("Weights overflow u64");
+
+        if let Source::Script(script) = &mut self.source {
+            assert!(total > 0, "cannot draw below zero");
+
+            return script.choose(Branches::Weighted(weights.to_vec())) as usize;
+        }
+
+        let mut target = self.below(total);
+
+        weights
+            .iter()
+            .position(|&w| {
+                if target < w {
+                    true
+                } else {
+                    target -= w;
+                    false
+                }
+            })
+            .unwrap()
+    }
+
+    /// `true` with probability `numerator / denominator`, consuming one position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denominator` is zero or smaller than `numerator`.
+    pub fn ratio(&mut self, numerator: u64, denominator: u64) -> bool {
+        assert!(denominator > 0 && numerator <= denominator, "invalid probability {}/{}", numerator, denominator);
+
+        match &mut self.source {
+            Source::Stream(rng) => scale(rng.next_u64(), denominator) < numerator,
+            Source::Script(script) => script.choose(Branches::Weighted(vec![numerator, denominator - numerator])) == 0,
+        }
+    }
+
+    /// A full 64-bit value, consuming one position.
+    ///
+    /// Generators using this can't be enumerated by [`enumerate_outcomes`].
+    pub fn raw(&mut self) -> u64 {
+        match &mut self.source {
+            Source::Stream(rng) => rng.next_u64(),
+            Source::Script(_) => panic::resume_unwind(Box::new(UnboundedDraw)),
+        }
+    }
+}
+
+/// Error returned by [`enumerate_outcomes`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnumerateError {
+    /// The distribution made an unbounded draw with [`Draws::raw`]
+    Unbounded,
+    /// The distribution has more than this many sequences of draws
+    TooManyPaths(usize),
+}
+
+impl fmt::Display for EnumerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnumerateError::Unbounded => write!(f, "distribution makes unbounded draws"),
+            EnumerateError::TooManyPaths(limit) => write!(f, "distribution has more than {} sequences of draws", limit),
+        }
+    }
+}
+
+impl std::error::Error for EnumerateError {}
+
+/// Every possible outcome of a distribution, with its probability.
+///
+/// Rather than sampling, this walks every sequence of bounded draws the distribution can make
+/// (at most `limit` of them) and adds up the probability of each distinct outcome. Outcomes are
+/// listed in the order they are first found, which for most distributions follows the order of
+/// the branches. Useful for checking loot and encounter tables add up to what designers intended.
+///
+/// The probability of a draw mapped to `0..n` is taken to be exactly `1 / n`; the true mapping
+/// differs from this by less than `2^-64`.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::dist::{self, Dist, EnumerateError, enumerate_outcomes};
+///
+/// // 75% of chests hold gold, the rest hold one or two gems
+/// let chest = dist::bernoulli(3, 4).and_then(|gold| {
+///     let gems = if gold { 0..=0u32 } else { 1..=2u32 };
+///     dist::uniform(gems).map(move |gems| (gold, gems))
+/// });
+///
+/// let outcomes = enumerate_outcomes(&chest, 100).unwrap();
+/// assert_eq!(outcomes, vec![((true, 0), 0.75), ((false, 1), 0.125), ((false, 2), 0.125)]);
+///
+/// // Distributions with too many possibilities are rejected
+/// let huge = dist::uniform(0..u64::MAX);
+/// assert_eq!(enumerate_outcomes(&huge, 1000), Err(EnumerateError::TooManyPaths(1000)));
+/// ```
+pub fn enumerate_outcomes<D: Dist>(dist: &D, limit: usize) -> Result<Vec<(D::Output, f64)>, EnumerateError> where D::Output: PartialEq {
+    let mut script = Script::default();
+    let mut outcomes: Vec<(D::Output, f64)> = Vec::new();
+    let mut paths = 0;
+
+    loop {
+        if paths == limit {
+            return Err(EnumerateError::TooManyPaths(limit));
+        }
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            dist.generate(&mut Draws { source: Source::Script(&mut script) })
+        }));
+
+        let value = match result {
+            Ok(value) => value,
+            Err(payload) => match payload.downcast::<UnboundedDraw>() {
+                Ok(_) => return Err(EnumerateError::Unbounded),
+                Err(payload) => panic::resume_unwind(payload),
+            },
+        };
+
+        let probability = script.probability();
+
+        match outcomes.iter_mut().find(|(outcome, _)| *outcome == value) {
+            Some((_, total)) => *total += probability,
+            None => outcomes.push((value, probability)),
+        }
+
+        paths += 1;
+
+        if !script.advance() {
+            return Ok(outcomes);
+        }
+    }
+}
+
+/// A distribution that can be sampled with a [`RandomAccessRNG`].
+pub trait Dist {
+    /// The type of value produced
+    type Output;
+
+    /// Produce a value using `draws` as the only source of randomness.
+    fn generate(&self, draws: &mut Draws<'_>) -> Self::Output;
+
+    /// Sample a value from the stream of `rng`, advancing it.
+    fn sample(&self, rng: &mut RandomAccessRNG) -> Self::Output {
+        self.generate(&mut Draws::new(rng))
+    }
+
+    /// Sample a value from the child of `rng` with the given key, without modifying `rng`.
+    fn sample_at<H: Hash>(&self, rng: &RandomAccessRNG, key: H) -> Self::Output {
+        self.sample(&mut rng.get(key))
+    }
+
+    /// Sample a value from the descendant of `rng` at `path`, without modifying `rng`.
+    fn sample_path<P: AsRef<Path>>(&self, rng: &RandomAccessRNG, path: P) -> Self::Output {
+        self.sample(&mut rng.path(path))
+    }
+
+    /// Transform every sample with `f`.
+    fn map<U, F: Fn(Self::Output) -> U>(self, f: F) -> Map<Self, F> where Self: Sized {
+        Map { dist: self, f }
+    }
+
+    /// Sample this distribution and then `other`, returning both values.
+    fn zip<D: Dist>(self, other: D) -> Zip<Self, D> where Self: Sized {
+        Zip { left: self, right: other }
+    }
+
+    /// Sample this distribution, then sample the distribution `f` builds from the result.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::dist::{self, Dist};
+    ///
+    /// // Roll a number of dice, then their total
+    /// let dice = dist::uniform(1..=3u32).and_then(|count| {
+    ///     dist::uniform(1..=6u32).repeat(count as usize).map(|rolls| rolls.iter().sum::<u32>())
+    /// });
+    ///
+    /// let total = dice.sample(&mut RandomAccessRNG::new("seed"));
+    /// assert!((1..=18).contains(&total));
+    /// ```
+    fn and_then<D: Dist, F: Fn(Self::Output) -> D>(self, f: F) -> AndThen<Self, F> where Self: Sized {
+        AndThen { dist: self, f }
+    }
+
+    /// Sample this distribution `count` times, collecting the results.
+    fn repeat(self, count: usize) -> Repeat<Self> where Self: Sized {
+        Repeat { dist: self, count }
+    }
+
+    /// Sample this distribution with probability `weight / (weight + other_weight)`, and `other`
+    /// otherwise.
+    ///
+    /// The choice is one weighted draw, made before sampling the chosen distribution. Chaining
+    /// mixes in more distributions, each weighed against everything before it.
+    ///
+    /// # Panics
+    ///
+    /// Sampling panics if both weights are zero, or their sum overflows a `u64`.
+    ///
+    /// ```rust
+    /// use random_access_rng::dist::{self, Dist, enumerate_outcomes};
+    ///
+    /// // Usually a common item, sometimes a random rare one
+    /// let drop = dist::constant("copper").weighted(3, dist::weighted([("ruby", 1), ("pearl", 1)]), 1);
+    ///
+    /// let outcomes = enumerate_outcomes(&drop, 100).unwrap();
+    /// assert_eq!(outcomes, vec![("copper", 0.75), ("ruby", 0.125), ("pearl", 0.125)]);
+    ///
+    /// // Mixing in a third distribution against the first two
+    /// let rarer = drop.weighted(9, dist::constant("crown"), 1);
+    /// assert_eq!(enumerate_outcomes(&rarer, 100).unwrap().last(), Some(&("crown", 0.1)));
+    /// ```
+    fn weighted<D: Dist<Output = Self::Output>>(self, weight: u64, other: D, other_weight: u64) -> Mixture<Self, D> where Self: Sized {
+        Mixture { first: self, second: other, weights: [weight, other_weight] }
+    }
+}
+
+impl<D: Dist + ?Sized> Dist for &D {
+    type Output = D::Output;
+
+    fn generate(&self, draws: &mut Draws<'_>) -> Self::Output {
+        (**self).generate(draws)
+    }
+}
+
+impl<D: Dist + ?Sized> Dist for Box<D> {
+    type Output = D::Output;
+
+    fn generate(&self, draws: &mut Draws<'_>) -> Self::Output {
+        (**self).generate(draws)
+    }
+}
+
+/// Integer types that can be sampled by [`uniform`].
+pub trait UniformInt: Copy {
+    /// Widen to an `i128`
+    fn to_i128(self) -> i128;
+    /// Narrow from an `i128` known to be in range
+    fn from_i128(value: i128) -> Self;
+}
+
+macro_rules! impl_uniform_int {
+    ($($t:ty),*) => {
+        $(
+            impl UniformInt for $t {
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+
+                fn from_i128(value: i128) -> Self {
+                    value as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_uniform_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Uniform integers in a range, see [`uniform`].
+#[derive(Clone, Debug)]
+pub struct Uniform<T> {
+    low: T,
+    span: u128,
+}
+
+/// Uniformly distributed integers in `range`.
+///
+/// Accepts both `a..b` and `a..=b`.
+///
+/// # Panics
+///
+/// Panics if the range is empty.
+pub fn uniform<T: UniformInt, R: Into<UniformRange<T>>>(range: R) -> Uniform<T> {
+    let UniformRange { low, high } = range.into();
+    assert!(low.to_i128() <= high.to_i128(), "cannot sample from an empty range");
+
+    Uniform {
+        low,
+        span: (high.to_i128() - low.to_i128()) as u128 + 1,
+    }
+}
+
+/// An inclusive integer range accepted by [`uniform`].
+pub struct UniformRange<T> {
+    low: T,
+    high: T,
+}
+
+impl<T: UniformInt> From<Range<T>> for UniformRange<T> {
+    fn from(range: Range<T>) -> Self {
+        assert!(range.start.to_i128() < range.end.to_i128(), "cannot sample from an empty range");
+
+        Self {
+            low: range.start,
+            high: T::from_i128(range.end.to_i128() - 1),
+        }
+    }
+}
+
+impl<T: UniformInt> From<RangeInclusive<T>> for UniformRange<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let (low, high) = range.into_inner();
+
+        Self { low, high }
+    }
+}
+
+impl<T: UniformInt> Dist for Uniform<T> {
+    type Output = T;
+
+    fn generate(&self, draws: &mut Draws<'_>) -> T {
+        let offset = if self.span > u64::MAX as u128 {
+            // Only the full range of a 64-bit type gets here
+            draws.raw() as u128
+        } else {
+            draws.below(self.span as u64) as u128
+        };
+
+        T::from_i128(self.low.to_i128() + offset as i128)
+    }
+}
+
+/// A choice between values with integer weights, see [`weighted`].
+#[derive(Clone, Debug)]
+pub struct Weighted<T> {
+    values: Vec<T>,
+    weights: Vec<u64>,
+}
+
+/// Choose one of `choices`, each `(value, weight)` pair having probability `weight / total`.
+///
+/// # Panics
+///
+/// Panics if there are no choices or the weights sum to zero.
+pub fn weighted<T: Clone, I: IntoIterator<Item = (T, u64)>>(choices: I) -> Weighted<T> {
+    let (values, weights): (Vec<T>, Vec<u64>) = choices.into_iter().unzip();
+    assert!(weights.iter().any(|&w| w > 0), "at least one choice must have a non-zero weight");
+
+    Weighted { values, weights }
+}
+
+impl<T: Clone> Dist for Weighted<T> {
+    type Output = T;
+
+    fn generate(&self, draws: &mut Draws<'_>) -> T {
+        self.values[draws.weighted_index(&self.weights)].clone()
+    }
+}
+
+/// `true` with probability `numerator / denominator`, see [`bernoulli`].
+#[derive(Clone, Copy, Debug)]
+pub struct Bernoulli {
+    numerator: u64,
+    denominator: u64,
+}
+
+/// `true` with probability `numerator / denominator`.
+///
+/// # Panics
+///
+/// Panics if `denominator` is zero or smaller than `numerator`.
+pub fn bernoulli(numerator: u64, denominator: u64) -> Bernoulli {
+    assert!(denominator > 0 && numerator <= denominator, "invalid probability {}/{}", numerator, denominator);
+
+    Bernoulli { numerator, denominator }
+}
+
+impl Dist for Bernoulli {
+    type Output = bool;
+
+    fn generate(&self, draws: &mut Draws<'_>) -> bool {
+        draws.ratio(self.numerator, self.denominator)
+    }
+}
+
+/// Always the same value, see [`constant`].
+#[derive(Clone, Debug)]
+pub struct Constant<T>(T);
+
+/// A distribution that always produces `value` and consumes no randomness.
+pub fn constant<T: Clone>(value: T) -> Constant<T> {
+    Constant(value)
+}
+
+impl<T: Clone> Dist for Constant<T> {
+    type Output = T;
+
+    fn generate(&self, _: &mut Draws<'_>) -> T {
+        self.0.clone()
+    }
+}
+
+/// See [`Dist::map`].
+#[derive(Clone)]
+pub struct Map<D, F> {
+    dist: D,
+    f: F,
+}
+
+impl<D: Dist, U, F: Fn(D::Output) -> U> Dist for Map<D, F> {
+    type Output = U;
+
+    fn generate(&self, draws: &mut Draws<'_>) -> U {
+        (self.f)(self.dist.generate(draws))
+    }
+}
+
+/// See [`Dist::zip`].
+#[derive(Clone)]
+pub struct Zip<A, B> {
+    left: A,
+    right: B,
+}
+
+impl<A: Dist, B: Dist> Dist for Zip<A, B> {
+    type Output = (A::Output, B::Output);
+
+    fn generate(&self, draws: &mut Draws<'_>) -> Self::Output {
+        let left = self.left.generate(draws);
+        let right = self.right.generate(draws);
+
+        (left, right)
+    }
+}
+
+/// See [`Dist::and_then`].
+#[derive(Clone)]
+pub struct AndThen<D, F> {
+    dist: D,
+    f: F,
+}
+
+impl<D: Dist, E: Dist, F: Fn(D::Output) -> E> Dist for AndThen<D, F> {
+    type Output = E::Output;
+
+    fn generate(&self, draws: &mut Draws<'_>) -> E::Output {
+        (self.f)(self.dist.generate(draws)).generate(draws)
+    }
+}
+
+/// See [`Dist::repeat`].
+#[derive(Clone)]
+pub struct Repeat<D> {
+    dist: D,
+    count: usize,
+}
+
+impl<D: Dist> Dist for Repeat<D> {
+    type Output = Vec<D::Output>;
+
+    fn generate(&self, draws: &mut Draws<'_>) -> Self::Output {
+        (0..self.count).map(|_| self.dist.generate(draws)).collect()
+    }
+}
+
+/// See [`Dist::weighted`].
+#[derive(Clone)]
+pub struct Mixture<A, B> {
+    first: A,
+    second: B,
+    weights: [u64; 2],
+}
+
+impl<A: Dist, B: Dist<Output = A::Output>> Dist for Mixture<A, B> {
+    type Output = A::Output;
+
+    fn generate(&self, draws: &mut Draws<'_>) -> A::Output {
+        match draws.weighted_index(&self.weights) {
+            0 => self.first.generate(draws),
+            _ => self.second.generate(draws),
+        }
+    }
+}
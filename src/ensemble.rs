@@ -0,0 +1,175 @@
+//! Running a simulation over many seeds.
+//!
+//! A seed sweep runs the same simulation under several independent roots, called replicates, and
+//! looks at the spread of the results. [`Ensemble`] keeps the bookkeeping consistent: replicate
+//! `i` always has the same root, every subsystem is derived from it the same way in every
+//! replicate, and results are aggregated in replicate order.
+//!
+//! ```rust
+//! use random_access_rng::ensemble::Ensemble;
+//!
+//! // The fraction of 1000 coin flips that land heads, over 32 replicates
+//! let ensemble = Ensemble::new("experiment_7", 32);
+//!
+//! let summary = ensemble.summarize(|_, root| {
+//!     let coins = root.path("coins");
+//!     (0..1000).filter(|&i| coins.bool_at(i, 0.5)).count() as f64 / 1000.0
+//! });
+//!
+//! assert_eq!(summary.count, 32);
+//! assert!((summary.mean - 0.5).abs() < 0.02);
+//! assert!(summary.min <= summary.mean && summary.mean <= summary.max);
+//! ```
+
+use std::hash::Hash;
+use std::path::Path;
+use crate::RandomAccessRNG;
+
+/// A fixed number of replicate roots derived from one seed.
+///
+/// The root of replicate `i` is `RandomAccessRNG::new(seed).get(i as u64)`, so any single
+/// replicate can be rerun on its own, and adding replicates to a sweep leaves the existing ones
+/// unchanged.
+#[derive(Clone)]
+pub struct Ensemble {
+    base: RandomAccessRNG,
+    replicates: usize,
+}
+
+impl Ensemble {
+
+    /// An ensemble of `replicates` roots derived from `seed`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `replicates` is zero.
+    pub fn new<H: Hash>(seed: H, replicates: usize) -> Self {
+        Self::from_rng(RandomAccessRNG::new(seed), replicates)
+    }
+
+    /// An ensemble of `replicates` roots derived from `base`, for sweeps below an existing node or
+    /// using a non-default [`Scheme`](crate::Scheme).
+    ///
+    /// The root of replicate `i` is `base.get(i as u64)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `replicates` is zero.
+    pub fn from_rng(base: RandomAccessRNG, replicates: usize) -> Self {
+        assert!(replicates > 0, "an ensemble needs at least one replicate");
+
+        Self { base, replicates }
+    }
+
+    /// The number of replicates.
+    pub fn replicates(&self) -> usize {
+        self.replicates
+    }
+
+    /// The root of replicate `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not below [`replicates`](Ensemble::replicates).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::ensemble::Ensemble;
+    ///
+    /// let ensemble = Ensemble::new("sweep", 100);
+    ///
+    /// // Rerun replicate 42 on its own
+    /// assert_eq!(ensemble.replicate(42).digest(), RandomAccessRNG::new("sweep").get(42u64).digest());
+    /// ```
+    pub fn replicate(&self, index: usize) -> RandomAccessRNG {
+        assert!(index < self.replicates, "replicate {} out of range for an ensemble of {}", index, self.replicates);
+
+        self.base.get(index as u64)
+    }
+
+    /// The roots of every replicate, in order.
+    pub fn roots(&self) -> impl Iterator<Item = RandomAccessRNG> + '_ {
+        (0..self.replicates).map(|index| self.replicate(index))
+    }
+
+    /// The node at `path` below each replicate's root, in replicate order.
+    ///
+    /// ```rust
+    /// use random_access_rng::ensemble::Ensemble;
+    ///
+    /// let ensemble = Ensemble::new("sweep", 8);
+    /// let weather = ensemble.path("world/weather");
+    ///
+    /// assert_eq!(weather.len(), 8);
+    /// assert_eq!(weather[3].digest(), ensemble.replicate(3).path("world/weather").digest());
+    /// ```
+    pub fn path<P: AsRef<Path>>(&self, path: P) -> Vec<RandomAccessRNG> {
+        self.roots().map(|root| root.path(path.as_ref())).collect()
+    }
+
+    /// Run `f` on each replicate's index and root, collecting the results in replicate order.
+    pub fn map<T, F: FnMut(usize, &RandomAccessRNG) -> T>(&self, mut f: F) -> Vec<T> {
+        self.roots().enumerate().map(|(index, root)| f(index, &root)).collect()
+    }
+
+    /// Run `f` on each replicate's index and root and summarize the results.
+    pub fn summarize<F: FnMut(usize, &RandomAccessRNG) -> f64>(&self, f: F) -> Summary {
+        Summary::of(&self.map(f))
+    }
+}
+
+/// Statistics of one value across the replicates of an [`Ensemble`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    /// The number of replicates
+    pub count: usize,
+    /// The mean value
+    pub mean: f64,
+    /// The sample variance, with `count - 1` degrees of freedom, or 0 for a single replicate
+    pub variance: f64,
+    /// The smallest value
+    pub min: f64,
+    /// The largest value
+    pub max: f64,
+}
+
+impl Summary {
+
+    /// Summarize `values`, computing the variance with Welford's method.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` is empty.
+    pub fn of(values: &[f64]) -> Self {
+        assert!(!values.is_empty(), "cannot summarize zero values");
+
+        let (mut mean, mut squares) = (0.0, 0.0);
+        let (mut min, mut max) = (f64::INFINITY, f64::NEG_INFINITY);
+
+        for (i, &value) in values.iter().enumerate() {
+            let delta = value - mean;
+            mean += delta / (i + 1) as f64;
+            squares += delta * (value - mean);
+
+            min = min.min(value);
+            max = max.max(value);
+        }
+
+        let count = values.len();
+        let variance = if count > 1 { squares / (count - 1) as f64 } else { 0.0 };
+
+        Self { count, mean, variance, min, max }
+    }
+
+    /// The sample standard deviation.
+    pub fn std_dev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+
+    /// The standard error of the mean, `std_dev / sqrt(count)`.
+    pub fn standard_error(&self) -> f64 {
+        self.std_dev() / (self.count as f64).sqrt()
+    }
+}
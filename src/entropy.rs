@@ -0,0 +1,121 @@
+//! Random roots that can be replayed.
+//!
+//! Test harnesses and fuzz-style property checks want a fresh seed on every run, to cover more
+//! cases, but a failure is only useful if the run can be repeated. [`RandomAccessRNG::from_entropy`]
+//! picks a root seed from the operating system and keeps it, so it can be logged alongside any
+//! failure and passed back to [`EntropyRoot::from_root_seed_bytes`] or [`EntropyRoot::from_hex`]
+//! to replay the run exactly.
+//!
+//! Requires the `getrandom` feature.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::entropy::EntropyRoot;
+//! use rand_core::RngCore;
+//!
+//! let mut root = RandomAccessRNG::from_entropy();
+//! println!("property test seed: {}", root);
+//!
+//! let first = root.get("case").next_u64();
+//!
+//! // Replaying the logged seed gives the same run
+//! let replay = EntropyRoot::from_hex(&root.to_string()).unwrap();
+//! assert_eq!(replay.get("case").next_u64(), first);
+//! ```
+//!
+//! # Seeding
+//!
+//! The root seed is 16 bytes, and the root is
+//! [`RandomAccessRNG::from_digest`] of those bytes read as a little endian `u128`, so a logged
+//! seed replays identically on every platform.
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use crate::RandomAccessRNG;
+
+/// A root seeded with random bytes, which it keeps so the run can be replayed, see the
+/// [module documentation](self).
+///
+/// Dereferences to the root, so values and children can be drawn from it like any RNG.
+/// [`Display`](fmt::Display) writes the root seed as 32 lowercase hex digits.
+#[derive(Clone, Debug)]
+pub struct EntropyRoot {
+    seed: [u8; 16],
+    rng: RandomAccessRNG,
+}
+
+impl EntropyRoot {
+
+    /// The root for a known seed, such as one logged by an earlier run.
+    pub fn from_root_seed_bytes(seed: [u8; 16]) -> Self {
+        Self {
+            seed,
+            rng: RandomAccessRNG::from_digest(u128::from_le_bytes(seed)),
+        }
+    }
+
+    /// The root for a seed written by [`Display`](fmt::Display), or `None` if `hex` is not 32 hex
+    /// digits.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        if hex.len() != 32 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let mut seed = [0u8; 16];
+
+        for (i, byte) in seed.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+        }
+
+        Some(Self::from_root_seed_bytes(seed))
+    }
+
+    /// The bytes the root was seeded with.
+    pub fn root_seed_bytes(&self) -> [u8; 16] {
+        self.seed
+    }
+
+    /// The root, without its seed.
+    pub fn into_inner(self) -> RandomAccessRNG {
+        self.rng
+    }
+}
+
+impl fmt::Display for EntropyRoot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.seed.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+impl RandomAccessRNG {
+
+    /// A root seeded with 16 random bytes from the operating system, keeping them so the run can be
+    /// replayed, see the [`entropy`](crate::entropy) module.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system can't provide random bytes.
+    pub fn from_entropy() -> EntropyRoot {
+        let mut seed = [0u8; 16];
+
+        if let Err(e) = getrandom::fill(&mut seed) {
+            panic!("could not get a random seed from the operating system: {}", e);
+        }
+
+        EntropyRoot::from_root_seed_bytes(seed)
+    }
+}
+
+impl Deref for EntropyRoot {
+    type Target = RandomAccessRNG;
+
+    fn deref(&self) -> &RandomAccessRNG {
+        &self.rng
+    }
+}
+
+impl DerefMut for EntropyRoot {
+    fn deref_mut(&mut self) -> &mut RandomAccessRNG {
+        &mut self.rng
+    }
+}
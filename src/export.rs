@@ -0,0 +1,128 @@
+//! Seeding other RNG crates from the hierarchy.
+//!
+//! Projects often use other RNGs alongside this one, for speed in hot loops or because a library
+//! expects a particular generator. Seeding those from ad hoc constants splits the project's
+//! randomness into several unrelated roots. [`export_seed_for`](RandomAccessRNG::export_seed_for)
+//! keeps a single hierarchy: the downstream RNG is seeded from a node of this one, so it is
+//! reproducible from the same root seed as everything else.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::export::Fastrand;
+//! use rand::Rng;
+//! use rand::rngs::SmallRng;
+//!
+//! let world = RandomAccessRNG::new("world_seed");
+//!
+//! // Any `SeedableRng` is seeded directly
+//! let mut particles: SmallRng = world.export_seed_for::<SmallRng, _>("effects/particles");
+//! let spark: f32 = particles.random();
+//!
+//! // Crates outside the `rand` ecosystem get a seed to pass to their own constructor
+//! let seed: u64 = world.export_seed_for::<Fastrand, _>("audio/jitter");
+//! ```
+//!
+//! # Stability Boundaries
+//!
+//! The exported seed is part of this crate's reproducibility guarantee and will never change for a
+//! given node. What the downstream RNG produces from it is only as stable as that RNG:
+//!
+//! | Target                                         | Values stable across target versions?     |
+//! |------------------------------------------------|-------------------------------------------|
+//! | `rand_chacha`, `rand_pcg`, `rand_xoshiro` RNGs | Yes, each type is a documented algorithm  |
+//! | `rand::rngs::StdRng` and `SmallRng`            | No, the algorithm may change in a release |
+//! | `fastrand`, through [`Fastrand`]               | Not promised, pin the exact version       |
+//! | `wyrand`, through [`WyRand`]                   | The algorithm is fixed, but pin it anyway |
+//!
+//! Where generated content must survive upgrades, prefer a generator with a fixed algorithm, or
+//! draw from this crate directly.
+
+use rand_core::SeedableRng;
+use std::path::Path;
+use xxhash_rust::xxh3::xxh3_128;
+use crate::RandomAccessRNG;
+
+/// Domain separating exported seeds from the values of the node's own stream, "export__" in ASCII
+const EXPORT_DOMAIN: u64 = 0x6578706f72745f5f;
+
+/// A downstream RNG that can be seeded from a node, see [`export_seed_for`](RandomAccessRNG::export_seed_for).
+///
+/// Every [`SeedableRng`] is a target, producing the seeded RNG itself. Crates with their own
+/// seeding API are supported through marker types such as [`Fastrand`], producing the seed to
+/// pass to that API.
+pub trait ExportTarget {
+    /// What the export produces, either the seeded RNG or its seed
+    type Output;
+
+    /// Produce the output from the seed bytes of a node, which are as many as needed and never
+    /// change for the node.
+    fn from_seed_bytes(bytes: &ExportedBytes) -> Self::Output;
+}
+
+/// The seed bytes of a node, read by an [`ExportTarget`].
+pub struct ExportedBytes {
+    node: RandomAccessRNG,
+}
+
+impl ExportedBytes {
+
+    /// Fill `buf` with the first `buf.len()` seed bytes.
+    pub fn fill(&self, buf: &mut [u8]) {
+        self.node.read_bytes_at(0, buf)
+    }
+
+    /// The first 8 seed bytes as a little endian `u64`.
+    pub fn u64(&self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+}
+
+impl<R: SeedableRng> ExportTarget for R {
+    type Output = R;
+
+    fn from_seed_bytes(bytes: &ExportedBytes) -> R {
+        let mut seed = R::Seed::default();
+        bytes.fill(seed.as_mut());
+        R::from_seed(seed)
+    }
+}
+
+/// Exports the `u64` seed for `fastrand::Rng::with_seed`.
+pub struct Fastrand;
+
+impl ExportTarget for Fastrand {
+    type Output = u64;
+
+    fn from_seed_bytes(bytes: &ExportedBytes) -> u64 {
+        bytes.u64()
+    }
+}
+
+/// Exports the `u64` state for `wyrand`'s `WyRand::new`.
+pub struct WyRand;
+
+impl ExportTarget for WyRand {
+    type Output = u64;
+
+    fn from_seed_bytes(bytes: &ExportedBytes) -> u64 {
+        bytes.u64()
+    }
+}
+
+impl RandomAccessRNG {
+
+    /// Seed the downstream RNG `T` from the node at `path`, see the
+    /// [module documentation](crate::export).
+    ///
+    /// The seed bytes are the byte stream, as read by [`read_bytes_at`](RandomAccessRNG::read_bytes_at),
+    /// of a child of the node in its own domain, so they never repeat the values the node itself
+    /// draws. Every target reads the same bytes from the start: a `u64` seed is the first 8 bytes
+    /// of a 32-byte seed for the same path.
+    pub fn export_seed_for<T: ExportTarget, P: AsRef<Path>>(&self, path: P) -> T::Output {
+        let node = self.path(path).get_prehashed(xxh3_128(&EXPORT_DOMAIN.to_le_bytes()));
+
+        T::from_seed_bytes(&ExportedBytes { node })
+    }
+}
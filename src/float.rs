@@ -0,0 +1,99 @@
+//! Uniform floats with a fixed mapping.
+//!
+//! The conversion from integers to floats in `rand` has changed between versions, and with it
+//! every float a seed produces. The methods here use a mapping that is part of this crate's
+//! reproducibility guarantee and will never change:
+//!
+//! - [`next_f64`](RandomAccessRNG::next_f64) takes the top 53 bits of a 64-bit draw, `v >> 11`,
+//!   and multiplies them by 2^-53.
+//! - [`next_f32`](RandomAccessRNG::next_f32) takes the top 24 bits, `v >> 40`, and multiplies them
+//!   by 2^-24.
+//!
+//! Both give every multiple of the step in `[0, 1)` with equal probability, are computed exactly,
+//! and consume one position like any other draw. [`f64_at`](RandomAccessRNG::f64_at) and
+//! [`f32_at`](RandomAccessRNG::f32_at) give the float at a position without moving the stream.
+
+use rand_core::RngCore;
+use crate::RandomAccessRNG;
+
+/// 2^-53, the step between consecutive values of [`RandomAccessRNG::next_f64`]
+const F64_STEP: f64 = 1.0 / (1u64 << 53) as f64;
+
+/// 2^-24, the step between consecutive values of [`RandomAccessRNG::next_f32`]
+const F32_STEP: f32 = 1.0 / (1u32 << 24) as f32;
+
+/// The `f64` for a 64-bit draw
+pub(crate) fn to_f64(v: u64) -> f64 {
+    (v >> 11) as f64 * F64_STEP
+}
+
+/// The `f32` for a 64-bit draw
+fn to_f32(v: u64) -> f32 {
+    (v >> 40) as f32 * F32_STEP
+}
+
+impl RandomAccessRNG {
+
+    /// A uniform `f64` in `[0, 1)`, consuming one position.
+    ///
+    /// The value is `(next_u64() >> 11) * 2^-53`, see the [module documentation](crate::float).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut a = RandomAccessRNG::new("seed");
+    /// let mut b = RandomAccessRNG::new("seed");
+    ///
+    /// let x = a.next_f64();
+    /// assert!((0.0..1.0).contains(&x));
+    /// assert_eq!(x, (b.next_u64() >> 11) as f64 / (1u64 << 53) as f64);
+    /// ```
+    pub fn next_f64(&mut self) -> f64 {
+        to_f64(self.next_u64())
+    }
+
+    /// A uniform `f32` in `[0, 1)`, consuming one position.
+    ///
+    /// The value is `(next_u64() >> 40) * 2^-24`, see the [module documentation](crate::float).
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// let x = rng.next_f32();
+    /// assert!((0.0..1.0).contains(&x));
+    /// ```
+    pub fn next_f32(&mut self) -> f32 {
+        to_f32(self.next_u64())
+    }
+
+    /// The value [`next_f64`](RandomAccessRNG::next_f64) gives at position `index`, without moving
+    /// the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let terrain = RandomAccessRNG::new("world_seed").get("terrain");
+    ///
+    /// let height = terrain.f64_at(4096);
+    ///
+    /// let mut stream = terrain.clone();
+    /// stream.set_position(4096);
+    /// assert_eq!(stream.next_f64(), height);
+    /// ```
+    pub fn f64_at(&self, index: u64) -> f64 {
+        to_f64(self.peek_at(index))
+    }
+
+    /// The value [`next_f32`](RandomAccessRNG::next_f32) gives at position `index`, without moving
+    /// the stream.
+    pub fn f32_at(&self, index: u64) -> f32 {
+        to_f32(self.peek_at(index))
+    }
+}
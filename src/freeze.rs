@@ -0,0 +1,216 @@
+//! A guard against keys that hash differently between platforms.
+//!
+//! A key's [`Hash`] implementation decides the child it derives, and some implementations feed
+//! the hasher values whose size depends on the platform: `usize` and `isize`, pointers, and the
+//! length every slice, `Vec` and array writes before its elements. A hierarchy keyed with them on
+//! a 64-bit server derives different children in a 32-bit or WebAssembly client. A frozen RNG,
+//! from [`RandomAccessRNG::freeze`], checks every key it derives a child from and rejects those
+//! that hash anything platform dependent, for projects where reproducibility across platforms is
+//! critical.
+//!
+//! # Stable Keys
+//!
+//! A key is stable if its `Hash` implementation only writes bytes and fixed-width integers, as
+//! the implementations of `str`, `String`, `bool`, `char`, the fixed-width integer types, and
+//! tuples and references of stable keys do. Path components, prehashed keys and coordinates are
+//! always stable. Rejected keys can usually be replaced with a stable one by converting `usize`
+//! values to `u64`, and slices to a string or a tuple.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//!
+//! let world = RandomAccessRNG::new("world_seed").freeze();
+//!
+//! let chunk = world.get("chunks").get((12i64, -3i64));
+//!
+//! // A usize is 4 bytes on some platforms and 8 on others
+//! let index: usize = 7;
+//! assert!(chunk.try_get(index).is_err());
+//! assert_eq!(chunk.get(index as u64).digest(), RandomAccessRNG::new("world_seed").get("chunks").get((12i64, -3i64)).get(7u64).digest());
+//! ```
+
+use std::any::type_name;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+use crate::{PathError, RandomAccessRNG};
+
+/// The error returned for a key that hashes a platform-dependent value, see
+/// [`FrozenRNG::try_get`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnstableKey {
+    /// The name of the key's type, as given by [`std::any::type_name`]
+    pub type_name: &'static str,
+}
+
+impl fmt::Display for UnstableKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "key of type `{}` hashes a platform-dependent usize or isize, which a frozen RNG does not allow", self.type_name)
+    }
+}
+
+impl Error for UnstableKey {}
+
+/// A hasher recording whether a key writes any platform-dependent value
+#[derive(Default)]
+struct StabilityProbe {
+    unstable: bool,
+}
+
+impl Hasher for StabilityProbe {
+    fn finish(&self) -> u64 {
+        0
+    }
+
+    fn write(&mut self, _bytes: &[u8]) {}
+
+    fn write_usize(&mut self, _i: usize) {
+        self.unstable = true;
+    }
+
+    fn write_isize(&mut self, _i: isize) {
+        self.unstable = true;
+    }
+}
+
+/// Whether `key` hashes only bytes and fixed-width integers
+fn check<H: Hash>(key: &H) -> Result<(), UnstableKey> {
+    let mut probe = StabilityProbe::default();
+    key.hash(&mut probe);
+
+    match probe.unstable {
+        false => Ok(()),
+        true => Err(UnstableKey { type_name: type_name::<H>() }),
+    }
+}
+
+/// Panic in debug builds if `key` is not stable
+fn debug_check<H: Hash>(key: &H) {
+    if cfg!(debug_assertions) && let Err(e) = check(key) {
+        panic!("{}", e);
+    }
+}
+
+/// An RNG whose children may only be derived from stable keys, see the
+/// [module documentation](self).
+///
+/// Dereferences to the RNG, so values can be drawn from it as usual. Children are derived
+/// exactly as by [`RandomAccessRNG`] and are frozen too, so freezing never changes the values
+/// produced.
+#[derive(Clone, Debug)]
+pub struct FrozenRNG {
+    rng: RandomAccessRNG,
+}
+
+impl FrozenRNG {
+
+    /// The child at `key`, see [`RandomAccessRNG::get`].
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `key` is not stable. Release builds skip the check, use
+    /// [`try_get`](FrozenRNG::try_get) to check in every build.
+    ///
+    /// ```rust,should_panic
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let items = RandomAccessRNG::new("world_seed").freeze().get("items");
+    ///
+    /// // Slices hash their length as a usize
+    /// items.get(vec![1u8, 2, 3]);
+    /// ```
+    pub fn get<H: Hash>(&self, key: H) -> Self {
+        debug_check(&key);
+
+        Self { rng: self.rng.get(key) }
+    }
+
+    /// The child at `key`, see [`RandomAccessRNG::get`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnstableKey`] if `key` hashes a `usize` or `isize`, directly or as the length of a
+    /// slice.
+    pub fn try_get<H: Hash>(&self, key: H) -> Result<Self, UnstableKey> {
+        check(&key)?;
+
+        Ok(Self { rng: self.rng.get(key) })
+    }
+
+    /// The descendant at `keys`, see [`RandomAccessRNG::descendant`].
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if any key is not stable.
+    pub fn descendant<K: Hash, I: IntoIterator<Item = K>>(&self, keys: I) -> Self {
+        Self { rng: self.rng.descendant(keys.into_iter().inspect(debug_check)) }
+    }
+
+    /// The descendant at `keys`, see [`RandomAccessRNG::descendant`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnstableKey`] for the first key that is not stable.
+    pub fn try_descendant<K: Hash, I: IntoIterator<Item = K>>(&self, keys: I) -> Result<Self, UnstableKey> {
+        let keys = keys.into_iter()
+            .map(|key| check(&key).map(|_| key))
+            .collect::<Result<Vec<K>, _>>()?;
+
+        Ok(Self { rng: self.rng.descendant(keys) })
+    }
+
+    /// The descendant at `path`, see [`RandomAccessRNG::path`]. Path components are always stable.
+    pub fn path<P: AsRef<Path>>(&self, path: P) -> Self {
+        Self { rng: self.rng.path(path) }
+    }
+
+    /// The descendant at `path`, see [`RandomAccessRNG::try_path`].
+    pub fn try_path<P: AsRef<Path>>(&self, path: P) -> Result<Self, PathError> {
+        Ok(Self { rng: self.rng.try_path(path)? })
+    }
+
+    /// The child at a prehashed key, see [`RandomAccessRNG::get_prehashed`].
+    pub fn get_prehashed(&self, hash: u128) -> Self {
+        Self { rng: self.rng.get_prehashed(hash) }
+    }
+
+    /// The child at a 2D coordinate, see [`RandomAccessRNG::get2`].
+    pub fn get2(&self, x: i64, y: i64) -> Self {
+        Self { rng: self.rng.get2(x, y) }
+    }
+
+    /// The child at a 3D coordinate, see [`RandomAccessRNG::get3`].
+    pub fn get3(&self, x: i64, y: i64, z: i64) -> Self {
+        Self { rng: self.rng.get3(x, y, z) }
+    }
+
+    /// The RNG, no longer guarded.
+    pub fn into_inner(self) -> RandomAccessRNG {
+        self.rng
+    }
+}
+
+impl RandomAccessRNG {
+
+    /// Guard this RNG and everything derived from it against keys that hash differently between
+    /// platforms, see the [`freeze`](crate::freeze) module.
+    pub fn freeze(self) -> FrozenRNG {
+        FrozenRNG { rng: self }
+    }
+}
+
+impl Deref for FrozenRNG {
+    type Target = RandomAccessRNG;
+
+    fn deref(&self) -> &RandomAccessRNG {
+        &self.rng
+    }
+}
+
+impl DerefMut for FrozenRNG {
+    fn deref_mut(&mut self) -> &mut RandomAccessRNG {
+        &mut self.rng
+    }
+}
@@ -0,0 +1,349 @@
+//! Golden value corpora for catching output changes.
+//!
+//! This crate promises that the values produced from a seed never change. A [`Corpus`] records the
+//! values at many `(seed, path, index)` points to a small text file, and
+//! [`verify`](Corpus::verify) replays them later, listing every value that has changed. The crate
+//! checks its own output against the corpus in `golden/corpus.txt`, and applications can do the
+//! same for the parts of the hierarchy they depend on.
+//!
+//! ```rust
+//! use random_access_rng::golden::Corpus;
+//!
+//! let corpus = Corpus::parse(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/golden/corpus.txt"))).unwrap();
+//!
+//! assert_eq!(corpus.len(), 2000);
+//! assert_eq!(corpus.verify(), Ok(()));
+//! ```
+//!
+//! # Format
+//!
+//! The first line is `# random_access_rng golden corpus v1`. Every following line holds one entry
+//! as `index`, `value`, `seed` and `path` separated by tabs, with the index and value in decimal.
+//! The value is [`seek_u64`](RandomAccessRNG::seek_u64) at the index of
+//! `RandomAccessRNG::new(seed).path(path)`.
+//!
+//! # Key Hashes
+//!
+//! The corpus fixes the values below a string seed, but every other key type relies on its
+//! [`Hash`](std::hash::Hash) implementation too. With the `stability` feature, `key_hashes`
+//! hashes a fixed battery of standard library keys (integers at their limits, strings, tuples,
+//! arrays, slices, `Vec` and `Option`) and of this crate's key types ([`Key`](crate::key::Key),
+//! [`Segment`](crate::key::Segment), [`DurationKey`](crate::key::DurationKey) and
+//! [`TimeKey`](crate::key::TimeKey)), and `verify_key_hashes` checks them against those recorded
+//! in `golden/keys.txt`. After a header, each line of the file holds a key's name and its
+//! [`key_hash`](crate::key::key_hash) in hex, separated by a tab. This extends
+//! [`verify_std_hash_stability`](crate::key::verify_std_hash_stability), which checks
+//! one canonical value per type and is always available.
+
+use std::fmt;
+use crate::RandomAccessRNG;
+#[cfg(feature = "stability")]
+use crate::key::{KeyChanged, StdHashChanged};
+
+/// The first line of an encoded corpus
+const HEADER: &str = "# random_access_rng golden corpus v1";
+
+/// A recorded value.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// The seed passed to [`RandomAccessRNG::new`]
+    pub seed: String,
+    /// The path of the node below the seed
+    pub path: String,
+    /// The position in the node's stream
+    pub index: u64,
+    /// The value at that position
+    pub value: u64,
+}
+
+impl Entry {
+
+    /// The value at this entry's position today
+    fn current(&self) -> u64 {
+        RandomAccessRNG::new(self.seed.as_str()).path(&self.path).seek_u64(self.index)
+    }
+}
+
+/// A recorded value that has changed, returned by [`Corpus::verify`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Mismatch {
+    /// The entry as recorded
+    pub entry: Entry,
+    /// The value produced today
+    pub actual: u64,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seed {:?} path {:?} index {}: recorded {}, got {}", self.entry.seed, self.entry.path, self.entry.index, self.entry.value, self.actual)
+    }
+}
+
+/// A set of recorded values, see the [module documentation](self).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Corpus {
+    entries: Vec<Entry>,
+}
+
+impl Corpus {
+
+    /// An empty corpus.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A corpus of `count` entries spread over many seeds, path depths and stream positions.
+    ///
+    /// The points are chosen by simple arithmetic rather than by this crate, so they stay the same
+    /// even if the values don't. This is how `golden/corpus.txt` was generated.
+    pub fn generate(count: usize) -> Self {
+        let mut corpus = Self::new();
+
+        for i in 0..count as u64 {
+            let seed = format!("seed{}", i % 97);
+            let path = (0..i % 6)
+                .map(|depth| format!("n{}", (i * 31 + depth * 17) % 13))
+                .collect::<Vec<_>>()
+                .join("/");
+            let index = match i % 4 {
+                0 => i % 16,
+                1 => i.wrapping_mul(0x9e3779b97f4a7c15) >> (i % 64),
+                2 => u64::MAX - i % 8,
+                _ => i * 1000,
+            };
+
+            corpus.record(&seed, &path, index);
+        }
+
+        corpus
+    }
+
+    /// Record the current value at `index` of `RandomAccessRNG::new(seed).path(path)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `seed` or `path` contains a tab or newline, or `path` is not a valid path for
+    /// [`RandomAccessRNG::path`].
+    pub fn record(&mut self, seed: &str, path: &str, index: u64) {
+        assert!(!(seed.contains(['\t', '\n']) || path.contains(['\t', '\n'])), "seeds and paths cannot contain tabs or newlines");
+
+        let mut entry = Entry {
+            seed: seed.to_string(),
+            path: path.to_string(),
+            index,
+            value: 0,
+        };
+
+        entry.value = entry.current();
+
+        self.entries.push(entry);
+    }
+
+    /// The recorded entries.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// The number of recorded entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the corpus has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Check every recorded value against the value produced today, listing those that changed.
+    pub fn verify(&self) -> Result<(), Vec<Mismatch>> {
+        let mismatches: Vec<Mismatch> = self.entries
+            .iter()
+            .filter_map(|entry| {
+                let actual = entry.current();
+
+                (actual != entry.value).then(|| Mismatch {
+                    entry: entry.clone(),
+                    actual,
+                })
+            })
+            .collect();
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+
+    /// Decode a corpus, see [Format](self#format).
+    ///
+    /// Returns [`None`] if the header is missing or any line is malformed, including a path that
+    /// [`RandomAccessRNG::try_path`] rejects.
+    ///
+    /// ```rust
+    /// use random_access_rng::golden::Corpus;
+    ///
+    /// let header = "# random_access_rng golden corpus v1";
+    ///
+    /// assert!(Corpus::parse(&format!("{}\n0\t0\tseed\ta/b", header)).is_some());
+    /// assert!(Corpus::parse(&format!("{}\n0\t0\tseed\ta/../b", header)).is_none());
+    /// ```
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+
+        if lines.next()? != HEADER {
+            return None;
+        }
+
+        let entries = lines
+            .map(|line| {
+                let mut fields = line.splitn(4, '\t');
+
+                let entry = Entry {
+                    index: fields.next()?.parse().ok()?,
+                    value: fields.next()?.parse().ok()?,
+                    seed: fields.next()?.to_string(),
+                    path: fields.next()?.to_string(),
+                };
+
+                // Checked here so that verify never meets a path it would panic on
+                RandomAccessRNG::new(entry.seed.as_str()).try_path(&entry.path).ok()?;
+
+                Some(entry)
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self { entries })
+    }
+}
+
+impl fmt::Display for Corpus {
+
+    /// Encode the corpus, see [Format](self#format).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+
+        for entry in &self.entries {
+            writeln!(f, "{}\t{}\t{}\t{}", entry.index, entry.value, entry.seed, entry.path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The recorded key hashes, see [Key Hashes](self#key-hashes)
+#[cfg(feature = "stability")]
+const KEY_HASHES: &str = include_str!("../golden/keys.txt");
+
+/// Name each key of the battery and hash it
+#[cfg(feature = "stability")]
+macro_rules! key_battery {
+    ($($name:literal => $value:expr),* $(,)?) => {
+        vec![$(($name, crate::key::key_hash(&$value))),*]
+    };
+}
+
+/// The hashes of the key battery today, as `(name, hash)` pairs, see
+/// [Key Hashes](self#key-hashes).
+///
+/// Requires the `stability` feature.
+#[cfg(feature = "stability")]
+pub fn key_hashes() -> Vec<(&'static str, u128)> {
+    use crate::key::{DurationKey, Key, Segment, TimeKey};
+    use std::time::Duration;
+
+    key_battery! {
+        "u8 0" => 0u8,
+        "u8 max" => u8::MAX,
+        "u16 max" => u16::MAX,
+        "u32 0" => 0u32,
+        "u32 max" => u32::MAX,
+        "u64 0" => 0u64,
+        "u64 max" => u64::MAX,
+        "u128 max" => u128::MAX,
+        "i8 min" => i8::MIN,
+        "i16 min" => i16::MIN,
+        "i32 -1" => -1i32,
+        "i32 min" => i32::MIN,
+        "i64 -1" => -1i64,
+        "i64 min" => i64::MIN,
+        "i128 min" => i128::MIN,
+        "usize 0" => 0usize,
+        "usize 2^32" => 1usize << 32,
+        "isize -1" => -1isize,
+        "bool false" => false,
+        "char a" => 'a',
+        "char emoji" => '🦀',
+        "str empty" => "",
+        "str ascii" => "world_seed",
+        "str unicode" => "wörld/シード",
+        "String" => String::from("world_seed"),
+        "bytes" => b"\x00\xffbytes"[..],
+        "unit" => (),
+        "pair" => (1u64, -1i64),
+        "nested tuple" => ((1u8, "a"), [2u16, 3], Some(4u32)),
+        "array empty" => [0u8; 0],
+        "array u8" => [1u8, 2, 3],
+        "Vec str" => vec!["a", "bc", ""],
+        "Vec empty" => Vec::<u64>::new(),
+        "Some str" => Some("key"),
+        "None u64" => None::<u64>,
+        "Key Str" => Key::Str("world_seed"),
+        "Key Bytes" => Key::Bytes(b"world_seed"),
+        "Key I32" => Key::I32(-7),
+        "Key I64" => Key::I64(i64::MIN),
+        "Key U32" => Key::U32(u32::MAX),
+        "Key U64" => Key::U64(u64::MAX),
+        "Key Usize" => Key::Usize(123_456),
+        "Key slice" => [Key::from("terrain"), Key::from(12i64), Key::from(7u32)],
+        "Segment Str" => Segment::Str("world_seed".to_string()),
+        "Segment U64" => Segment::U64(u64::MAX),
+        "Segment I64" => Segment::I64(-1),
+        "Segment Bytes" => Segment::Bytes(vec![0, 255]),
+        "Segment Tuple" => Segment::Tuple(vec![Segment::from("a"), Segment::from(1u64)]),
+        "DurationKey" => DurationKey(Duration::new(1_700_000_000, 123_456_789)),
+        "DurationKey max" => DurationKey(Duration::MAX),
+        "TimeKey day" => TimeKey::new(Duration::from_secs(1_700_000_000), Duration::from_secs(86_400)),
+        "TimeKey ms" => TimeKey::new(Duration::new(1_700_000_000, 987_654_321), Duration::from_millis(1)),
+    }
+}
+
+/// Check the hashes of the key battery against those recorded in `golden/keys.txt`, listing
+/// every key whose hash has changed, see [Key Hashes](self#key-hashes).
+///
+/// A key missing from the file is reported with a recorded hash of zero.
+///
+/// Requires the `stability` feature.
+///
+/// ```rust
+/// use random_access_rng::golden::{key_hashes, verify_key_hashes};
+///
+/// assert!(key_hashes().len() > 50);
+/// assert_eq!(verify_key_hashes(), Ok(()));
+/// ```
+#[cfg(feature = "stability")]
+pub fn verify_key_hashes() -> Result<(), Vec<StdHashChanged>> {
+    let recorded = |name: &str| {
+        KEY_HASHES
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .find(|&(recorded, _)| recorded == name)
+            .and_then(|(_, hash)| u128::from_str_radix(hash.trim_start_matches("0x"), 16).ok())
+            .unwrap_or(0)
+    };
+
+    let changed: Vec<StdHashChanged> = key_hashes()
+        .into_iter()
+        .filter_map(|(key, actual)| {
+            let expected = recorded(key);
+
+            (actual != expected).then_some(StdHashChanged { key, change: KeyChanged { expected, actual } })
+        })
+        .collect();
+
+    if changed.is_empty() {
+        Ok(())
+    } else {
+        Err(changed)
+    }
+}
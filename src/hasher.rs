@@ -0,0 +1,78 @@
+//! Deterministic hashing for standard collections.
+//!
+//! The default [`RandomState`](std::collections::hash_map::RandomState) of a
+//! [`HashMap`](std::collections::HashMap) is seeded randomly for every process, so iteration
+//! order changes from run to run and any output that depends on it can't be compared against a
+//! golden file. [`DeterministicState`] seeds the hasher from an RNG node instead, making iteration
+//! order a pure function of the seed and the operations performed.
+//!
+//! # HashDoS
+//!
+//! Random seeding exists to stop attackers who can choose keys from forcing every key into the
+//! same bucket. A deterministic seed gives that protection up: anyone who knows (or can guess)
+//! the seed can craft colliding keys. Only use [`DeterministicState`] for maps whose keys don't
+//! come from untrusted input, such as in tests, tools and offline generation.
+
+use std::hash::BuildHasher;
+use std::path::Path;
+use xxhash_rust::xxh3::Xxh3;
+use crate::RandomAccessRNG;
+
+/// A [`BuildHasher`] producing identically seeded XXH3 hashers in every run.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::hasher::DeterministicState;
+/// use std::collections::HashMap;
+///
+/// let tests = RandomAccessRNG::new("golden_tests");
+///
+/// let build = || {
+///     let mut map = HashMap::with_hasher(DeterministicState::from_path(&tests, "inventory"));
+///     for item in ["sword", "shield", "potion", "bow", "arrow"] {
+///         map.insert(item, item.len());
+///     }
+///     map
+/// };
+///
+/// // Iteration order is the same every time
+/// let a: Vec<_> = build().into_iter().collect();
+/// let b: Vec<_> = build().into_iter().collect();
+/// assert_eq!(a, b);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DeterministicState {
+    seed: u64,
+}
+
+impl DeterministicState {
+
+    /// Seed hashers from the digest of `rng`.
+    pub fn new(rng: &RandomAccessRNG) -> Self {
+        Self {
+            seed: rng.digest() as u64,
+        }
+    }
+
+    /// Seed hashers from the descendant of `rng` at `path`.
+    ///
+    /// Paths follow the same rules as [`RandomAccessRNG::path`].
+    pub fn from_path<P: AsRef<Path>>(rng: &RandomAccessRNG, path: P) -> Self {
+        Self::new(&rng.path(path))
+    }
+
+    /// The seed given to every hasher.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl BuildHasher for DeterministicState {
+    type Hasher = Xxh3;
+
+    fn build_hasher(&self) -> Xxh3 {
+        Xxh3::with_seed(self.seed)
+    }
+}
@@ -0,0 +1,134 @@
+//! Rivers traced over a heightmap.
+//!
+//! A river flows from each cell to its lowest neighbour. Heightmaps made from quantised or
+//! fixed-point noise are full of ties, and breaking them by neighbour order or by drawing from a
+//! stream makes a river depend on the direction it was traced from or on what was traced before it.
+//! Here ties are broken by a choice keyed on the cell itself, so the flow out of every cell is a
+//! pure function of the cell and its neighbours' heights. Chunks can be traced independently and in
+//! parallel, rivers that meet continue as one, and a river crossing a chunk boundary takes the same
+//! course on both sides.
+//!
+//! Heights are given as a function of the cell, typically a [`DensityField`](crate::noise::DensityField)
+//! or other noise from [`noise`](crate::noise), so rivers can be traced beyond the chunk being
+//! generated.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::hydrology::{Outlet, trace_river};
+//!
+//! let rivers = RandomAccessRNG::new("world_seed").get("rivers");
+//!
+//! // A square pyramid pit, where most cells have three equally low neighbours
+//! let height = |(x, y): (i64, i64)| x.abs().max(y.abs());
+//!
+//! let river = trace_river(&rivers, (40, 13), height, 1000);
+//! assert_eq!(river.outlet, Outlet::Sink);
+//! assert_eq!(river.points.last(), Some(&(0, 0)));
+//!
+//! // Tracing from any point of the river follows the rest of it exactly
+//! let middle = river.points[5];
+//! let rest = trace_river(&rivers, middle, height, 1000);
+//! assert_eq!(rest.points, river.points[5..]);
+//! ```
+
+use crate::RandomAccessRNG;
+use crate::dist::Draws;
+use crate::polyline::Point;
+
+/// The eight neighbours of a cell, as offsets
+const NEIGHBOURS: [Point; 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+/// Why a river ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Outlet {
+    /// The river reached a cell with no lower neighbour, such as a pit or a flat
+    Sink,
+    /// The river reached the maximum length before ending
+    MaxLength,
+}
+
+/// A river traced by [`trace_river`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct River {
+    /// Every cell of the river in order of flow, starting at the source
+    pub points: Vec<Point>,
+    /// Why the river ended at its last point
+    pub outlet: Outlet,
+}
+
+/// The cell water flows to from `cell`, or [`None`] if no neighbour is lower.
+///
+/// Water flows to the lowest of the eight neighbours. When several share the lowest height, one of
+/// them is chosen uniformly by `rng.get(cell)`, so the result depends only on the RNG node, the
+/// cell and its neighbours' heights.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::hydrology::downstream;
+///
+/// let rivers = RandomAccessRNG::new("world_seed").get("rivers");
+///
+/// // Every neighbour below is equally low, and the same one is always chosen
+/// let height = |(_, y): (i64, i64)| y;
+/// let next = downstream(&rivers, (5, 5), height).unwrap();
+///
+/// assert_eq!(next.1, 4);
+/// assert_eq!(downstream(&rivers, (5, 5), height), Some(next));
+///
+/// // Nothing is lower than the bottom of a pit
+/// assert_eq!(downstream(&rivers, (0, 0), |(x, y): (i64, i64)| x.abs() + y.abs()), None);
+/// ```
+pub fn downstream<F: Fn(Point) -> i64>(rng: &RandomAccessRNG, cell: Point, height: F) -> Option<Point> {
+    let here = height(cell);
+
+    let neighbours = NEIGHBOURS.map(|(dx, dy)| {
+        let neighbour = (cell.0 + dx, cell.1 + dy);
+        (neighbour, height(neighbour))
+    });
+
+    let lowest = neighbours.iter().map(|&(_, h)| h).min()?;
+
+    if lowest >= here {
+        return None;
+    }
+
+    let candidates: Vec<Point> = neighbours
+        .iter()
+        .filter(|&&(_, h)| h == lowest)
+        .map(|&(neighbour, _)| neighbour)
+        .collect();
+
+    let choice = match candidates.len() {
+        1 => 0,
+        n => Draws::new(&mut rng.get(cell)).below(n as u64) as usize,
+    };
+
+    Some(candidates[choice])
+}
+
+/// Trace the river starting at `source` by following [`downstream`] until it reaches a sink or
+/// `max_length` cells.
+///
+/// Because each step depends only on the current cell, a river traced from any of its cells
+/// follows the rest of it exactly, and two rivers that reach the same cell continue together.
+/// Heights strictly decrease along a river, so it never visits a cell twice.
+pub fn trace_river<F: Fn(Point) -> i64>(rng: &RandomAccessRNG, source: Point, height: F, max_length: usize) -> River {
+    let mut points = vec![source];
+
+    while points.len() < max_length {
+        match downstream(rng, *points.last().unwrap(), &height) {
+            Some(next) => points.push(next),
+            None => return River { points, outlet: Outlet::Sink },
+        }
+    }
+
+    // A river exactly `max_length` long may still have ended at a sink
+    let outlet = match downstream(rng, *points.last().unwrap(), &height) {
+        Some(_) => Outlet::MaxLength,
+        None => Outlet::Sink,
+    };
+
+    River { points, outlet }
+}
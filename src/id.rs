@@ -0,0 +1,159 @@
+//! Minting stable IDs for new content.
+//!
+//! Content created during play, such as a monster spawned by an event or an item crafted by a
+//! player, needs an ID that is reproducible and never clashes with any ID handed out before. An
+//! [`IdAllocator`] derives the `n`th ID of a namespace from the namespace node and the counter
+//! `n`, and records every ID it has issued so a collision, however unlikely, is skipped rather than
+//! returned. Its [`IdState`] can be stored with a save and restored later to continue where it
+//! left off.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::id::IdAllocator;
+//!
+//! let world = RandomAccessRNG::new("world_seed");
+//!
+//! let mut monsters = IdAllocator::new(&world, "monsters");
+//! let first = monsters.allocate();
+//! let second = monsters.allocate();
+//!
+//! // Save, and continue from the saved state later
+//! let saved = monsters.state();
+//! let third = monsters.allocate();
+//!
+//! let mut restored = IdAllocator::from_state(&world, "monsters", saved).unwrap();
+//! assert_eq!(restored.allocate(), third);
+//!
+//! // A fresh allocator mints the same sequence
+//! let mut replay = IdAllocator::new(&world, "monsters");
+//! assert_eq!((replay.allocate(), replay.allocate()), (first, second));
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::Hash;
+use xxhash_rust::xxh3::xxh3_64_with_seed;
+use crate::RandomAccessRNG;
+
+/// Seed separating allocated IDs from content IDs and the value stream, "id_alloc" in ASCII
+const ID_DOMAIN: u64 = 0x69645f616c6c6f63;
+
+/// The saved state of an [`IdAllocator`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IdState {
+    /// The digest of the namespace node the IDs were derived from
+    pub digest: u128,
+    /// The counter the next ID will be derived from
+    pub counter: u64,
+    /// Every ID issued or reserved so far, in ascending order
+    pub taken: Vec<u64>,
+}
+
+/// Error returned by [`IdAllocator::from_state`] when the state was saved from a different namespace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IdStateMismatch;
+
+impl fmt::Display for IdStateMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "id allocator state was saved from a different namespace")
+    }
+}
+
+impl std::error::Error for IdStateMismatch {}
+
+/// Hands out non-colliding IDs derived from a namespace and a counter, see the
+/// [module documentation](self).
+///
+/// Memory use grows with the number of IDs taken, as each is remembered for the collision check.
+#[derive(Clone)]
+pub struct IdAllocator {
+    namespace: RandomAccessRNG,
+    counter: u64,
+    taken: HashSet<u64>,
+}
+
+impl IdAllocator {
+
+    /// An allocator for the namespace `rng.get(namespace)`, starting from counter 0.
+    pub fn new<H: Hash>(rng: &RandomAccessRNG, namespace: H) -> Self {
+        Self { namespace: rng.get(namespace), counter: 0, taken: HashSet::new() }
+    }
+
+    /// An allocator continuing from `state`, saved with [`state`](IdAllocator::state) from the
+    /// allocator for the same namespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IdStateMismatch`] if the state was saved from a different namespace.
+    pub fn from_state<H: Hash>(rng: &RandomAccessRNG, namespace: H, state: IdState) -> Result<Self, IdStateMismatch> {
+        let namespace = rng.get(namespace);
+
+        if namespace.digest() != state.digest {
+            return Err(IdStateMismatch);
+        }
+
+        Ok(Self { namespace, counter: state.counter, taken: state.taken.into_iter().collect() })
+    }
+
+    /// The ID for `counter`, whether or not it has been issued.
+    ///
+    /// This is XXH3-64 of the little endian digest of `namespace.get(counter)`, seeded with a
+    /// domain constant of this module.
+    pub fn id_at(&self, counter: u64) -> u64 {
+        xxh3_64_with_seed(&self.namespace.get(counter).digest().to_le_bytes(), ID_DOMAIN)
+    }
+
+    /// Issue the next ID.
+    ///
+    /// IDs are derived from successive counters; a counter whose ID has already been taken is
+    /// skipped, so the result is never an ID issued or [reserved](IdAllocator::reserve) before.
+    pub fn allocate(&mut self) -> u64 {
+        loop {
+            let id = self.id_at(self.counter);
+            self.counter += 1;
+
+            if self.taken.insert(id) {
+                return id;
+            }
+        }
+    }
+
+    /// Mark `id` as taken, for example because generated content already uses it, so it is never
+    /// issued. Returns `false` if it was already taken.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::id::IdAllocator;
+    ///
+    /// let world = RandomAccessRNG::new("world_seed");
+    /// let mut items = IdAllocator::new(&world, "items");
+    ///
+    /// // Pretend the first ID clashes with an existing item
+    /// let clash = items.id_at(0);
+    /// items.reserve(clash);
+    ///
+    /// assert_ne!(items.allocate(), clash);
+    /// ```
+    pub fn reserve(&mut self, id: u64) -> bool {
+        self.taken.insert(id)
+    }
+
+    /// Whether `id` has been issued or reserved.
+    pub fn is_taken(&self, id: u64) -> bool {
+        self.taken.contains(&id)
+    }
+
+    /// The counter the next ID will be derived from.
+    pub fn counter(&self) -> u64 {
+        self.counter
+    }
+
+    /// The state to store to continue allocating later.
+    pub fn state(&self) -> IdState {
+        let mut taken: Vec<u64> = self.taken.iter().copied().collect();
+        taken.sort_unstable();
+
+        IdState { digest: self.namespace.digest(), counter: self.counter, taken }
+    }
+}
@@ -0,0 +1,85 @@
+//! Reading the stream through [`std::io`].
+//!
+//! [`ByteStream`] implements [`Read`] and [`Seek`] over the byte array of
+//! [`read_bytes_at`](RandomAccessRNG::read_bytes_at), so a seeded RNG can be handed to anything
+//! that expects a reader, such as a decoder under fuzzing or code writing large deterministic test
+//! files.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::io::ByteStream;
+//! use std::io::{self, Read, Seek, SeekFrom};
+//!
+//! let rng = RandomAccessRNG::new("seed").get("test_file");
+//!
+//! // A 1 MiB deterministic file
+//! let mut file = Vec::new();
+//! io::copy(&mut ByteStream::new(rng.clone()).take(1 << 20), &mut file).unwrap();
+//!
+//! // Seeking reads the same bytes again
+//! let mut stream = ByteStream::new(rng);
+//! stream.seek(SeekFrom::Start(5000)).unwrap();
+//!
+//! let mut chunk = [0u8; 100];
+//! stream.read_exact(&mut chunk).unwrap();
+//! assert_eq!(chunk, file[5000..5100]);
+//! ```
+
+use std::io::{self, Read, Seek, SeekFrom};
+use crate::RandomAccessRNG;
+
+/// A reader over the stream of an RNG viewed as a byte array, see the [module documentation](self).
+///
+/// The array is `u64::MAX` bytes long, so reads only run out after exabytes. Byte `n` is the one
+/// [`read_bytes_at`](RandomAccessRNG::read_bytes_at) gives at offset `n`; the RNG's own position
+/// is neither used nor changed.
+#[derive(Clone)]
+pub struct ByteStream {
+    rng: RandomAccessRNG,
+    offset: u64,
+}
+
+impl ByteStream {
+
+    /// A reader over the bytes of `rng`, starting at offset 0.
+    pub fn new(rng: RandomAccessRNG) -> Self {
+        Self { rng, offset: 0 }
+    }
+
+    /// The offset of the next byte read.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The underlying RNG.
+    pub fn into_inner(self) -> RandomAccessRNG {
+        self.rng
+    }
+}
+
+impl Read for ByteStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min((u64::MAX - self.offset).try_into().unwrap_or(usize::MAX));
+
+        self.rng.read_bytes_at(self.offset, &mut buf[..len]);
+        self.offset += len as u64;
+
+        Ok(len)
+    }
+}
+
+impl Seek for ByteStream {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, delta) = match pos {
+            SeekFrom::Start(offset) => (offset, 0),
+            SeekFrom::End(delta) => (u64::MAX, delta),
+            SeekFrom::Current(delta) => (self.offset, delta),
+        };
+
+        self.offset = base
+            .checked_add_signed(delta)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "seek to an offset outside the stream"))?;
+
+        Ok(self.offset)
+    }
+}
@@ -0,0 +1,241 @@
+//! Iterator adapters over the value stream.
+
+#[cfg(feature = "rand")]
+use std::marker::PhantomData;
+#[cfg(feature = "rand")]
+use rand::distr::{Distribution, StandardUniform};
+use rand_core::RngCore;
+use crate::RandomAccessRNG;
+
+/// An iterator over the `u64` values of a stream, advancing the RNG it borrows, see
+/// [`RandomAccessRNG::iter`].
+///
+/// The iterator never ends. [`nth`](Iterator::nth) jumps straight to the requested position
+/// instead of generating the values in between, and since the standard [`skip`](Iterator::skip)
+/// and [`step_by`](Iterator::step_by) adapters are built on `nth`, they are O(1) per call too.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+///
+/// let mut rng = RandomAccessRNG::new("seed");
+///
+/// // Skipping a trillion positions is instant
+/// let chunk: Vec<u64> = rng.iter().skip(1_000_000_000_000).take(4).collect();
+/// assert_eq!(chunk[0], rng.peek_at(1_000_000_000_000));
+///
+/// // Every millionth value
+/// let sparse: Vec<u64> = RandomAccessRNG::new("seed").into_iter().step_by(1_000_000).take(4).collect();
+/// assert_eq!(sparse[3], rng.peek_at(3_000_000));
+/// ```
+pub struct Iter<'a> {
+    rng: &'a mut RandomAccessRNG,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        Some(self.rng.next_u64())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<u64> {
+        self.rng.advance(n as u128);
+        self.next()
+    }
+}
+
+/// An iterator over the `u64` values of a stream, owning its RNG, see
+/// [`RandomAccessRNG::into_iter`](IntoIterator::into_iter).
+///
+/// Behaves exactly like [`Iter`].
+pub struct IntoIter {
+    rng: RandomAccessRNG,
+}
+
+impl Iterator for IntoIter {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        Some(self.rng.next_u64())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<u64> {
+        self.rng.advance(n as u128);
+        self.next()
+    }
+}
+
+impl IntoIterator for RandomAccessRNG {
+    type Item = u64;
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        IntoIter { rng: self }
+    }
+}
+
+impl<'a> IntoIterator for &'a mut RandomAccessRNG {
+    type Item = u64;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over values of type `T` drawn from rand's [`StandardUniform`] distribution, see
+/// [`RandomAccessRNG::iter_with`].
+///
+/// Requires the `rand` feature.
+#[cfg(feature = "rand")]
+pub struct IterWith<'a, T> {
+    rng: &'a mut RandomAccessRNG,
+    _marker: PhantomData<fn() -> T>,
+}
+
+#[cfg(feature = "rand")]
+impl<T> Iterator for IterWith<'_, T> where StandardUniform: Distribution<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(StandardUniform.sample(self.rng))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl RandomAccessRNG {
+
+    /// Iterate over the `u64` values of the stream from the current position, advancing this RNG.
+    ///
+    /// Each item is what [`next_u64`](RngCore::next_u64) would have returned. The iterator
+    /// never ends, and skipping ahead with [`nth`](Iterator::nth) or [`skip`](Iterator::skip) is
+    /// O(1). Use [`into_iter`](IntoIterator::into_iter) to iterate over an owned RNG instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// let values: Vec<u64> = rng.iter().take(1000).collect();
+    /// assert_eq!(rng.position(), 1000);
+    ///
+    /// // Skipping doesn't generate the skipped values
+    /// let far = RandomAccessRNG::new("seed").into_iter().nth(1 << 40).unwrap();
+    /// assert_eq!(far, rng.seek_u64(1 << 40));
+    /// ```
+    pub fn iter(&mut self) -> Iter<'_> {
+        Iter { rng: self }
+    }
+
+    /// Iterate over values of type `T` from rand's [`StandardUniform`] distribution, advancing
+    /// this RNG.
+    ///
+    /// Requires the `rand` feature.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// let coins: Vec<bool> = rng.iter_with::<bool>().take(16).collect();
+    /// let unit: Vec<f64> = rng.iter_with::<f64>().take(16).collect();
+    /// assert!(unit.iter().all(|x| (0.0..1.0).contains(x)));
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn iter_with<T>(&mut self) -> IterWith<'_, T> where StandardUniform: Distribution<T> {
+        IterWith {
+            rng: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// An iterator of `(position, value)` pairs sampled from a distribution, see
+/// [`RandomAccessRNG::tagged_samples`].
+///
+/// Requires the `rand` feature.
+#[cfg(feature = "rand")]
+pub struct TaggedSamples<D, T> {
+    rng: RandomAccessRNG,
+    dist: D,
+    _marker: PhantomData<fn() -> T>,
+}
+
+#[cfg(feature = "rand")]
+impl<D: Distribution<T>, T> Iterator for TaggedSamples<D, T> {
+    type Item = (u128, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let position = self.rng.index;
+        let value = self.dist.sample(&mut self.rng);
+
+        Some((position, value))
+    }
+}
+
+#[cfg(feature = "rand")]
+impl RandomAccessRNG {
+
+    /// Sample values from `dist`, tagging each with the stream position its sampling started at.
+    ///
+    /// Distributions may consume any number of stream positions per sample, so the tags are not
+    /// necessarily consecutive. Recording the tag alongside a value lets it be reproduced later,
+    /// on its own, with [`sample_at_position`](RandomAccessRNG::sample_at_position). For the values
+    /// alone, use [`rand::Rng::sample_iter`].
+    ///
+    /// Requires the `rand` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand::distr::Uniform;
+    ///
+    /// let rng = RandomAccessRNG::new("seed");
+    /// let dice = Uniform::new_inclusive(1, 6).unwrap();
+    ///
+    /// let rolls: Vec<(u128, u32)> = rng.clone().tagged_samples(dice).take(10).collect();
+    ///
+    /// // Any recorded roll can be re-queried directly from its position
+    /// let (position, roll) = rolls[7];
+    /// assert_eq!(rng.sample_at_position(position, &dice), roll);
+    ///
+    /// // The same values as sampling without tags
+    /// use rand::Rng;
+    /// let plain: Vec<u32> = rng.clone().sample_iter(dice).take(10).collect();
+    /// assert_eq!(plain[7], roll);
+    /// ```
+    pub fn tagged_samples<T, D: Distribution<T>>(self, dist: D) -> TaggedSamples<D, T> {
+        TaggedSamples {
+            rng: self,
+            dist,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sample a value from `dist` starting at stream position `position`, without modifying this RNG.
+    ///
+    /// Requires the `rand` feature.
+    pub fn sample_at_position<T, D: Distribution<T>>(&self, position: u128, dist: &D) -> T {
+        let mut rng = self.clone();
+        rng.index = position;
+
+        dist.sample(&mut rng)
+    }
+}
@@ -0,0 +1,646 @@
+//! Helpers for building keys and seeds.
+//!
+//! Keys and seeds in this crate are any type implementing [`std::hash::Hash`], usually through
+//! `#[derive(Hash)]`. The items in this module cover the cases where deriving isn't enough, or
+//! where hashing the key at runtime is undesirable.
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use xxhash_rust::const_xxh3::xxh3_128;
+use crate::RandomAccessRNG;
+
+/// Hash a string key for use with [`get_prehashed`](crate::RandomAccessRNG::get_prehashed).
+///
+/// This is a `const fn`, so it can be evaluated at compile time; the [`xxh3_const!`](crate::xxh3_const)
+/// macro guarantees that it is.
+///
+/// ```rust
+/// use random_access_rng::key::prehash;
+///
+/// const TERRAIN: u128 = prehash("terrain");
+/// assert_eq!(TERRAIN, random_access_rng::xxh3_const!("terrain"));
+/// ```
+pub const fn prehash(key: &str) -> u128 {
+    xxh3_128(key.as_bytes())
+}
+
+/// Hash a string literal at compile time, for use with [`get_prehashed`](crate::RandomAccessRNG::get_prehashed).
+///
+/// Expands to a `u128` constant equal to [`prehash`](crate::key::prehash) of the literal, so no
+/// hashing of the key happens at runtime.
+///
+/// ```rust
+/// use random_access_rng::{RandomAccessRNG, xxh3_const};
+/// use rand_core::RngCore;
+///
+/// let world = RandomAccessRNG::new("world_seed");
+///
+/// let mut terrain = world.get_prehashed(xxh3_const!("terrain"));
+/// let value = terrain.next_u64();
+/// ```
+#[macro_export]
+macro_rules! xxh3_const {
+    ($key:expr) => {{
+        const HASH: u128 = $crate::key::prehash($key);
+        HASH
+    }};
+}
+
+/// One level of a structured hierarchy address, see
+/// [`path_segments`](crate::RandomAccessRNG::path_segments).
+///
+/// Unlike [`Key`], segments own their data, so addresses can be built at runtime, stored and,
+/// with the `serde` feature, serialized. Each segment hashes exactly as the value it holds, and a
+/// tuple hashes as a Rust tuple of its elements, so segments derive the same nodes as the
+/// equivalent typed keys.
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::key::Segment;
+///
+/// let world = RandomAccessRNG::new("world_seed");
+///
+/// let address = vec![
+///     Segment::from("regions"),
+///     Segment::Tuple(vec![Segment::I64(-3), Segment::I64(8)]),
+///     Segment::U64(42),
+/// ];
+///
+/// assert_eq!(world.path_segments(&address).digest(), world.get("regions").get((-3i64, 8i64)).get(42u64).digest());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Segment {
+    /// A string key, hashed as `&str`
+    Str(String),
+    /// A `u64` key
+    U64(u64),
+    /// An `i64` key
+    I64(i64),
+    /// A byte string key, hashed as `&[u8]`
+    Bytes(Vec<u8>),
+    /// Several keys forming one level, hashed as a tuple of them
+    Tuple(Vec<Segment>),
+}
+
+impl Hash for Segment {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        match self {
+            Segment::Str(key) => key.as_str().hash(state),
+            Segment::U64(key) => key.hash(state),
+            Segment::I64(key) => key.hash(state),
+            Segment::Bytes(key) => key.as_slice().hash(state),
+            Segment::Tuple(keys) => {
+                // Tuples hash their fields in order, with no length
+                for key in keys {
+                    key.hash(state);
+                }
+            },
+        }
+    }
+}
+
+impl From<&str> for Segment {
+    fn from(key: &str) -> Self {
+        Segment::Str(key.to_string())
+    }
+}
+
+impl From<String> for Segment {
+    fn from(key: String) -> Self {
+        Segment::Str(key)
+    }
+}
+
+impl From<u64> for Segment {
+    fn from(key: u64) -> Self {
+        Segment::U64(key)
+    }
+}
+
+impl From<i64> for Segment {
+    fn from(key: i64) -> Self {
+        Segment::I64(key)
+    }
+}
+
+impl From<Vec<u8>> for Segment {
+    fn from(key: Vec<u8>) -> Self {
+        Segment::Bytes(key)
+    }
+}
+
+/// Derive a descendant from a sequence of keys of any types, without building a path string.
+///
+/// `rng_path!(rng, a, b, c)` expands to `rng.get(a).get(b).get(c)`, so each key hashes as its own
+/// type and nothing is allocated. Every key must implement [`Hash`]; anything else, such as a
+/// float, is rejected at compile time.
+///
+/// ```rust
+/// use random_access_rng::{RandomAccessRNG, rng_path};
+///
+/// let world = RandomAccessRNG::new("world_seed");
+/// let (x, y) = (12i64, -7i64);
+///
+/// let chunk = rng_path!(world, "terrain", x, y);
+/// assert_eq!(chunk.digest(), world.get("terrain").get(x).get(y).digest());
+/// ```
+///
+/// ```compile_fail
+/// use random_access_rng::{RandomAccessRNG, rng_path};
+///
+/// let world = RandomAccessRNG::new("world_seed");
+///
+/// // Floats have no Hash implementation
+/// let cell = rng_path!(world, "terrain", 0.5f64);
+/// ```
+#[macro_export]
+macro_rules! rng_path {
+    ($rng:expr $(, $key:expr)+ $(,)?) => {
+        $rng$(.get($key))+
+    };
+}
+
+/// Escape `key` for use as one component of a path under [`Scheme::V5`](crate::Scheme::V5).
+///
+/// `%`, `/` and `\` are replaced by `%25`, `%2F` and `%5C`, so the component never splits and
+/// decodes back to `key`: `path(escape_path_key(key))` is the same node as `get(key)`. Under
+/// earlier schemes paths are not decoded, so escaped keys don't round-trip.
+///
+/// ```rust
+/// use random_access_rng::key::escape_path_key;
+///
+/// assert_eq!(escape_path_key("a/b\\c%"), "a%2Fb%5Cc%25");
+/// assert_eq!(escape_path_key("plain"), "plain");
+/// ```
+pub fn escape_path_key(key: &str) -> String {
+    let mut escaped = String::with_capacity(key.len());
+
+    for c in key.chars() {
+        match c {
+            '%' => escaped.push_str("%25"),
+            '/' => escaped.push_str("%2F"),
+            '\\' => escaped.push_str("%5C"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// A value hashed by a custom function instead of its own [`Hash`] implementation.
+///
+/// Useful when a key struct contains a field of a third-party type that doesn't implement
+/// [`Hash`] (or implements it in a way that isn't stable enough for seeding), without writing a
+/// newtype and a manual `Hash` impl for it. Since this wrapper implements [`Hash`] itself, the
+/// containing struct can still use `#[derive(Hash)]`.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::key::HashWith;
+/// use rand_core::RngCore;
+/// use std::hash::{Hash, Hasher};
+///
+/// // A type from another crate, with no Hash implementation
+/// struct Color { r: f32, g: f32, b: f32 }
+///
+/// fn hash_color(color: &Color, state: &mut dyn Hasher) {
+///     for channel in [color.r, color.g, color.b] {
+///         state.write_u32(channel.to_bits());
+///     }
+/// }
+///
+/// #[derive(Hash)]
+/// struct TileKey {
+///     position: (i32, i32),
+///     tint: HashWith<Color>,
+/// }
+///
+/// let world = RandomAccessRNG::new("world_seed");
+///
+/// let key = |x| TileKey {
+///     position: (x, 7),
+///     tint: HashWith::new(Color { r: 1.0, g: 0.5, b: 0.0 }, hash_color),
+/// };
+///
+/// let mut a = world.get(key(3));
+/// let mut b = world.get(key(3));
+///
+/// assert_eq!(a.next_u64(), b.next_u64());
+/// ```
+#[derive(Clone, Copy)]
+pub struct HashWith<T> {
+    value: T,
+    hash: fn(&T, &mut dyn Hasher),
+}
+
+impl<T> HashWith<T> {
+
+    /// Wrap `value`, hashing it with `hash`.
+    pub fn new(value: T, hash: fn(&T, &mut dyn Hasher)) -> Self {
+        Self { value, hash }
+    }
+
+    /// The wrapped value.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Unwrap the value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Hash for HashWith<T> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        (self.hash)(&self.value, state)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for HashWith<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("HashWith").field(&self.value).finish()
+    }
+}
+
+/// One key out of a sequence of keys of different types.
+///
+/// [`descendant`](crate::RandomAccessRNG::descendant) takes keys of a single type, so a mixed
+/// path such as `"terrain"`, `12`, `7` is written with each key wrapped in `Key`. Every variant
+/// hashes exactly as the value it holds, so wrapping a key never changes the RNG it derives. The
+/// exception is [`Key::Usize`], which hashes as a `u64` so that it derives the same RNG on 32 and
+/// 64-bit platforms.
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::key::Key;
+///
+/// let world = RandomAccessRNG::new("world_seed");
+///
+/// let chunk = |x: i64, y: i64| world.descendant([Key::from("terrain"), x.into(), y.into()]);
+///
+/// assert_eq!(chunk(12, 7).digest(), world.get("terrain").get(12i64).get(7i64).digest());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key<'a> {
+    /// A string key, hashed as `&str`
+    Str(&'a str),
+    /// A byte string key, hashed as `&[u8]`
+    Bytes(&'a [u8]),
+    /// An `i32` key
+    I32(i32),
+    /// An `i64` key
+    I64(i64),
+    /// A `u32` key
+    U32(u32),
+    /// A `u64` key
+    U64(u64),
+    /// A `usize` key, hashed as `u64` on every platform
+    ///
+    /// ```rust
+    /// use random_access_rng::key::{key_hash, Key};
+    ///
+    /// assert_eq!(key_hash(&Key::Usize(123_456)), key_hash(&123_456u64));
+    /// ```
+    Usize(usize),
+}
+
+impl Hash for Key<'_> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        match self {
+            Key::Str(key) => key.hash(state),
+            Key::Bytes(key) => key.hash(state),
+            Key::I32(key) => key.hash(state),
+            Key::I64(key) => key.hash(state),
+            Key::U32(key) => key.hash(state),
+            Key::U64(key) => key.hash(state),
+            Key::Usize(key) => (*key as u64).hash(state),
+        }
+    }
+}
+
+macro_rules! key_from {
+    ($($t:ty => $variant:ident),*) => {
+        $(
+            impl<'a> From<$t> for Key<'a> {
+                fn from(key: $t) -> Self {
+                    Key::$variant(key)
+                }
+            }
+        )*
+    };
+}
+
+key_from!(&'a str => Str, &'a [u8] => Bytes, i32 => I32, i64 => I64, u32 => U32, u64 => U64, usize => Usize);
+
+impl<'a> From<&'a String> for Key<'a> {
+    fn from(key: &'a String) -> Self {
+        Key::Str(key)
+    }
+}
+
+/// A [`Duration`] key with a stable hash.
+///
+/// The standard [`Hash`] implementation of [`Duration`] depends on its internal layout, which is
+/// not guaranteed. This wrapper hashes the total number of nanoseconds as a little endian `u128`,
+/// so the derived RNG is the same on every platform and Rust version.
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::key::DurationKey;
+/// use rand_core::RngCore;
+/// use std::time::Duration;
+///
+/// let world = RandomAccessRNG::new("world_seed");
+///
+/// let mut a = world.get(DurationKey::from(Duration::from_millis(1500)));
+/// let mut b = world.get(DurationKey::from(Duration::from_micros(1_500_000)));
+/// assert_eq!(a.next_u64(), b.next_u64());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DurationKey(pub Duration);
+
+impl From<Duration> for DurationKey {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl Hash for DurationKey {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write(&self.0.as_nanos().to_le_bytes())
+    }
+}
+
+/// A timestamp key, truncated to an explicit resolution.
+///
+/// Seeding from the current time directly picks up sub-millisecond noise, so two events meant to
+/// share a seed (the same daily challenge, the same hourly event) rarely do. A `TimeKey` truncates
+/// the time since the Unix epoch down to a whole multiple of its resolution before hashing, and
+/// hashes like the [`DurationKey`] of the truncated time.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::key::TimeKey;
+/// use rand_core::RngCore;
+/// use std::time::Duration;
+///
+/// const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+///
+/// let challenges = RandomAccessRNG::new("daily_challenge");
+///
+/// // Two times on the same day give the same key
+/// let morning = TimeKey::new(Duration::from_secs(1_699_930_000), DAY);
+/// let evening = TimeKey::new(Duration::from_secs(1_700_000_000), DAY);
+/// assert_eq!(morning, evening);
+/// assert_eq!(morning.ticks(), 19675);
+///
+/// let mut a = challenges.get(morning);
+/// let mut b = challenges.get(evening);
+/// assert_eq!(a.next_u64(), b.next_u64());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeKey {
+    since_epoch: DurationKey,
+    resolution: Duration,
+}
+
+impl TimeKey {
+
+    /// A key for the time `since_epoch` after the Unix epoch, truncated to `resolution`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resolution` is zero.
+    pub fn new(since_epoch: Duration, resolution: Duration) -> Self {
+        let step = resolution.as_nanos();
+        assert!(step > 0, "resolution must be non-zero");
+
+        let nanos = since_epoch.as_nanos() / step * step;
+        let since_epoch = Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32);
+
+        Self {
+            since_epoch: DurationKey(since_epoch),
+            resolution,
+        }
+    }
+
+    /// A key for a [`SystemTime`], truncated to `resolution`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resolution` is zero or `time` is before the Unix epoch.
+    pub fn from_system_time(time: SystemTime, resolution: Duration) -> Self {
+        Self::new(time.duration_since(UNIX_EPOCH).expect("Time before the Unix epoch"), resolution)
+    }
+
+    /// The truncated time since the Unix epoch.
+    pub fn since_epoch(&self) -> Duration {
+        self.since_epoch.0
+    }
+
+    /// The resolution the time was truncated to.
+    pub fn resolution(&self) -> Duration {
+        self.resolution
+    }
+
+    /// The number of whole resolution steps since the Unix epoch.
+    pub fn ticks(&self) -> u128 {
+        self.since_epoch.0.as_nanos() / self.resolution.as_nanos()
+    }
+}
+
+impl Hash for TimeKey {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.since_epoch.hash(state)
+    }
+}
+
+/// Error returned by [`check_key_stability`] when a key no longer hashes to its recorded value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyChanged {
+    /// The recorded hash
+    pub expected: u128,
+    /// The hash of the key today
+    pub actual: u128,
+}
+
+impl fmt::Display for KeyChanged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "key hash changed from {:#034x} to {:#034x}, so everything derived from this key has changed", self.expected, self.actual)
+    }
+}
+
+impl std::error::Error for KeyChanged {}
+
+/// The hash identifying a key, as seen by [`RandomAccessRNG::new`].
+///
+/// Two keys with the same hash seed identical RNGs.
+pub fn key_hash<T: Hash + ?Sized>(key: &T) -> u128 {
+    RandomAccessRNG::new(key).digest()
+}
+
+/// Check that a key still hashes to a previously recorded value.
+///
+/// Keys usually rely on `#[derive(Hash)]`, whose output changes when fields are added, removed,
+/// reordered or change type, and can change when a dependency updates the `Hash` implementation of
+/// one of its types. Any such change silently alters every value derived from the key. Recording
+/// the hash of a canonical instance of each key type in a test, and checking it with this function
+/// (or [`assert_key_stable!`](crate::assert_key_stable)), turns that into a test failure.
+///
+/// The standard library hashes integers in native byte order, so recorded hashes of keys with
+/// integer fields are only comparable between platforms of the same endianness.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::key::{check_key_stability, key_hash};
+///
+/// #[derive(Hash)]
+/// struct ChunkKey { x: i32, z: i32, layer: u8 }
+///
+/// let canonical = ChunkKey { x: 0, z: 0, layer: 0 };
+/// let recorded = key_hash(&canonical);
+///
+/// assert!(check_key_stability(&canonical, recorded).is_ok());
+///
+/// // Adding a field changes the hash
+/// #[derive(Hash)]
+/// struct ChunkKeyV2 { x: i32, z: i32, layer: u8, dimension: u8 }
+///
+/// let error = check_key_stability(&ChunkKeyV2 { x: 0, z: 0, layer: 0, dimension: 0 }, recorded).unwrap_err();
+/// assert_eq!(error.expected, recorded);
+/// ```
+pub fn check_key_stability<T: Hash + ?Sized>(canonical: &T, recorded: u128) -> Result<(), KeyChanged> {
+    let actual = key_hash(canonical);
+
+    if actual == recorded {
+        Ok(())
+    } else {
+        Err(KeyChanged {
+            expected: recorded,
+            actual,
+        })
+    }
+}
+
+/// Assert that a key still hashes to a recorded value, for use in tests.
+///
+/// Panics with a [`KeyChanged`](crate::key::KeyChanged) message showing both hashes if the key has
+/// changed. To record the hash of a new key, run the test once with any value and copy the actual
+/// hash from the message.
+///
+/// ```rust
+/// use random_access_rng::assert_key_stable;
+///
+/// #[derive(Hash)]
+/// struct ChunkKey { x: i32, z: i32, layer: u8 }
+///
+/// assert_key_stable!(ChunkKey { x: 0, z: 0, layer: 0 }, 0xbe637bf2e7ab4aec17dbb924bfd111e6);
+/// ```
+#[macro_export]
+macro_rules! assert_key_stable {
+    ($key:expr, $recorded:expr) => {
+        if let Err(changed) = $crate::key::check_key_stability(&$key, $recorded) {
+            panic!("{}", changed);
+        }
+    };
+}
+
+/// A standard key type whose hash differs from the recorded value, returned by
+/// [`verify_std_hash_stability`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StdHashChanged {
+    /// The key type, such as `"u32"` or `"tuple"`
+    pub key: &'static str,
+    /// The recorded and current hash
+    pub change: KeyChanged,
+}
+
+impl fmt::Display for StdHashChanged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.change)
+    }
+}
+
+impl std::error::Error for StdHashChanged {}
+
+/// Check a canonical value of each key type against its recorded hash
+macro_rules! check_canonical_keys {
+    ($($name:literal => $value:expr => $recorded:literal),* $(,)?) => {
+        [$(($name, check_key_stability(&$value, $recorded))),*]
+    };
+}
+
+/// Check that the standard library still hashes common key types the way it did when this crate
+/// recorded them.
+///
+/// Seeding relies on the [`Hash`] implementations of the standard library, which aren't formally
+/// guaranteed to stay the same between Rust releases. This hashes a canonical value of each
+/// commonly used key type (every integer type, `bool`, `char`, strings, `()`, tuples, arrays,
+/// slices, `Vec`, `Option` and [`DurationKey`]) and lists every type whose hash has changed. Calling
+/// it at startup, or in a test, turns a toolchain change that would silently alter every derived
+/// value into a visible error.
+///
+/// The hashes were recorded on a little-endian 64-bit platform. The standard library hashes
+/// integers in native byte order and `usize` at native width, so this also reports platforms
+/// where keys hash differently from that reference.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::key::verify_std_hash_stability;
+///
+/// if let Err(changes) = verify_std_hash_stability() {
+///     for change in &changes {
+///         eprintln!("{}", change);
+///     }
+///
+///     panic!("derived values will differ from other builds");
+/// }
+/// ```
+pub fn verify_std_hash_stability() -> Result<(), Vec<StdHashChanged>> {
+    let checks = check_canonical_keys! {
+        "u8" => 0xa5u8 => 0x71d3b97d97ee075540f22ce7ca17bd07,
+        "u16" => 0xa55au16 => 0x9af1ec2e248cb043e578c15ce62c9697,
+        "u32" => 0xa55a_3cc3u32 => 0x1f6330003b9d7e70b3c0ba890f3555fc,
+        "u64" => 0xa55a_3cc3_0ff0_9669u64 => 0x2f8cc52914aaf4b4164a257233b69d0a,
+        "u128" => 0xa55a_3cc3_0ff0_9669_1234_5678_9abc_def0u128 => 0xe62f8401d681f5a300ab13d006581a22,
+        "i8" => -5i8 => 0x4b120ec907565137f487e4acab9c606c,
+        "i16" => -500i16 => 0x2b1cec00321ec2f4606c933f345eb322,
+        "i32" => -500_000i32 => 0x34e50019a5c5d6faae7cfe868feabbfe,
+        "i64" => -5_000_000_000i64 => 0x2c984d3bc3e038ba7ea2e886ff79c32d,
+        "i128" => -50_000_000_000_000_000_000i128 => 0xd8fe71d47753574f875a96a9c926fdab,
+        "usize" => 123_456usize => 0x5a24c7c571e5e2474f0dd8f429415d70,
+        "isize" => -123_456isize => 0x4b3d150ed8df6ee0dd87bc79ea95c119,
+        "bool" => true => 0x51025a4491835505e12ef9d2eb86ceeb,
+        "char" => 'é' => 0x785e127efb050510a524f044e2d567c1,
+        "str" => "canonical" => 0x8ed6bd39da8df84c1ed1c3b01dfbf717,
+        "String" => String::from("canonical") => 0x8ed6bd39da8df84c1ed1c3b01dfbf717,
+        "()" => () => 0x99aa06d3014798d86001c324468d497f,
+        "tuple" => (7u32, "seven", false) => 0x4a5810fbeb46b76951fd5993749beea7,
+        "array" => [1u16, 2, 3, 4] => 0x82802ef579d61c31370150cb9103dfbc,
+        "slice" => [1u16, 2, 3, 4][..] => 0x82802ef579d61c31370150cb9103dfbc,
+        "Vec" => vec![10u64, 20, 30] => 0x892597942eb3267cad98693777fd8424,
+        "Some" => Some(42u32) => 0xe0f870af88514d2601a5efc6d290da16,
+        "None" => None::<u32> => 0x2c0a8a99dc147d5445c3b49d035665b2,
+        "DurationKey" => DurationKey(Duration::new(5, 6)) => 0x7d01b9be1c592425bac6c002cd0b137a,
+    };
+
+    let changed: Vec<StdHashChanged> = checks
+        .into_iter()
+        .filter_map(|(key, check)| check.err().map(|change| StdHashChanged { key, change }))
+        .collect();
+
+    if changed.is_empty() {
+        Ok(())
+    } else {
+        Err(changed)
+    }
+}
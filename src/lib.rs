@@ -151,18 +151,75 @@
 //! use random_access_rng::RandomAccessRNG;
 //! use rand_core::RngCore;
 //! 
-//! #[test]
-//! fn test_deterministic_behavior() {
-//!     let mut rng1 = RandomAccessRNG::new("test_seed");
-//!     let mut rng2 = RandomAccessRNG::new("test_seed");
-//!     
-//!     // Both RNGs should produce identical sequences
-//!     for _ in 0..100 {
-//!         assert_eq!(rng1.next_u64(), rng2.next_u64());
-//!     }
+//! let mut rng1 = RandomAccessRNG::new("test_seed");
+//! let mut rng2 = RandomAccessRNG::new("test_seed");
+//!
+//! // Both RNGs should produce identical sequences
+//! for _ in 0..100 {
+//!     assert_eq!(rng1.next_u64(), rng2.next_u64());
 //! }
 //! ```
 
 // Expose the random access RNG module
 pub mod random_access_rng;
-pub use random_access_rng::RandomAccessRNG;
+pub use random_access_rng::{PathError, RandomAccessRNG, Scheme};
+pub mod iter;
+
+// Building blocks for keys and seeds
+pub mod key;
+
+// Domain specific helpers built on top of the RNG
+pub mod ai;
+pub mod audio;
+pub mod block;
+pub mod budget;
+pub mod chance;
+pub mod chaos;
+pub mod checkpoint;
+pub mod combination;
+pub mod compat;
+pub mod diff;
+pub mod dist;
+pub mod ensemble;
+#[cfg(feature = "getrandom")]
+pub mod entropy;
+pub mod export;
+pub mod float;
+pub mod freeze;
+pub mod golden;
+pub mod hasher;
+pub mod hydrology;
+pub mod id;
+pub mod io;
+pub mod mix;
+pub mod node;
+pub mod noise;
+pub mod parallel;
+#[cfg(feature = "regex-syntax")]
+pub mod pattern;
+pub mod permutation;
+pub mod physics;
+pub mod placement;
+pub mod polyline;
+pub mod pool;
+#[cfg(feature = "rand_core_06")]
+pub mod rand06;
+pub mod range;
+pub mod report;
+pub mod reroll;
+pub mod reservoir;
+pub mod rollout;
+pub mod sampling;
+pub mod scenario;
+pub mod seeding;
+#[cfg(feature = "shader")]
+pub mod shader;
+pub mod spatial;
+pub mod state;
+pub mod stateless;
+pub mod structure;
+pub mod text;
+pub mod tracked;
+pub mod versioning;
+#[cfg(feature = "index")]
+pub mod view;
@@ -0,0 +1,52 @@
+//! The low-level mixing functions behind the value stream.
+//!
+//! These are exported for building custom derivation schemes on top of the crate, and for
+//! reimplementing generation elsewhere, such as in a GPU shader, so that it provably matches the
+//! values produced here. Both functions are covered by the crate's reproducibility guarantee: their
+//! outputs will never change.
+//!
+//! Both are XXH3-128 of the little endian bytes of their inputs, so any XXH3 implementation can
+//! reproduce them.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::mix::hash_index;
+//!
+//! let mut terrain = RandomAccessRNG::new("world_seed").get("terrain");
+//!
+//! // A shader given only the digest can generate any value of the stream
+//! let digest = terrain.digest();
+//! assert_eq!(hash_index(digest, 1000), terrain.seek_u128(1000));
+//! ```
+
+use xxhash_rust::xxh3::xxh3_128;
+use crate::random_access_rng::xxh3_integer_hash;
+
+/// Mix two 128-bit values into one.
+///
+/// This is XXH3-128 of the 32 bytes `a.to_le_bytes()` followed by `b.to_le_bytes()`. It is not
+/// symmetric, so `mix128(a, b)` and `mix128(b, a)` are unrelated.
+///
+/// ```rust
+/// use random_access_rng::mix::mix128;
+///
+/// assert_ne!(mix128(1, 2), mix128(2, 1));
+/// assert_eq!(mix128(1, 2), 0xc88e3255b4546aadee3fb5249c767bd2);
+/// ```
+pub fn mix128(a: u128, b: u128) -> u128 {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(&a.to_le_bytes());
+    bytes[16..].copy_from_slice(&b.to_le_bytes());
+
+    xxh3_128(&bytes)
+}
+
+/// The 128-bit value at position `index` of the stream of the node whose
+/// [`digest`](crate::RandomAccessRNG::digest) is `digest`.
+///
+/// This is XXH3-128 of the 16 bytes `(digest ^ index).to_le_bytes()`, and equals
+/// [`seek_u128`](crate::RandomAccessRNG::seek_u128) at `index` under every scheme. Up to
+/// [`Scheme::V3`](crate::Scheme::V3), 64-bit draws are its low 64 bits.
+pub fn hash_index(digest: u128, index: u128) -> u128 {
+    xxh3_integer_hash(digest ^ index)
+}
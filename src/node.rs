@@ -0,0 +1,137 @@
+//! Separate types for organising the hierarchy and for drawing values.
+//!
+//! Nodes in the middle of a hierarchy, such as `world` or `world/biomes`, usually exist only to
+//! derive their children. Drawing values from one of them by mistake doesn't fail, it silently
+//! couples everything drawn from it, and nothing flags it in review. [`RngNode`] can only derive
+//! children, and has to be turned into an [`RngStream`] explicitly before any value is drawn, so a
+//! team can make organisational nodes undrawable at compile time.
+//!
+//! ```rust
+//! use random_access_rng::node::RngNode;
+//! use rand_core::RngCore;
+//!
+//! let world = RngNode::new("world_seed");
+//! let biomes = world.get("biomes");
+//!
+//! let mut forest = biomes.get("forest").stream();
+//! let tree_count = forest.next_u64() % 100;
+//! ```
+//!
+//! Nodes have no drawing methods:
+//!
+//! ```rust,compile_fail
+//! use random_access_rng::node::RngNode;
+//! use rand_core::RngCore;
+//!
+//! let world = RngNode::new("world_seed");
+//! world.next_u64();
+//! ```
+
+use std::hash::Hash;
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+use crate::{RandomAccessRNG, Scheme};
+
+/// A node of the hierarchy that can derive children but not draw values.
+///
+/// Children are derived exactly as by the methods of the same names on [`RandomAccessRNG`], so a
+/// node and an RNG with the same seed and path have the same stream.
+#[derive(Clone, Debug)]
+pub struct RngNode {
+    rng: RandomAccessRNG,
+}
+
+impl RngNode {
+
+    /// The root node for `seed`, as [`RandomAccessRNG::new`].
+    pub fn new<H: Hash>(seed: H) -> Self {
+        Self { rng: RandomAccessRNG::new(seed) }
+    }
+
+    /// The root node for `seed` using `scheme`, as [`RandomAccessRNG::with_scheme`].
+    pub fn with_scheme<H: Hash>(seed: H, scheme: Scheme) -> Self {
+        Self { rng: RandomAccessRNG::with_scheme(seed, scheme) }
+    }
+
+    /// The child node at `key`, see [`RandomAccessRNG::get`].
+    pub fn get<H: Hash>(&self, key: H) -> Self {
+        Self { rng: self.rng.get(key) }
+    }
+
+    /// The child node at a prehashed key, see [`RandomAccessRNG::get_prehashed`].
+    pub fn get_prehashed(&self, hash: u128) -> Self {
+        Self { rng: self.rng.get_prehashed(hash) }
+    }
+
+    /// The descendant node reached by applying `keys` in order, see [`RandomAccessRNG::descendant`].
+    pub fn descendant<K: Hash, I: IntoIterator<Item = K>>(&self, keys: I) -> Self {
+        Self { rng: self.rng.descendant(keys) }
+    }
+
+    /// The descendant node at `path`, see [`RandomAccessRNG::path`].
+    pub fn path<P: AsRef<Path>>(&self, path: P) -> Self {
+        Self { rng: self.rng.path(path) }
+    }
+
+    /// The digest identifying this node, see [`RandomAccessRNG::digest`].
+    pub fn digest(&self) -> u128 {
+        self.rng.digest()
+    }
+
+    /// The scheme this node uses.
+    pub fn scheme(&self) -> Scheme {
+        self.rng.scheme()
+    }
+
+    /// A stream drawing values from this node, starting at position 0.
+    pub fn stream(&self) -> RngStream {
+        RngStream { rng: self.rng.clone() }
+    }
+}
+
+impl From<RandomAccessRNG> for RngNode {
+
+    /// The node of an RNG, discarding its position.
+    fn from(mut rng: RandomAccessRNG) -> Self {
+        rng.set_position(0);
+
+        Self { rng }
+    }
+}
+
+/// A node that draws values, created with [`RngNode::stream`].
+///
+/// Dereferences to a [`RandomAccessRNG`], and so implements [`RngCore`](rand_core::RngCore) and
+/// can be used anywhere an RNG can.
+#[derive(Clone, Debug)]
+pub struct RngStream {
+    rng: RandomAccessRNG,
+}
+
+impl RngStream {
+
+    /// The underlying RNG, at the stream's current position.
+    pub fn into_inner(self) -> RandomAccessRNG {
+        self.rng
+    }
+}
+
+impl From<RngNode> for RngStream {
+    fn from(node: RngNode) -> Self {
+        Self { rng: node.rng }
+    }
+}
+
+impl Deref for RngStream {
+    type Target = RandomAccessRNG;
+
+    fn deref(&self) -> &RandomAccessRNG {
+        &self.rng
+    }
+}
+
+impl DerefMut for RngStream {
+    fn deref_mut(&mut self) -> &mut RandomAccessRNG {
+        &mut self.rng
+    }
+}
@@ -0,0 +1,317 @@
+//! Fixed-point noise for terrain generation.
+//!
+//! Floating point noise functions can differ in their last bits between platforms, compilers and
+//! optimisation levels, which is enough to flip a voxel from solid to air and desynchronise a
+//! world. Everything here is computed with integer arithmetic on Q16.16 fixed-point numbers (see
+//! [`ONE`]), and every value is a pure function of its coordinates, so results are identical on
+//! every platform and in whatever order chunks are generated.
+
+use std::hash::Hash;
+use xxhash_rust::xxh3::xxh3_64_with_seed;
+use crate::RandomAccessRNG;
+use crate::polyline::Point;
+
+/// The value `1.0` in the Q16.16 fixed-point format used by this module.
+pub const ONE: i64 = 1 << 16;
+
+/// Hash lattice coordinates to a value in `[-ONE, ONE)`
+fn lattice(key: u64, x: i64, y: i64, z: i64) -> i64 {
+    let mut bytes = [0u8; 24];
+    bytes[..8].copy_from_slice(&x.to_le_bytes());
+    bytes[8..16].copy_from_slice(&y.to_le_bytes());
+    bytes[16..].copy_from_slice(&z.to_le_bytes());
+
+    // The top 17 bits give a value in 0..2 * ONE
+    (xxh3_64_with_seed(&bytes, key) >> 47) as i64 - ONE
+}
+
+/// Linear interpolation between `a` and `b` with `t` in Q16
+fn lerp(a: i64, b: i64, t: i64) -> i64 {
+    a + (((b - a) * t) >> 16)
+}
+
+/// Smoothstep `3t^2 - 2t^3` for `t` in Q16
+fn fade(t: i64) -> i64 {
+    (((t * t) >> 16) * (3 * ONE - 2 * t)) >> 16
+}
+
+/// Split a coordinate into its lattice cell and the Q16 position within the cell
+fn cell(v: i64, size: i64) -> (i64, i64) {
+    (v.div_euclid(size), (v.rem_euclid(size) << 16) / size)
+}
+
+/// Settings for a [`DensityField`].
+#[derive(Clone, Debug)]
+pub struct DensitySettings {
+    /// Number of noise octaves to combine
+    pub octaves: u32,
+    /// Size in blocks of a lattice cell in the first octave, halved for every following octave
+    /// down to a minimum of one block
+    pub scale: i64,
+    /// Amplitude multiplier from one octave to the next, in Q16 (`ONE / 2` halves each octave).
+    /// Values above `ONE` grow the amplitude with every octave, and the density saturates at the
+    /// limits of `i64` instead of overflowing.
+    pub persistence: i64,
+    /// Bias added to the noise as a function of height, as `(y, bias)` control points sorted by
+    /// `y` with the bias in Q16. Linearly interpolated between points and held constant outside them.
+    pub bias: Vec<(i64, i64)>,
+}
+
+impl Default for DensitySettings {
+    fn default() -> Self {
+        Self {
+            octaves: 4,
+            scale: 64,
+            persistence: ONE / 2,
+            bias: Vec::new(),
+        }
+    }
+}
+
+impl DensitySettings {
+
+    /// The height bias at `y`, in Q16
+    fn bias_at(&self, y: i64) -> i64 {
+        let (Some(&first), Some(&last)) = (self.bias.first(), self.bias.last()) else {
+            return 0;
+        };
+
+        if y <= first.0 {
+            return first.1;
+        }
+
+        if y >= last.0 {
+            return last.1;
+        }
+
+        let i = self.bias.partition_point(|&(py, _)| py <= y);
+        let ((y0, b0), (y1, b1)) = (self.bias[i - 1], self.bias[i]);
+
+        lerp(b0, b1, ((y - y0) << 16) / (y1 - y0))
+    }
+}
+
+/// A 3D density function for voxel terrain, combining several octaves of value noise with a
+/// height bias.
+///
+/// Positive densities are conventionally solid and negative densities empty. All values are
+/// Q16.16 fixed-point.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::noise::{DensityField, DensitySettings, ONE};
+///
+/// let world = RandomAccessRNG::new("world_seed");
+///
+/// let settings = DensitySettings {
+///     // Solid far below the surface at y = 64, air far above it
+///     bias: vec![(0, 4 * ONE), (64, 0), (128, -4 * ONE)],
+///     ..DensitySettings::default()
+/// };
+///
+/// let field = DensityField::new(&world.get("terrain"), settings);
+///
+/// assert!(field.density(10, 0, -30) > 0);
+/// assert!(field.density(10, 128, -30) < 0);
+///
+/// // Chunk order doesn't matter: values are pure functions of the coordinates
+/// let forwards: Vec<i64> = (0..16).map(|x| field.density(x, 64, 0)).collect();
+/// let backwards: Vec<i64> = (0..16).rev().map(|x| field.density(x, 64, 0)).collect();
+/// assert!(forwards.iter().eq(backwards.iter().rev()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DensityField {
+    settings: DensitySettings,
+    keys: Vec<u64>,
+}
+
+impl DensityField {
+
+    /// Create a density field from an RNG node, with one derived key per octave.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `settings.scale` is not positive.
+    pub fn new(rng: &RandomAccessRNG, settings: DensitySettings) -> Self {
+        assert!(settings.scale > 0, "scale must be positive");
+
+        let keys = (0..settings.octaves)
+            .map(|octave| rng.get(("octave", octave)).digest() as u64)
+            .collect();
+
+        Self { settings, keys }
+    }
+
+    /// Value noise for one octave, in `[-ONE, ONE)`
+    fn octave(&self, key: u64, size: i64, x: i64, y: i64, z: i64) -> i64 {
+        let (cx, tx) = cell(x, size);
+        let (cy, ty) = cell(y, size);
+        let (cz, tz) = cell(z, size);
+
+        let (fx, fy, fz) = (fade(tx), fade(ty), fade(tz));
+
+        let corner = |dx, dy, dz| lattice(key, cx + dx, cy + dy, cz + dz);
+
+        let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), fx);
+        let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), fx);
+        let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), fx);
+        let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), fx);
+
+        lerp(lerp(x00, x10, fy), lerp(x01, x11, fy), fz)
+    }
+
+    /// The density at a block coordinate, in Q16.
+    ///
+    /// Any number of octaves and any persistence are accepted, see [`DensitySettings`].
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::noise::{DensityField, DensitySettings, ONE};
+    ///
+    /// let settings = DensitySettings { octaves: 70, persistence: 4 * ONE, ..DensitySettings::default() };
+    /// let field = DensityField::new(&RandomAccessRNG::new("world_seed"), settings);
+    ///
+    /// assert_eq!(field.density(3, 4, 5), field.density(3, 4, 5));
+    /// ```
+    pub fn density(&self, x: i64, y: i64, z: i64) -> i64 {
+        let mut amplitude = ONE;
+        let mut total = 0i64;
+
+        for (octave, &key) in self.keys.iter().enumerate() {
+            let size = self.settings.scale.checked_shr(octave as u32).unwrap_or(0).max(1);
+
+            total = total.saturating_add(self.octave(key, size, x, y, z).saturating_mul(amplitude) >> 16);
+            amplitude = amplitude.saturating_mul(self.settings.persistence) >> 16;
+        }
+
+        total.saturating_add(self.settings.bias_at(y))
+    }
+}
+
+/// The density at a single block coordinate.
+///
+/// Equivalent to `DensityField::new(rng, settings.clone()).density(x, y, z)`. When sampling many
+/// coordinates, create a [`DensityField`] once instead, which avoids re-deriving the octave keys.
+pub fn density_3d(rng: &RandomAccessRNG, x: i64, y: i64, z: i64, settings: &DensitySettings) -> i64 {
+    DensityField::new(rng, settings.clone()).density(x, y, z)
+}
+
+/// Number of noise samples used to find the threshold for a [`Mask`]'s coverage
+const MASK_SAMPLES: i64 = 4096;
+
+/// Lattice size used by [`mask_2d`]
+const MASK_SCALE: i64 = 16;
+
+/// A 2D boolean mask covering a given fraction of the plane, for cave openings, ore veins and decals.
+///
+/// The mask is thresholded value noise, so covered points form smooth blobs roughly `scale`
+/// across. The threshold is chosen from a fixed sample of the noise so that on average a fraction
+/// `coverage` of points are covered. Every point is a pure function of its coordinates, so any
+/// sub-rectangle of a query gives exactly the same mask as querying it directly.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::noise::Mask;
+///
+/// let world = RandomAccessRNG::new("world_seed");
+/// let ore = Mask::new(&world.get("iron_veins"), 0.2, 8);
+///
+/// let big = ore.rect((0, 0), 256, 256);
+/// let covered = big.iter().filter(|&&b| b).count() as f64 / big.len() as f64;
+/// assert!((0.1..0.3).contains(&covered));
+///
+/// // A sub-rectangle matches the corresponding part of the larger query
+/// let small = ore.rect((100, 40), 16, 16);
+/// for y in 0..16 {
+///     for x in 0..16 {
+///         assert_eq!(small[y * 16 + x], big[(40 + y) * 256 + 100 + x]);
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Mask {
+    key: u64,
+    scale: i64,
+    threshold: i64,
+}
+
+impl Mask {
+
+    /// A mask derived from `rng` covering a fraction `coverage` of points, with blobs roughly
+    /// `scale` points across.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is not positive.
+    pub fn new(rng: &RandomAccessRNG, coverage: f64, scale: i64) -> Self {
+        assert!(scale > 0, "scale must be positive");
+
+        let key = rng.digest() as u64;
+        let coverage = coverage.clamp(0.0, 1.0);
+
+        // Sample the noise once per cell, far enough apart to be independent, at varying offsets
+        let mut samples: Vec<i64> = (0..MASK_SAMPLES)
+            .map(|i| value_2d(key, scale, i * scale * 3 + (i * 7) % scale, i * scale * 5 + (i * 13) % scale))
+            .collect();
+        samples.sort_unstable();
+
+        let threshold = if coverage <= 0.0 {
+            i64::MIN
+        } else if coverage >= 1.0 {
+            i64::MAX
+        } else {
+            samples[(coverage * MASK_SAMPLES as f64) as usize]
+        };
+
+        Self { key, scale, threshold }
+    }
+
+    /// Whether the point `(x, y)` is covered.
+    pub fn get(&self, x: i64, y: i64) -> bool {
+        value_2d(self.key, self.scale, x, y) < self.threshold
+    }
+
+    /// The `width` by `height` rectangle of the mask starting at `origin`, in row-major order.
+    pub fn rect(&self, origin: Point, width: usize, height: usize) -> Vec<bool> {
+        (0..height as i64)
+            .flat_map(|y| (0..width as i64).map(move |x| (origin.0 + x, origin.1 + y)))
+            .map(|(x, y)| self.get(x, y))
+            .collect()
+    }
+}
+
+/// A `width` by `height` mask covering roughly a fraction `coverage` of its points, in row-major
+/// order.
+///
+/// Shorthand for a [`Mask`] of the child of `rng` at `key`, with blobs about 16 points across,
+/// queried at the origin.
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::noise::mask_2d;
+///
+/// let world = RandomAccessRNG::new("world_seed");
+///
+/// let caves = mask_2d(&world, ("caves", 3, 7), 64, 64, 0.3);
+/// assert_eq!(caves.len(), 64 * 64);
+/// assert_eq!(caves, mask_2d(&world, ("caves", 3, 7), 64, 64, 0.3));
+/// ```
+pub fn mask_2d<H: Hash>(rng: &RandomAccessRNG, key: H, width: usize, height: usize, coverage: f64) -> Vec<bool> {
+    Mask::new(&rng.get(key), coverage, MASK_SCALE).rect((0, 0), width, height)
+}
+
+/// Single octave 2D value noise, in `[-ONE, ONE)`
+fn value_2d(key: u64, size: i64, x: i64, y: i64) -> i64 {
+    let (cx, tx) = cell(x, size);
+    let (cy, ty) = cell(y, size);
+
+    let (fx, fy) = (fade(tx), fade(ty));
+
+    let corner = |dx, dy| lattice(key, cx + dx, cy + dy, 0);
+
+    lerp(lerp(corner(0, 0), corner(1, 0), fx), lerp(corner(0, 1), corner(1, 1), fx), fy)
+}
@@ -0,0 +1,91 @@
+//! Splitting work over a pool of workers.
+//!
+//! Because any position of a stream can be reached directly, a range of indices can be generated
+//! by several workers at once and give exactly the values one worker would have. [`partition`]
+//! does the bookkeeping: it splits the range and hands each worker an RNG already positioned at
+//! the start of its share.
+
+use std::ops::Range;
+use crate::RandomAccessRNG;
+
+/// One worker's share of a [`partition`].
+#[derive(Clone)]
+pub struct Share {
+    /// The worker number, from 0
+    pub worker: usize,
+    /// The indices this worker is responsible for
+    pub indices: Range<u64>,
+    /// An RNG whose next value is the value at `indices.start`
+    pub rng: RandomAccessRNG,
+}
+
+/// Split `indices` into `workers` contiguous shares, each with an RNG positioned at its start.
+///
+/// Shares are in order, cover every index exactly once, and differ in length by at most one, with
+/// the longer shares first. Drawing `indices.len()` values in order from each share's RNG and
+/// concatenating them gives the same values as drawing them all from one RNG positioned at
+/// `indices.start`, whatever the number of workers. Workers that get no indices still get a share,
+/// with an empty range.
+///
+/// Each RNG is a copy of `rng` moved to the start of its share, so its first value is the value
+/// at that index. This differs from calling [`seek_u64`](RandomAccessRNG::seek_u64) with the
+/// start index, which consumes that value itself.
+///
+/// # Panics
+///
+/// Panics if `workers` is zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::parallel::partition;
+/// use rand_core::RngCore;
+/// use std::thread;
+///
+/// let terrain = RandomAccessRNG::new("world_seed").get("terrain");
+///
+/// let generate = |workers| {
+///     let handles: Vec<_> = partition(&terrain, 1000..11000, workers)
+///         .into_iter()
+///         .map(|mut share| thread::spawn(move || {
+///             share.indices.map(|_| share.rng.next_u64()).collect::<Vec<u64>>()
+///         }))
+///         .collect();
+///
+///     handles.into_iter().flat_map(|h| h.join().unwrap()).collect::<Vec<u64>>()
+/// };
+///
+/// // The same values for any number of workers
+/// let single = generate(1);
+/// for workers in 2..=8 {
+///     assert_eq!(generate(workers), single);
+/// }
+///
+/// assert_eq!(single[0], terrain.peek_at(1000));
+/// ```
+pub fn partition(rng: &RandomAccessRNG, indices: Range<u64>, workers: usize) -> Vec<Share> {
+    assert!(workers > 0, "cannot partition over zero workers");
+
+    let len = indices.end.saturating_sub(indices.start);
+    let (base, extra) = (len / workers as u64, len % workers as u64);
+    let mut start = indices.start;
+
+    (0..workers)
+        .map(|worker| {
+            let size = base + ((worker as u64) < extra) as u64;
+            let mut share_rng = rng.clone();
+            share_rng.index = start as u128;
+
+            let share = Share {
+                worker,
+                indices: start..start + size,
+                rng: share_rng,
+            };
+
+            start += size;
+
+            share
+        })
+        .collect()
+}
@@ -0,0 +1,147 @@
+//! Random access permutations of `0..len`.
+//!
+//! A [`Permutation`] shuffles the integers `0..len` without ever materialising the shuffled
+//! sequence: the element at any position (and the position of any element) is computed directly,
+//! in constant time and memory. This makes it possible to deal a deck of any size, or visit every
+//! cell of a huge grid exactly once in a random order, while keeping the crate's random access
+//! guarantees.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::permutation::Permutation;
+//!
+//! let deck = Permutation::new(&RandomAccessRNG::new("seed").get("deck"), 52);
+//!
+//! // Every card appears exactly once
+//! let mut cards: Vec<u64> = deck.iter().collect();
+//! cards.sort();
+//! assert_eq!(cards, (0..52).collect::<Vec<_>>());
+//!
+//! // And the 40th card dealt can be found without dealing the first 39
+//! let card = deck.get(39);
+//! assert_eq!(deck.position(card), 39);
+//! ```
+
+use crate::RandomAccessRNG;
+use crate::random_access_rng::xxh3_integer_hash;
+
+/// Number of Feistel rounds, enough for statistically well mixed permutations
+const ROUNDS: u128 = 6;
+
+/// A keyed pseudo-random permutation of `0..len` with random access in both directions.
+///
+/// Internally this is a balanced Feistel network over the smallest even number of bits covering
+/// `len`, with cycle walking to stay inside the range. The permutation is entirely determined by
+/// the RNG node it was created from and its length.
+#[derive(Clone, Debug)]
+pub struct Permutation {
+    key: u128,
+    len: u64,
+    half_bits: u32,
+}
+
+impl Permutation {
+
+    /// Create the permutation of `0..len` belonging to the given RNG node.
+    ///
+    /// The position of the RNG in its stream is ignored; two RNGs for the same node always give
+    /// the same permutation. Use a dedicated child node (for example `rng.get("deck")`) so the
+    /// permutation is independent of other values drawn from the same node.
+    pub fn new(rng: &RandomAccessRNG, len: u64) -> Self {
+        Self::from_key(rng.digest(), len)
+    }
+
+    /// Create a permutation from a raw 128-bit key
+    pub(crate) fn from_key(key: u128, len: u64) -> Self {
+        let bits = (64 - len.saturating_sub(1).leading_zeros()).max(2);
+
+        Self {
+            key,
+            len,
+            half_bits: bits.div_ceil(2),
+        }
+    }
+
+    /// Number of elements in the permutation.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if the permutation has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn mask(&self) -> u64 {
+        (1u64 << self.half_bits) - 1
+    }
+
+    /// Round function of the Feistel network
+    fn round(&self, round: u128, half: u64) -> u64 {
+        xxh3_integer_hash(self.key ^ (round << 64 | half as u128)) as u64 & self.mask()
+    }
+
+    /// One full pass of the network over the power-of-four domain
+    fn encrypt(&self, value: u64) -> u64 {
+        let mut left = value >> self.half_bits;
+        let mut right = value & self.mask();
+
+        for round in 0..ROUNDS {
+            (left, right) = (right, left ^ self.round(round, right));
+        }
+
+        left << self.half_bits | right
+    }
+
+    /// Inverse of [`encrypt`](Permutation::encrypt)
+    fn decrypt(&self, value: u64) -> u64 {
+        let mut left = value >> self.half_bits;
+        let mut right = value & self.mask();
+
+        for round in (0..ROUNDS).rev() {
+            (left, right) = (right ^ self.round(round, left), left);
+        }
+
+        left << self.half_bits | right
+    }
+
+    /// The element at position `index` of the shuffled sequence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`len`](Permutation::len).
+    pub fn get(&self, index: u64) -> u64 {
+        assert!(index < self.len, "index {} out of range for permutation of length {}", index, self.len);
+
+        // Cycle walk until we land back inside the range
+        let mut value = self.encrypt(index);
+
+        while value >= self.len {
+            value = self.encrypt(value);
+        }
+
+        value
+    }
+
+    /// The position at which `value` appears in the shuffled sequence, the inverse of [`get`](Permutation::get).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is not less than [`len`](Permutation::len).
+    pub fn position(&self, value: u64) -> u64 {
+        assert!(value < self.len, "value {} out of range for permutation of length {}", value, self.len);
+
+        let mut index = self.decrypt(value);
+
+        while index >= self.len {
+            index = self.decrypt(index);
+        }
+
+        index
+    }
+
+    /// Iterate over the shuffled sequence in order.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        (0..self.len).map(|i| self.get(i))
+    }
+}
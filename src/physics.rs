@@ -0,0 +1,121 @@
+//! Replay-stable jitter values for physics engines.
+//!
+//! Physics engines that add a little randomness (restitution jitter, contact dithering,
+//! solver ordering noise) must still produce bit-identical results on every peer of a
+//! lockstep simulation. Floating point randomness is easy to get subtly wrong, so this
+//! module derives every value purely from a `(body id, step index)` pair and returns it
+//! as an explicit fixed-point integer.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::physics::PhysicsJitter;
+//!
+//! let world = RandomAccessRNG::new("match_seed");
+//! let jitter = PhysicsJitter::new(world.get("physics"));
+//!
+//! // Q16 fixed-point value in [-1, 1) for body 7 at simulation step 1200
+//! let restitution_noise = jitter.fixed(7u64, 1200, 16);
+//! assert!((-(1 << 16)..(1 << 16)).contains(&restitution_noise));
+//!
+//! // The same (body, step) pair always yields the same value, in any order
+//! assert_eq!(restitution_noise, jitter.fixed(7u64, 1200, 16));
+//! ```
+
+use std::hash::Hash;
+use rand_core::RngCore;
+use crate::RandomAccessRNG;
+
+/// Source of per-body, per-step jitter values.
+///
+/// Each body gets its own child RNG (derived with [`get`](RandomAccessRNG::get)) and the step
+/// index is used as the position in that child's stream, so values can be queried in any order
+/// and on any peer without sharing state.
+#[derive(Clone)]
+pub struct PhysicsJitter {
+    rng: RandomAccessRNG,
+}
+
+impl PhysicsJitter {
+
+    /// Create a jitter source rooted at the given RNG node.
+    ///
+    /// Use a dedicated node (for example `world.get("physics")`) so jitter values never overlap
+    /// with other consumers of the parent's stream.
+    pub fn new(rng: RandomAccessRNG) -> Self {
+        Self { rng }
+    }
+
+    /// The raw 64-bit value for a body at a given step.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::physics::PhysicsJitter;
+    /// use rand_core::RngCore;
+    ///
+    /// let root = RandomAccessRNG::new("seed");
+    /// let jitter = PhysicsJitter::new(root.clone());
+    ///
+    /// // Equivalent to seeking the body's child stream
+    /// assert_eq!(jitter.raw("crate_12", 5), root.get("crate_12").seek_u64(5));
+    /// ```
+    pub fn raw<H: Hash>(&self, body: H, step: u64) -> u64 {
+        self.rng.get(body).seek_u64(step)
+    }
+
+    /// A signed fixed-point value in `[-1, 1)` with `frac_bits` fractional bits.
+    ///
+    /// The result lies in `-(1 << frac_bits)..(1 << frac_bits)`, so with `frac_bits = 16` the
+    /// output is a Q16 number ready to be scaled by an amplitude using integer arithmetic.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frac_bits` is greater than 62.
+    pub fn fixed<H: Hash>(&self, body: H, step: u64, frac_bits: u32) -> i64 {
+        assert!(frac_bits <= 62, "frac_bits must be at most 62");
+
+        // Arithmetic shift keeps the top bits, which is the most significant part of the draw
+        (self.raw(body, step) as i64) >> (63 - frac_bits)
+    }
+
+    /// A signed integer offset in `[-amplitude, amplitude]`, uniformly distributed.
+    ///
+    /// Useful when jitter is expressed directly in the engine's own fixed-point units.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::physics::PhysicsJitter;
+    ///
+    /// let jitter = PhysicsJitter::new(RandomAccessRNG::new("seed"));
+    ///
+    /// for step in 0..100 {
+    ///     let offset = jitter.offset(3u32, step, 250);
+    ///     assert!((-250..=250).contains(&offset));
+    /// }
+    ///
+    /// assert_eq!(jitter.offset(3u32, 0, 0), 0);
+    /// ```
+    pub fn offset<H: Hash>(&self, body: H, step: u64, amplitude: u32) -> i64 {
+        let span = 2 * amplitude as u128 + 1;
+
+        // Multiply-high maps the full 64-bit draw onto the span without a modulo
+        let scaled = (self.raw(body, step) as u128 * span) >> 64;
+
+        scaled as i64 - amplitude as i64
+    }
+
+    /// Fill `out` with consecutive raw values for a body starting at `step`.
+    ///
+    /// This derives the body's child RNG once, which is cheaper than calling
+    /// [`raw`](PhysicsJitter::raw) repeatedly when processing several steps at a time.
+    pub fn fill_steps<H: Hash>(&self, body: H, step: u64, out: &mut [u64]) {
+        let mut child = self.rng.get(body);
+
+        if let Some((first, rest)) = out.split_first_mut() {
+            *first = child.seek_u64(step);
+
+            for value in rest {
+                *value = child.next_u64();
+            }
+        }
+    }
+}
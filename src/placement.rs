@@ -0,0 +1,117 @@
+//! Placement of features such as trees, rocks and ores in a region.
+//!
+//! Scattering features usually needs two things at once: a minimum spacing, so features don't
+//! overlap, and a weighted choice of what each feature is. Doing the choice after spacing (or
+//! with a separate RNG) is easy to get subtly wrong, for example by drawing types only for
+//! accepted points, which makes every type depend on every earlier rejection.
+//! [`place_features`] does both in one pass with a fixed stream layout.
+
+use std::collections::HashMap;
+use crate::RandomAccessRNG;
+use crate::dist::Draws;
+use crate::polyline::Point;
+
+/// An axis-aligned rectangle of grid points, from `min` inclusive to `max` exclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Region {
+    /// The lowest corner, inclusive
+    pub min: Point,
+    /// The highest corner, exclusive
+    pub max: Point,
+}
+
+impl Region {
+
+    /// The region from `min` inclusive to `max` exclusive.
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+}
+
+/// A placed feature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Feature {
+    /// Where the feature was placed
+    pub position: Point,
+    /// The index of the feature's type in the weights passed to [`place_features`]
+    pub kind: usize,
+}
+
+/// Place features in `region` at least `min_dist` apart, choosing each feature's type in
+/// proportion to `weights`.
+///
+/// This is dart throwing Poisson-disk sampling: `candidates` points are drawn uniformly in the
+/// region, in order, and each is kept if it is at least `min_dist` (Euclidean) from every point
+/// kept before it. Candidate `i` always uses positions `3i`, `3i + 1` and `3i + 2` of the stream
+/// of `rng` for its x coordinate, y coordinate and type, whether or not it is kept, so the type of
+/// a feature doesn't depend on which earlier candidates were rejected.
+///
+/// Results depend on the region, so regions are the unit of determinism: generate each chunk as
+/// its own region from its own node, for example `world.get(("trees", chunk_x, chunk_y))`.
+///
+/// # Panics
+///
+/// Panics if the region is empty, or if the weights are empty or sum to zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::placement::{Region, place_features};
+///
+/// let world = RandomAccessRNG::new("world_seed");
+///
+/// // Oaks are four times as common as pines, and no two trees are closer than 5
+/// let weights = [4, 1];
+/// let region = Region::new((0, 0), (64, 64));
+///
+/// let trees = place_features(&world.get(("trees", 0, 0)), region, 200, 5, &weights);
+///
+/// for (i, a) in trees.iter().enumerate() {
+///     for b in &trees[i + 1..] {
+///         let (dx, dy) = (a.position.0 - b.position.0, a.position.1 - b.position.1);
+///         assert!(dx * dx + dy * dy >= 25);
+///     }
+/// }
+///
+/// assert_eq!(trees, place_features(&world.get(("trees", 0, 0)), region, 200, 5, &weights));
+/// ```
+pub fn place_features(rng: &RandomAccessRNG, region: Region, candidates: usize, min_dist: i64, weights: &[u64]) -> Vec<Feature> {
+    let (width, height) = (region.max.0 - region.min.0, region.max.1 - region.min.1);
+    assert!(width > 0 && height > 0, "region must not be empty");
+    assert!(weights.iter().any(|&w| w > 0), "at least one feature type must have a non-zero weight");
+
+    let mut stream = rng.clone();
+    stream.index = 0;
+    let mut draws = Draws::new(&mut stream);
+
+    // Accepted features bucketed into cells of side min_dist, so only neighbouring cells need checking
+    let cell_size = min_dist.max(1);
+    let cell = |(x, y): Point| (x.div_euclid(cell_size), y.div_euclid(cell_size));
+    let mut grid: HashMap<Point, Vec<usize>> = HashMap::new();
+    let mut features: Vec<Feature> = Vec::new();
+
+    for _ in 0..candidates {
+        let x = region.min.0 + draws.below(width as u64) as i64;
+        let y = region.min.1 + draws.below(height as u64) as i64;
+        let kind = draws.weighted_index(weights);
+
+        let (cx, cy) = cell((x, y));
+        let too_close = (cx - 1..=cx + 1)
+            .flat_map(|nx| (cy - 1..=cy + 1).map(move |ny| (nx, ny)))
+            .filter_map(|neighbour| grid.get(&neighbour))
+            .flatten()
+            .any(|&i| {
+                let (px, py) = features[i].position;
+                let (dx, dy) = ((px - x) as i128, (py - y) as i128);
+                dx * dx + dy * dy < (min_dist as i128) * (min_dist as i128)
+            });
+
+        if !too_close {
+            grid.entry((cx, cy)).or_default().push(features.len());
+            features.push(Feature { position: (x, y), kind });
+        }
+    }
+
+    features
+}
@@ -0,0 +1,124 @@
+//! Deterministic jittered polylines for procedural rivers, roads and borders.
+//!
+//! A straight polyline is turned into a natural looking path by repeated midpoint displacement.
+//! All arithmetic is integer, and each segment is keyed by its own endpoints rather than its
+//! position in the polyline, so a segment that crosses a chunk boundary regenerates identically
+//! from either chunk, without knowing about the rest of the path.
+
+use crate::RandomAccessRNG;
+
+/// A point on an integer grid
+pub type Point = (i64, i64);
+
+/// Uniform offset in `[-amplitude, amplitude]` from a 64-bit draw, zero for non-positive amplitudes
+fn offset(draw: u64, amplitude: i64) -> i128 {
+    let amplitude = amplitude.max(0);
+    let span = 2 * amplitude as u128 + 1;
+
+    ((draw as u128 * span) >> 64) as i128 - amplitude as i128
+}
+
+/// Recursively displace the midpoint of `a`-`b`, pushing every point except `b` onto `out`
+fn subdivide(rng: &mut RandomAccessRNG, a: Point, b: Point, amplitude: i64, depth: u32, node: u64, out: &mut Vec<Point>) {
+    if depth == 0 {
+        out.push(a);
+        return;
+    }
+
+    let (dx, dy) = ((b.0 - a.0) as i128, (b.1 - a.1) as i128);
+    let length = (dx * dx + dy * dy) as u128;
+    let length = length.isqrt() as i128;
+
+    // Node numbering follows a binary heap, so every midpoint has a fixed position in the stream
+    let shift = offset(rng.seek_u64(node), amplitude);
+
+    let (mut mx, mut my) = ((a.0 as i128 + b.0 as i128) / 2, (a.1 as i128 + b.1 as i128) / 2);
+
+    if length > 0 {
+        // Displace along the segment's normal
+        mx += -dy * shift / length;
+        my += dx * shift / length;
+    }
+
+    let mid = (mx as i64, my as i64);
+
+    subdivide(rng, a, mid, amplitude / 2, depth - 1, 2 * node, out);
+    subdivide(rng, mid, b, amplitude / 2, depth - 1, 2 * node + 1, out);
+}
+
+/// Jitter a single segment from `a` to `b`, returning `2^depth + 1` points including both ends.
+///
+/// The segment's randomness comes from `rng.get((min(a, b), max(a, b)))`, so the result only
+/// depends on the RNG node and the endpoints. Reversing the endpoints reverses the result.
+/// Midpoints are displaced along the segment's normal by up to `amplitude`, halving at each level.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::polyline::jitter_segment;
+///
+/// let rivers = RandomAccessRNG::new("world_seed").get("rivers");
+///
+/// let forward = jitter_segment(&rivers, (0, 0), (1000, 0), 100, 4);
+/// assert_eq!(forward.len(), 17);
+/// assert_eq!(forward.first(), Some(&(0, 0)));
+/// assert_eq!(forward.last(), Some(&(1000, 0)));
+///
+/// // Direction does not matter
+/// let mut backward = jitter_segment(&rivers, (1000, 0), (0, 0), 100, 4);
+/// backward.reverse();
+/// assert_eq!(forward, backward);
+/// ```
+pub fn jitter_segment(rng: &RandomAccessRNG, a: Point, b: Point, amplitude: i64, depth: u32) -> Vec<Point> {
+    let (start, end) = if a <= b { (a, b) } else { (b, a) };
+
+    let mut child = rng.get((start, end));
+    let mut out = Vec::with_capacity((1 << depth.min(20)) + 1);
+
+    subdivide(&mut child, start, end, amplitude, depth, 1, &mut out);
+    out.push(end);
+
+    if a > b {
+        out.reverse();
+    }
+
+    out
+}
+
+/// Jitter every segment of a polyline, keeping the original vertices in place.
+///
+/// Each segment is jittered with [`jitter_segment`], so any part of the path can be regenerated
+/// on its own (for example only the segments intersecting the current chunk) with identical
+/// results.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::polyline::{jitter_path, jitter_segment};
+///
+/// let roads = RandomAccessRNG::new("world_seed").get("roads");
+/// let road = [(0, 0), (500, 200), (900, 900)];
+///
+/// let full = jitter_path(&roads, &road, 40, 3);
+/// assert_eq!(full.len(), 2 * 8 + 1);
+///
+/// // The second segment on its own matches the second half of the full path
+/// let second = jitter_segment(&roads, (500, 200), (900, 900), 40, 3);
+/// assert_eq!(&full[8..], second.as_slice());
+/// ```
+pub fn jitter_path(rng: &RandomAccessRNG, points: &[Point], amplitude: i64, depth: u32) -> Vec<Point> {
+    let mut out = Vec::new();
+
+    for pair in points.windows(2) {
+        let segment = jitter_segment(rng, pair[0], pair[1], amplitude, depth);
+
+        // Shared vertices are only emitted once
+        out.extend_from_slice(&segment[..segment.len() - 1]);
+    }
+
+    out.extend(points.last());
+
+    out
+}
@@ -0,0 +1,137 @@
+//! Reusable per-task RNGs for job systems.
+//!
+//! A job system spawning thousands of short-lived generation tasks per frame needs a distinct,
+//! reproducible RNG for each of them. [`RngPool`] numbers tasks with a counter and gives task `n`
+//! the child `root.get(n)`, writing its hashing state over the state of a released RNG so that
+//! steady-state spawning doesn't allocate.
+
+use std::ops::{Deref, DerefMut};
+use crate::RandomAccessRNG;
+
+/// An RNG handed out by an [`RngPool`] for a single task.
+///
+/// Dereferences to the task's [`RandomAccessRNG`]. Return it with [`RngPool::release`] when the
+/// task is done so its hashing state can be reused.
+pub struct PooledRng {
+    task: u64,
+    rng: RandomAccessRNG,
+}
+
+impl PooledRng {
+
+    /// The task number this RNG was derived for.
+    pub fn task(&self) -> u64 {
+        self.task
+    }
+}
+
+impl Deref for PooledRng {
+    type Target = RandomAccessRNG;
+
+    fn deref(&self) -> &RandomAccessRNG {
+        &self.rng
+    }
+}
+
+impl DerefMut for PooledRng {
+    fn deref_mut(&mut self) -> &mut RandomAccessRNG {
+        &mut self.rng
+    }
+}
+
+/// A pool handing out child RNGs keyed by a task counter.
+///
+/// Task numbers are assigned in the order RNGs are [`acquire`](RngPool::acquire)d, so as long as
+/// tasks are spawned in a deterministic order (typically on one thread, before being distributed
+/// to workers) every task gets the same RNG on every run, however the work is scheduled.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::pool::RngPool;
+/// use rand_core::RngCore;
+///
+/// let frame = RandomAccessRNG::new("world_seed").get(("frame", 42));
+/// let mut pool = RngPool::with_capacity(frame.clone(), 64);
+///
+/// for _ in 0..1000 {
+///     let mut rng = pool.acquire();
+///
+///     // Each task gets the child keyed by its number
+///     assert_eq!(rng.next_u64(), frame.get(rng.task()).next_u64());
+///
+///     pool.release(rng);
+/// }
+///
+/// assert_eq!(pool.next_task(), 1000);
+/// ```
+pub struct RngPool {
+    root: RandomAccessRNG,
+    next_task: u64,
+    free: Vec<RandomAccessRNG>,
+}
+
+impl RngPool {
+
+    /// Create an empty pool deriving task RNGs from `root`, starting at task 0.
+    pub fn new(root: RandomAccessRNG) -> Self {
+        Self::with_capacity(root, 0)
+    }
+
+    /// Create a pool with `capacity` RNGs allocated up front.
+    pub fn with_capacity(root: RandomAccessRNG, capacity: usize) -> Self {
+        let free = (0..capacity).map(|_| root.unshared()).collect();
+
+        Self {
+            root,
+            next_task: 0,
+            free,
+        }
+    }
+
+    /// The RNG for a task, as handed out by [`acquire`](RngPool::acquire).
+    pub fn task_rng(&self, task: u64) -> RandomAccessRNG {
+        self.root.get(task)
+    }
+
+    /// Hand out the RNG for the next task, reusing a released RNG's hashing state if one is
+    /// available.
+    pub fn acquire(&mut self) -> PooledRng {
+        let task = self.next_task;
+        let rng = match self.free.pop() {
+            Some(mut rng) => {
+                rng.become_child(&self.root, task);
+                rng
+            }
+            None => self.task_rng(task),
+        };
+
+        self.next_task += 1;
+
+        PooledRng { task, rng }
+    }
+
+    /// Return a task's RNG to the pool for reuse.
+    ///
+    /// RNGs from other pools are accepted too, since only the hashing state is kept. A state still
+    /// shared with a clone of the RNG can't be overwritten, so reusing it allocates a new one.
+    pub fn release(&mut self, rng: PooledRng) {
+        self.free.push(rng.rng);
+    }
+
+    /// The number of the task the next [`acquire`](RngPool::acquire) will hand out.
+    pub fn next_task(&self) -> u64 {
+        self.next_task
+    }
+
+    /// Restart task numbering at `task`, for example at the start of each frame.
+    pub fn set_next_task(&mut self, task: u64) {
+        self.next_task = task;
+    }
+
+    /// The number of released RNGs waiting to be reused.
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+}
@@ -0,0 +1,72 @@
+//! Adapters for crates still on `rand` 0.8 and `rand_core` 0.6.
+//!
+//! The RNGs of this crate implement the `rand_core` 0.9 traits, and with them `TryRngCore`, so
+//! they work with `rand` 0.9 directly. Crates that haven't migrated yet expect the 0.6 generation
+//! of [`RngCore`](rand_core_06::RngCore) and [`SeedableRng`](rand_core_06::SeedableRng), whose
+//! methods differ. [`Rand06`] wraps any 0.9 RNG and implements the 0.6 traits by delegating to
+//! it, so the values drawn are exactly those of the wrapped RNG.
+//!
+//! Requires the `rand_core_06` feature.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::rand06::Rand06;
+//! use rand_core::RngCore;
+//!
+//! // A function from a crate built against rand_core 0.6
+//! fn roll<R: rand_core_06::RngCore>(rng: &mut R) -> u64 {
+//!     rng.next_u64() % 6 + 1
+//! }
+//!
+//! let dice = RandomAccessRNG::new("match_seed").get("dice");
+//!
+//! let mut adapted = Rand06(dice.clone());
+//! let rolled = roll(&mut adapted);
+//!
+//! assert_eq!(rolled, dice.clone().next_u64() % 6 + 1);
+//! assert_eq!(adapted.0.position(), 1);
+//! ```
+
+use rand_core::{RngCore, SeedableRng};
+
+/// An RNG implementing the `rand_core` 0.9 traits, adapted to the 0.6 traits, see the
+/// [module documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rand06<R>(pub R);
+
+impl<R: RngCore> rand_core_06::RngCore for Rand06<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    /// Never fails, since the wrapped RNG can't.
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core_06::Error> {
+        self.0.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Seeds exactly as the wrapped RNG's 0.9 [`SeedableRng`] implementation does, so
+/// `Rand06::<R>::seed_from_u64(n)` wraps `R::seed_from_u64(n)`.
+impl<R: SeedableRng> rand_core_06::SeedableRng for Rand06<R>
+where
+    R::Seed: Sized + Default + AsMut<[u8]>,
+{
+    type Seed = R::Seed;
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self(R::from_seed(seed))
+    }
+
+    fn seed_from_u64(state: u64) -> Self {
+        Self(R::seed_from_u64(state))
+    }
+}
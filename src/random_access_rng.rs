@@ -1,12 +1,250 @@
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use rand_core::{RngCore, impls, SeedableRng};
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::fmt;
+use std::io::{self, Read};
+use std::ops::Range;
 use std::path::{Component, Path};
-use xxhash_rust::xxh3::{Xxh3, xxh3_128};
+use std::sync::Arc;
+use xxhash_rust::xxh3::{Xxh3, xxh3_128, xxh3_64_with_seed};
 
-fn xxh3_integer_hash(n: u128) -> u128 {
+/// Seed separating content IDs from the value stream, "content_" in ASCII
+const CONTENT_ID_DOMAIN: u64 = 0x636f6e74656e745f;
+
+/// Domain separating combined seeds from plain ones, "combine_" in ASCII
+const COMBINE_DOMAIN: u64 = 0x636f6d62696e655f;
+
+/// Domain separating 2D coordinate children from other prehashed keys, "coord2d_" in ASCII
+const COORD_2D_DOMAIN: u64 = 0x636f6f726432645f;
+
+/// Domain separating 3D coordinate children from other prehashed keys, "coord3d_" in ASCII
+const COORD_3D_DOMAIN: u64 = 0x636f6f726433645f;
+
+/// Domain separating sub-streams from other prehashed keys, "substrm_" in ASCII
+const SUBSTREAM_DOMAIN: u64 = 0x7375627374726d5f;
+
+pub(crate) fn xxh3_integer_hash(n: u128) -> u128 {
     xxh3_128(n.to_le_bytes().as_slice())
 }
 
+/// A path component as a key, hashed losslessly whether or not it is valid UTF-8
+pub(crate) struct ComponentKey<'a>(Cow<'a, [u8]>);
+
+impl Hash for ComponentKey<'_> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        // Matches the Hash implementation of str for valid UTF-8
+        state.write(&self.0);
+        state.write_u8(0xff);
+    }
+}
+
+/// The bytes of a path component with `%2F`, `%5C` and `%25` decoded, or `None` if it contains
+/// any other `%` sequence
+fn unescape_component(component: &OsStr) -> Option<Cow<'_, [u8]>> {
+    let bytes = component.as_encoded_bytes();
+
+    if !bytes.contains(&b'%') {
+        return Some(Cow::Borrowed(bytes));
+    }
+
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut rest = bytes;
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte != b'%' {
+            decoded.push(byte);
+            rest = tail;
+            continue;
+        }
+
+        let escaped = match tail {
+            [b'2', b'F' | b'f', ..] => b'/',
+            [b'5', b'C' | b'c', ..] => b'\\',
+            [b'2', b'5', ..] => b'%',
+            _ => return None,
+        };
+
+        decoded.push(escaped);
+        rest = &tail[2..];
+    }
+
+    Some(Cow::Owned(decoded))
+}
+
+/// The keys of the components of `path`, decoding escapes if `unescape` is set, or the error
+/// [`RandomAccessRNG::try_path`] gives for it
+pub(crate) fn path_keys(path: &Path, unescape: bool) -> Result<impl Iterator<Item = ComponentKey<'_>>, PathError> {
+    for component in path.components() {
+        match component {
+            Component::Normal(c) if unescape && unescape_component(c).is_none() => {
+                return Err(PathError::InvalidEscape(c.to_string_lossy().into_owned()));
+            },
+            Component::Normal(_) | Component::RootDir => {},
+            Component::Prefix(p) => return Err(PathError::Prefix(p.as_os_str().to_string_lossy().into_owned())),
+            Component::CurDir => return Err(PathError::CurDir),
+            Component::ParentDir => return Err(PathError::ParentDir),
+        }
+    }
+
+    Ok(path
+        .components()
+        .filter_map(move |component| match component {
+            Component::Normal(c) if unescape => Some(ComponentKey(unescape_component(c).unwrap())),
+            Component::Normal(c) => Some(ComponentKey(Cow::Borrowed(c.as_encoded_bytes()))),
+            _ => None,
+        }))
+}
+
+/// Version of the scheme used to derive nodes and generate values.
+///
+/// The values produced by a given scheme never change between releases. Improvements that would
+/// change them are introduced as new schemes instead, which must be opted into with
+/// [`RandomAccessRNG::with_scheme`]. [`RandomAccessRNG::new`] always uses [`Scheme::V1`], and
+/// children inherit the scheme of their parent. Each scheme includes the changes of the schemes
+/// before it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Scheme {
+    /// The original scheme.
+    #[default]
+    V1,
+    /// Compacts the hasher to a 128-bit digest at every level of derivation, so the cost of
+    /// deriving and drawing from a node doesn't depend on its depth.
+    V2,
+    /// [`fill_bytes`](RngCore::fill_bytes) uses all 16 bytes of the value at each position, in
+    /// little endian order, instead of 8, halving the hashing needed for byte output.
+    ///
+    /// ```rust
+    /// use random_access_rng::{RandomAccessRNG, Scheme};
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::with_scheme("seed", Scheme::V3);
+    ///
+    /// let mut bytes = [0u8; 20];
+    /// rng.fill_bytes(&mut bytes);
+    ///
+    /// // Two positions were used, the second only in part
+    /// assert_eq!(rng.position(), 2);
+    /// assert_eq!(bytes[..16], rng.seek_u128(0).to_le_bytes());
+    /// assert_eq!(bytes[16..], rng.seek_u128(1).to_le_bytes()[..4]);
+    /// ```
+    V3,
+    /// 64 and 32-bit draws take half of a 128-bit hash each, so one hash serves two consecutive
+    /// positions and sequential [`next_u64`](RngCore::next_u64) calls need half as many hashes.
+    ///
+    /// The 64-bit value at position `n` is the low half of a hash for even `n` and the high half
+    /// for odd `n`, and the last hash is buffered so the following position reuses it. Every
+    /// position still holds exactly one value, so seeking and peeking work as before. The hashes
+    /// come from the upper half of the position space, so they never overlap the values of
+    /// [`next_u128`](RandomAccessRNG::next_u128) and [`fill_bytes`](RngCore::fill_bytes) below
+    /// position 2^127, which are unchanged.
+    ///
+    /// ```rust
+    /// use random_access_rng::{RandomAccessRNG, Scheme};
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::with_scheme("seed", Scheme::V4);
+    ///
+    /// let values: Vec<u64> = (0..10).map(|_| rng.next_u64()).collect();
+    ///
+    /// assert_eq!(rng.seek_u64(7), values[7]);
+    /// assert_eq!(rng.peek_at(4), values[4]);
+    /// assert_eq!(rng.next_u64(), values[8]);
+    /// ```
+    V4,
+    /// [`path`](RandomAccessRNG::path) and [`try_path`](RandomAccessRNG::try_path) decode the
+    /// escapes `%2F`, `%5C` and `%25` in each component to `/`, `\` and `%`, so a key containing
+    /// a separator can be written in a path with [`escape_path_key`](crate::key::escape_path_key).
+    /// Any other `%` in a component is an error, [`PathError::InvalidEscape`].
+    ///
+    /// ```rust
+    /// use random_access_rng::{RandomAccessRNG, Scheme};
+    /// use random_access_rng::key::escape_path_key;
+    ///
+    /// let root = RandomAccessRNG::with_scheme("seed", Scheme::V5);
+    ///
+    /// let key = "either/or 100%";
+    /// let path = format!("quotes/{}", escape_path_key(key));
+    ///
+    /// assert_eq!(root.path(&path).digest(), root.get("quotes").get(key).digest());
+    /// ```
+    V5,
+    /// 32 and 8-bit draws take a quarter or a sixteenth of a 128-bit hash each, so sequential
+    /// [`next_u32`](RngCore::next_u32) and [`next_u8`](RandomAccessRNG::next_u8) calls use every
+    /// bit of a hash instead of discarding the rest of it.
+    ///
+    /// This extends the packing of [`Scheme::V4`] to narrower draws. The 32-bit value at position
+    /// `n` is quarter `n % 4` of a hash, lowest first, and the 8-bit value is byte `n % 16` of
+    /// another, with the last hash buffered as for 64-bit draws. Every draw still consumes exactly
+    /// one position, so seeking and peeking work as before, but a 32-bit value is no longer the
+    /// low half of the 64-bit value at the same position. Each width has its own range of hashes
+    /// in the upper positions, so they never overlap each other, and only overlap the values of
+    /// [`next_u128`](RandomAccessRNG::next_u128) above position 2^124.
+    ///
+    /// ```rust
+    /// use random_access_rng::{RandomAccessRNG, Scheme};
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::with_scheme("seed", Scheme::V6);
+    ///
+    /// let values: Vec<u32> = (0..8).map(|_| rng.next_u32()).collect();
+    ///
+    /// // Four consecutive draws are the four quarters of one hash
+    /// let hash = rng.seek_raw(1 << 126);
+    /// assert_eq!(values[..4], [hash as u32, (hash >> 32) as u32, (hash >> 64) as u32, (hash >> 96) as u32]);
+    ///
+    /// assert_eq!(rng.seek_u32(6), values[6]);
+    /// assert_eq!(rng.next_u32(), values[7]);
+    /// ```
+    V6,
+}
+
+impl Scheme {
+
+    /// Finish one level of derivation after a key has been hashed into `hasher`
+    fn finish_level(self, hasher: Xxh3) -> Xxh3 {
+        match self {
+            Scheme::V1 => hasher,
+            _ => {
+                let mut compacted = Xxh3::new();
+                compacted.update(&hasher.digest128().to_le_bytes());
+                compacted
+            }
+        }
+    }
+}
+
+/// Error returned by [`RandomAccessRNG::try_path`] for a path component that can't be used as a key.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathError {
+    /// The path starts with a current directory component (`.`)
+    CurDir,
+    /// The path contains a parent directory component (`..`)
+    ParentDir,
+    /// The path starts with a Windows prefix such as `C:`, given lossily as UTF-8
+    Prefix(String),
+    /// A `..` component would go above the root of a [`TrackedRNG`](crate::tracked::TrackedRNG)
+    AboveRoot,
+    /// From [`Scheme::V5`], a component contains a `%` that doesn't start one of the escapes
+    /// `%2F`, `%5C` or `%25`. The component is given lossily as UTF-8.
+    InvalidEscape(String),
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::CurDir => write!(f, "current directory components (`.`) are not supported in paths"),
+            PathError::ParentDir => write!(f, "parent directory components (`..`) are not supported in paths"),
+            PathError::Prefix(prefix) => write!(f, "windows path prefixes are not supported in paths, found `{}`", prefix),
+            PathError::AboveRoot => write!(f, "parent directory component (`..`) goes above the root"),
+            PathError::InvalidEscape(component) => write!(f, "invalid escape in path component `{}`, `%` must start `%2F`, `%5C` or `%25`", component),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
 /// A deterministic random number generator that supports random access and hierarchical seeding.
 /// 
 /// This RNG uses fast XXH3 hashing to generate deterministic random numbers from any seed that
@@ -23,6 +261,73 @@ fn xxh3_integer_hash(n: u128) -> u128 {
 /// - **Reproducibility**: Seeding will never change between versions
 /// - **Seek**: Efficiently seek to the nth random number in a sequence instead of having to calculate all n values.
 ///
+/// # Positions
+///
+/// Every draw consumes exactly one position of the stream, whatever its width. [`next_u32`],
+/// [`next_u64`] and [`next_u128`](RandomAccessRNG::next_u128) return the low 32, low 64 or all
+/// 128 bits of the value at the current position (see [`Scheme::V4`] and [`Scheme::V6`] for how
+/// this changes) and move on to the next, and
+/// [`fill_bytes`](RngCore::fill_bytes) consumes one position per 8 bytes (16 from [`Scheme::V3`]
+/// onwards), rounding up. A stream
+/// mixing draws of different widths can therefore be random-accessed by counting draws, and the
+/// `seek_*` and `peek_*` methods all agree on what position `n` means.
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use rand_core::RngCore;
+///
+/// let mut rng = RandomAccessRNG::new("seed");
+///
+/// let a = rng.next_u32();
+/// let b = rng.next_u64();
+/// let c = rng.next_u32();
+///
+/// assert_eq!(rng.seek_u32(0), a);
+/// assert_eq!(rng.seek_u64(1), b);
+/// assert_eq!(rng.seek_u32(2), c);
+/// assert_eq!(c, rng.peek_at(2) as u32);
+/// ```
+///
+/// [`next_u32`]: RngCore::next_u32
+/// [`next_u64`]: RngCore::next_u64
+///
+/// # Stream Length
+///
+/// Positions in the stream are `u128`s, so a stream holds 2^128 values and can't be exhausted in
+/// practice. Drawing past the last position wraps around to position 0. The seek methods take a
+/// `u64` index for convenience, which covers the first 2^64 values; the rest are reached with
+/// [`set_position`](RandomAccessRNG::set_position) or the `u128` variants
+/// [`seek_u64_at`](RandomAccessRNG::seek_u64_at), [`seek_u128_at`](RandomAccessRNG::seek_u128_at),
+/// [`peek_at_u128`](RandomAccessRNG::peek_at_u128) and
+/// [`read_bytes_at_u128`](RandomAccessRNG::read_bytes_at_u128). Values at positions below 2^64 are
+/// the same as when positions were 64-bit.
+///
+/// # Memory
+///
+/// The hashing state of a node, over 500 bytes, is shared between clones of an RNG through an
+/// [`Arc`] and only copied when a child is derived from it. A handle itself is a few dozen bytes,
+/// so keeping many copies of a node, such as one per entity drawing from a shared stream at its own
+/// position, costs little more than their positions. Deriving a child allocates its own hashing
+/// state; [`RngPool`](crate::pool::RngPool) reuses released states for workloads that derive
+/// children continuously. The `memory` benchmark (`cargo bench --bench memory`) measures both.
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+///
+/// let npcs = RandomAccessRNG::new("world_seed").get("npcs");
+///
+/// // A million handles to the same node, each with its own position
+/// let handles: Vec<RandomAccessRNG> = (0..1_000_000u128)
+///     .map(|i| {
+///         let mut handle = npcs.clone();
+///         handle.set_position(i * 1000);
+///         handle
+///     })
+///     .collect();
+///
+/// assert!(std::mem::size_of::<RandomAccessRNG>() <= 96);
+/// ```
+///
 /// # Examples
 /// 
 /// ## Standard RNG
@@ -85,19 +390,53 @@ fn xxh3_integer_hash(n: u128) -> u128 {
 /// RNG (marked with the [`CryptoRng`](rand_core::CryptoRng) trait) for security-sensitive applications.
 #[derive(Clone)]
 pub struct RandomAccessRNG {
-    hasher: Xxh3,
-    index: u64,
+    hasher: Arc<Xxh3>,
+    pub(crate) index: u128,
+    scheme: Scheme,
+    /// The last hash used for 64-bit values from [`Scheme::V4`], with its block number
+    buffer: Option<(u128, u128)>,
+    /// The number of values drawn through this handle, see [`draw_count`](RandomAccessRNG::draw_count)
+    pub(crate) draws: u64,
 }
 
 impl RandomAccessRNG {
 
+    /// Turn this handle into `parent.get(key)`, writing the child's hashing state over this
+    /// handle's own if no clone shares it, so no allocation is made (used by the pool)
+    pub(crate) fn become_child<H: Hash>(&mut self, parent: &Self, key: H) {
+        let Some(state) = Arc::get_mut(&mut self.hasher) else {
+            *self = parent.get(key);
+            return;
+        };
+
+        state.clone_from(&parent.hasher);
+        key.hash(state);
+        *state = parent.scheme.finish_level(std::mem::replace(state, Xxh3::new()));
+
+        self.index = 0;
+        self.scheme = parent.scheme;
+        self.buffer = None;
+        self.draws = 0;
+    }
+
+    /// A copy of this handle with its own hashing state, shared with no other handle
+    pub(crate) fn unshared(&self) -> Self {
+        Self {
+            hasher: Arc::new((*self.hasher).clone()),
+            ..self.clone()
+        }
+    }
+
     /// Helper function to generate new RandomAccessRNGs (new or get)
-    fn new_helper<H: Hash>(mut xxh3: Xxh3, seed: H) -> Self {
+    fn new_helper<H: Hash>(mut xxh3: Xxh3, seed: H, scheme: Scheme) -> Self {
         seed.hash(& mut xxh3);
 
         Self {
-            hasher: xxh3,
+            hasher: Arc::new(scheme.finish_level(xxh3)),
             index: 0,
+            scheme,
+            buffer: None,
+            draws: 0,
         }
     }
 
@@ -150,7 +489,180 @@ impl RandomAccessRNG {
     /// ```
     ///
     pub fn new<H: Hash>(seed: H) -> Self {
-        Self::new_helper(Xxh3::new(), seed)
+        Self::new_helper(Xxh3::new(), seed, Scheme::V1)
+    }
+
+    /// Generate a new [`RandomAccessRNG`] from a seed, using a specific [`Scheme`].
+    ///
+    /// All children of the new RNG use the same scheme. `with_scheme(seed, Scheme::V1)` is
+    /// identical to [`new`](RandomAccessRNG::new).
+    ///
+    /// # Examples
+    ///
+    /// Under [`Scheme::V2`] every level of a hierarchy is compacted to a fixed-size digest, so very
+    /// deep paths are as cheap to draw from as shallow ones:
+    ///
+    /// ```rust
+    /// use random_access_rng::{RandomAccessRNG, Scheme};
+    /// use rand_core::RngCore;
+    ///
+    /// let root = RandomAccessRNG::with_scheme("seed", Scheme::V2);
+    ///
+    /// let mut deep = root.clone();
+    /// for level in 0..10_000u32 {
+    ///     deep = deep.get(level);
+    /// }
+    ///
+    /// assert_eq!(deep.scheme(), Scheme::V2);
+    ///
+    /// // Derivation is still equivalent however it is spelled
+    /// let mut a = root.descendant(["a", "b"].iter());
+    /// let mut b = root.get("a").get("b");
+    /// assert_eq!(a.next_u64(), b.next_u64());
+    ///
+    /// // But the values differ from the original scheme
+    /// let mut v1 = RandomAccessRNG::new("seed").get("a").get("b");
+    /// let mut v2 = RandomAccessRNG::with_scheme("seed", Scheme::V2).get("a").get("b");
+    /// assert_ne!(v1.next_u64(), v2.next_u64());
+    /// ```
+    pub fn with_scheme<H: Hash>(seed: H, scheme: Scheme) -> Self {
+        Self::new_helper(Xxh3::new(), seed, scheme)
+    }
+
+    /// Generate a new [`RandomAccessRNG`] from two independent seed sources, such as a world seed
+    /// and a save slot.
+    ///
+    /// The RNG is seeded with the tuple of a domain constant, `a` and `b`, so it is the same on
+    /// every run and never collides with an RNG seeded with `a` or `b` alone, or with the plain
+    /// tuple `(a, b)`. Mixing is order-sensitive: `combine(a, b)` and `combine(b, a)` are different
+    /// RNGs. More sources can be combined by passing a tuple as either argument, which is safer
+    /// than concatenating them into a string, where `("ab", "c")` and `("a", "bc")` would collide.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let world_seed = 0x5eed_u64;
+    /// let save_slot = 2u32;
+    ///
+    /// let game = RandomAccessRNG::combine(world_seed, (save_slot, "expansion_mod"));
+    ///
+    /// assert_eq!(game, RandomAccessRNG::combine(world_seed, (save_slot, "expansion_mod")));
+    /// assert_ne!(game, RandomAccessRNG::combine((save_slot, "expansion_mod"), world_seed));
+    /// assert_ne!(game, RandomAccessRNG::new((world_seed, (save_slot, "expansion_mod"))));
+    /// ```
+    pub fn combine<A: Hash, B: Hash>(a: A, b: B) -> Self {
+        Self::new((COMBINE_DOMAIN, a, b))
+    }
+
+    /// Generate a new [`RandomAccessRNG`] from all the bytes of `reader`, such as a file or stdin.
+    ///
+    /// The bytes are streamed into the hasher in chunks, so seed material of any size is read
+    /// without holding it in memory. The RNG is the same as seeding with a key whose [`Hash`]
+    /// implementation writes every byte in a single [`Hasher::write`] call, so small seeds can be
+    /// reproduced without a reader.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from `reader` other than [`Interrupted`](io::ErrorKind::Interrupted),
+    /// which is retried.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::key::HashWith;
+    /// use std::hash::Hasher;
+    ///
+    /// let artifact = vec![0x5a; 100_000];
+    ///
+    /// let world = RandomAccessRNG::from_reader(artifact.as_slice()).unwrap();
+    ///
+    /// let whole = HashWith::new(artifact, |bytes: &Vec<u8>, state: &mut dyn Hasher| state.write(bytes));
+    /// assert_eq!(world.digest(), RandomAccessRNG::new(whole).digest());
+    /// ```
+    pub fn from_reader<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut xxh3 = Xxh3::new();
+        let mut chunk = [0u8; 8192];
+
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => xxh3.update(&chunk[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(Self::with_hasher(xxh3))
+    }
+
+    /// Generate a new [`RandomAccessRNG`] from an XXH3 hasher that seed material has already been
+    /// written to.
+    ///
+    /// The hasher's state is used as the root, as if a key had written the same bytes, so
+    /// `with_hasher` gives the same RNG as [`new`](RandomAccessRNG::new) with a key that writes
+    /// them through [`Hash`]. Material that arrives in pieces, such as the chunks of a streamed
+    /// asset, can be fed to the hasher as it arrives rather than collected into one key.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use xxhash_rust::xxh3::Xxh3;
+    /// use std::hash::Hash;
+    ///
+    /// let mut hasher = Xxh3::new();
+    /// for chunk in [&b"header"[..], &b"tiles"[..], &b"footer"[..]] {
+    ///     hasher.update(chunk);
+    /// }
+    ///
+    /// let level = RandomAccessRNG::with_hasher(hasher);
+    ///
+    /// // The same as hashing a key that writes the same bytes
+    /// let mut key = Xxh3::new();
+    /// 42u64.hash(&mut key);
+    /// assert_eq!(RandomAccessRNG::with_hasher(key), RandomAccessRNG::new(42u64));
+    /// ```
+    pub fn with_hasher(hasher: Xxh3) -> Self {
+        Self {
+            hasher: Arc::new(Scheme::default().finish_level(hasher)),
+            index: 0,
+            scheme: Scheme::default(),
+            buffer: None,
+            draws: 0,
+        }
+    }
+
+    /// Generate a new [`RandomAccessRNG`] from a digest computed elsewhere, such as the content
+    /// hash of an asset, without hashing the material again.
+    ///
+    /// The RNG is seeded with the 16 little endian bytes of `digest`, so it is the same on every
+    /// platform. A digest of a different size can be widened or truncated to a `u128` first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use xxhash_rust::xxh3::xxh3_128;
+    ///
+    /// // Computed once by the asset pipeline and stored with the asset
+    /// let asset_hash = xxh3_128(b"dungeon_tileset_v3.png contents");
+    ///
+    /// let decoration = RandomAccessRNG::from_digest(asset_hash).get("decoration");
+    /// assert_eq!(decoration, RandomAccessRNG::from_digest(asset_hash).get("decoration"));
+    /// ```
+    pub fn from_digest(digest: u128) -> Self {
+        let mut hasher = Xxh3::new();
+        hasher.update(&digest.to_le_bytes());
+
+        Self::with_hasher(hasher)
+    }
+
+    /// The [`Scheme`] used by this RNG.
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
     }
 
     /// Create a child RNG with a new seed derived from this RNG's state and the provided key.
@@ -187,111 +699,763 @@ impl RandomAccessRNG {
     /// - **Testing**: Create independent RNGs for different test scenarios
     /// - **Simulation**: Separate RNGs for different simulation components
     pub fn get<H: Hash>(&self, key: H) -> Self {
-        Self::new_helper(self.hasher.clone(), key)
+        Self::new_helper((*self.hasher).clone(), key, self.scheme)
     }
 
-    /// Create a descendant RNG by applying multiple keys in sequence.
-    /// 
-    /// This is equivalent to calling [`get`](RandomAccessRNG::get) multiple times in sequence.
-    /// The keys are applied in the order they appear in the iterator.
-    /// 
+    /// Create a child RNG for each of a fixed number of keys.
+    ///
+    /// The children are the same as calling [`get`](RandomAccessRNG::get) with each key, returned
+    /// in the same order. Each child still starts from its own copy of the parent's hashing state,
+    /// since hashing a key consumes it.
+    ///
     /// # Examples
-    /// 
+    ///
     /// ```rust
     /// use random_access_rng::RandomAccessRNG;
-    /// use rand_core::RngCore;
-    /// 
-    /// let parent = RandomAccessRNG::new("root");
-    /// 
-    /// // Using a vector of keys
-    /// let keys = vec!["level1", "level2", "level3"];
-    /// let mut descendant1 = parent.descendant(keys.iter());
-    /// 
-    /// // Equivalent to:
-    /// let mut descendant2 = parent.get("level1").get("level2").get("level3");
     ///
-    /// assert_eq!(descendant1.next_u64(), descendant2.next_u64());
+    /// let world = RandomAccessRNG::new("world_seed");
+    ///
+    /// let [terrain, enemies, items] = world.get_many(["terrain", "enemies", "items"]);
+    ///
+    /// assert_eq!(enemies.digest(), world.get("enemies").digest());
     /// ```
+    pub fn get_many<H: Hash, const N: usize>(&self, keys: [H; N]) -> [Self; N] {
+        keys.map(|key| self.get(key))
+    }
+
+    /// Lazily create a child RNG for each key of `keys`, in order.
+    ///
+    /// Each child is the same as calling [`get`](RandomAccessRNG::get) with its key.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let chunks = RandomAccessRNG::new("world_seed").get("chunks");
     ///
-    pub fn descendant<'a, H: Hash + 'a + ?Sized, I: IntoIterator<Item = & 'a H>>(&self, keys: I) -> Self {
-        let mut h = self.hasher.clone();
+    /// // Every chunk of a 16 by 16 region
+    /// let region: Vec<RandomAccessRNG> = chunks.get_all((0..16i64).flat_map(|x| (0..16i64).map(move |y| (x, y)))).collect();
+    ///
+    /// assert_eq!(region.len(), 256);
+    /// assert_eq!(region[17].digest(), chunks.get((1i64, 1i64)).digest());
+    /// ```
+    pub fn get_all<H: Hash, I: IntoIterator<Item = H>>(&self, keys: I) -> impl Iterator<Item = Self> {
+        keys.into_iter().map(|key| self.get(key))
+    }
 
-        for key in keys {
-            key.hash(&mut h);
-        }
+    /// Turn this RNG into its own child at `key`, starting again from position 0.
+    ///
+    /// The same as `*self = self.get(key)`, for long-lived generators that move through phases.
+    /// The count of [`draw_count`](RandomAccessRNG::draw_count) carries over, since it counts the
+    /// draws made through this handle.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let colony = RandomAccessRNG::new("sim_seed").get(("colony", 3));
+    ///
+    /// let mut rng = colony.clone();
+    /// rng.next_u64();
+    ///
+    /// // The colony enters its second era
+    /// rng.reseed("era_2");
+    /// assert_eq!(rng, colony.get("era_2"));
+    /// ```
+    pub fn reseed<H: Hash>(&mut self, key: H) {
+        self.hasher = self.get(key).hasher;
+        self.index = 0;
+        self.buffer = None;
+    }
+
+    /// Create a child RNG from a key that has already been hashed to a `u128`.
+    ///
+    /// Hashing a key through [`Hash`] on every [`get`](RandomAccessRNG::get) can be avoided for
+    /// constant keys by hashing them once, ideally at compile time with
+    /// [`xxh3_const!`](crate::xxh3_const). The 16 bytes of `hash` are fed to the hasher in little
+    /// endian order, so prehashed keys form their own namespace: `get_prehashed(xxh3_const!("a"))`
+    /// is a different child from `get("a")`, but is the same on every platform.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::{RandomAccessRNG, xxh3_const};
+    /// use random_access_rng::key::prehash;
+    /// use rand_core::RngCore;
+    ///
+    /// const TERRAIN: u128 = xxh3_const!("terrain");
+    ///
+    /// let world = RandomAccessRNG::new("world_seed");
+    ///
+    /// let mut a = world.get_prehashed(TERRAIN);
+    /// let mut b = world.get_prehashed(prehash("terrain"));
+    /// assert_eq!(a.next_u64(), b.next_u64());
+    /// ```
+    pub fn get_prehashed(&self, hash: u128) -> Self {
+        let mut hasher = (*self.hasher).clone();
+        hasher.update(&hash.to_le_bytes());
 
         Self {
-            hasher: h,
+            hasher: Arc::new(self.scheme.finish_level(hasher)),
             index: 0,
+            scheme: self.scheme,
+            buffer: None,
+            draws: 0,
         }
     }
 
-    /// Create a descendant RNG from a path.
-    /// 
-    /// Each path component is treated as a key for creating child RNGs.
-    /// The path is parsed using standard path semantics, with some restrictions.
-    /// 
-    /// # Path Semantics
-    /// 
-    /// - **Normal components**: Used as keys (e.g., "folder", "file.txt")
-    /// - **Root directory (`/`)**: Ignored
-    /// - **Current directory (`.`)**: Not supported, will panic
-    /// - **Parent directory (`..`)**: Not supported, will panic
-    /// - **Windows prefixes**: Not supported, will panic
-    /// 
-    /// # Examples
+    /// Create a child RNG for the 2D coordinate `(x, y)`, without allocating.
     ///
-    /// The following child objects are equivalent
+    /// Deriving per-cell RNGs with `get(format!("{}/{}", x, y))` allocates a string for every
+    /// cell. This hashes the coordinates directly instead, as the little endian bytes of a domain
+    /// constant, `x` and `y`, and derives the child with
+    /// [`get_prehashed`](RandomAccessRNG::get_prehashed). The domain keeps coordinate children
+    /// apart from every other prehashed key, including those of [`get3`](RandomAccessRNG::get3).
+    ///
+    /// # Examples
     ///
     /// ```rust
     /// use random_access_rng::RandomAccessRNG;
     /// use rand_core::RngCore;
-    /// 
-    /// let parent = RandomAccessRNG::new("root");
-    /// 
-    /// // Unix-style paths
-    /// let mut child1 = parent.path("world/enemy/color");
-    /// 
-    /// // Windows-style paths (forward slashes)
-    /// let mut child2 = parent.path("world\\enemy\\color");
-    /// 
-    /// // Paths with root directory are handled
+    ///
+    /// let terrain = RandomAccessRNG::new("world_seed").get("terrain");
+    ///
+    /// let mut cell = terrain.get2(-3, 17);
+    /// assert_eq!(cell.next_u64(), terrain.get2(-3, 17).next_u64());
+    ///
+    /// // Coordinates are ordered, and distinct from the same values in 3D
+    /// assert_ne!(terrain.get2(-3, 17).digest(), terrain.get2(17, -3).digest());
+    /// assert_ne!(terrain.get2(1, 2).digest(), terrain.get3(1, 2, 0).digest());
+    /// ```
+    pub fn get2(&self, x: i64, y: i64) -> Self {
+        let mut key = [0u8; 24];
+        key[..8].copy_from_slice(&COORD_2D_DOMAIN.to_le_bytes());
+        key[8..16].copy_from_slice(&x.to_le_bytes());
+        key[16..].copy_from_slice(&y.to_le_bytes());
+
+        self.get_prehashed(xxh3_128(&key))
+    }
+
+    /// Create a child RNG for the 3D coordinate `(x, y, z)`, without allocating.
+    ///
+    /// The 3D counterpart of [`get2`](RandomAccessRNG::get2), hashing a domain constant, `x`, `y`
+    /// and `z` as little endian bytes.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let voxels = RandomAccessRNG::new("world_seed").get("voxels");
+    ///
+    /// assert_eq!(voxels.get3(1, -64, 9).digest(), voxels.get3(1, -64, 9).digest());
+    /// ```
+    pub fn get3(&self, x: i64, y: i64, z: i64) -> Self {
+        let mut key = [0u8; 32];
+        key[..8].copy_from_slice(&COORD_3D_DOMAIN.to_le_bytes());
+        key[8..16].copy_from_slice(&x.to_le_bytes());
+        key[16..24].copy_from_slice(&y.to_le_bytes());
+        key[24..].copy_from_slice(&z.to_le_bytes());
+
+        self.get_prehashed(xxh3_128(&key))
+    }
+
+    /// The independent sub-stream numbered `lane`, such as one per worker of a Monte Carlo run.
+    ///
+    /// Each lane is a child derived from the little endian bytes of a domain constant and `lane`
+    /// with [`get_prehashed`](RandomAccessRNG::get_prehashed), starting at position 0. The domain
+    /// keeps lanes apart from the children of [`get`](RandomAccessRNG::get) with any key, so
+    /// `substream(3)` is never the same node as `get(3u64)`, and different lanes are different
+    /// nodes. Unlike splitting the positions of one stream, a lane has the full length of a stream
+    /// and can be seeked, reseeded or split again without reaching into its neighbours.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    /// use std::thread;
+    ///
+    /// let trials = RandomAccessRNG::new("experiment_7").get("trials");
+    ///
+    /// // Each worker estimates pi from its own lane
+    /// let hits: u64 = thread::scope(|scope| {
+    ///     let handles: Vec<_> = (0..4u64)
+    ///         .map(|worker| {
+    ///             let mut lane = trials.substream(worker);
+    ///             scope.spawn(move || (0..1000).filter(|_| {
+    ///                 let (x, y) = (lane.next_f64(), lane.next_f64());
+    ///                 x * x + y * y < 1.0
+    ///             }).count() as u64)
+    ///         })
+    ///         .collect();
+    ///
+    ///     handles.into_iter().map(|h| h.join().unwrap()).sum()
+    /// });
+    ///
+    /// assert!((hits as f64 / 4000.0 * 4.0 - std::f64::consts::PI).abs() < 0.2);
+    /// assert_ne!(trials.substream(3), trials.get(3u64));
+    /// ```
+    pub fn substream(&self, lane: u64) -> Self {
+        let mut key = [0u8; 16];
+        key[..8].copy_from_slice(&SUBSTREAM_DOMAIN.to_le_bytes());
+        key[8..].copy_from_slice(&lane.to_le_bytes());
+
+        self.get_prehashed(xxh3_128(&key))
+    }
+
+    /// The first `count` [`substream`](RandomAccessRNG::substream)s, one for each lane from 0.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let trials = RandomAccessRNG::new("experiment_7").get("trials");
+    ///
+    /// let lanes = trials.split(8);
+    /// assert_eq!(lanes[5], trials.substream(5));
+    /// ```
+    pub fn split(&self, count: usize) -> Vec<Self> {
+        (0..count as u64).map(|lane| self.substream(lane)).collect()
+    }
+
+    /// Create a descendant RNG by applying multiple keys in sequence.
+    /// 
+    /// This is equivalent to calling [`get`](RandomAccessRNG::get) multiple times in sequence.
+    /// The keys are applied in the order they appear in the iterator, which may yield references
+    /// or owned keys. A reference hashes the same as the key it refers to.
+    ///
+    /// Keys of different types can be mixed by wrapping them in [`Key`](crate::key::Key), which
+    /// hashes each key exactly as its own type does.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    /// 
+    /// let parent = RandomAccessRNG::new("root");
+    /// 
+    /// // Using a vector of keys
+    /// let keys = vec!["level1", "level2", "level3"];
+    /// let mut descendant1 = parent.descendant(keys.iter());
+    /// 
+    /// // Equivalent to:
+    /// let mut descendant2 = parent.get("level1").get("level2").get("level3");
+    ///
+    /// assert_eq!(descendant1.next_u64(), descendant2.next_u64());
+    ///
+    /// // Owned keys of mixed types
+    /// use random_access_rng::key::Key;
+    ///
+    /// let mut descendant3 = parent.descendant([Key::from("terrain"), Key::from(12i64), Key::from(7i64)]);
+    /// let mut descendant4 = parent.get("terrain").get(12i64).get(7i64);
+    ///
+    /// assert_eq!(descendant3.next_u64(), descendant4.next_u64());
+    /// ```
+    ///
+    pub fn descendant<K: Hash, I: IntoIterator<Item = K>>(&self, keys: I) -> Self {
+        self.descend(keys)
+    }
+
+    /// Create a descendant RNG from a structured address, applying each [`Segment`](crate::key::Segment)
+    /// as one level.
+    ///
+    /// Equivalent to [`descendant`](RandomAccessRNG::descendant) with the segments as keys. See
+    /// `Segment` for how each kind of segment hashes.
+    pub fn path_segments(&self, segments: &[crate::key::Segment]) -> Self {
+        self.descend(segments)
+    }
+
+    /// Helper applying a sequence of keys of any one type (descendant and path)
+    fn descend<K: Hash, I: IntoIterator<Item = K>>(&self, keys: I) -> Self {
+        let mut h = (*self.hasher).clone();
+
+        for key in keys {
+            key.hash(&mut h);
+            h = self.scheme.finish_level(h);
+        }
+
+        Self {
+            hasher: Arc::new(h),
+            index: 0,
+            scheme: self.scheme,
+            buffer: None,
+            draws: 0,
+        }
+    }
+
+    /// Create a descendant RNG from a path.
+    /// 
+    /// Each path component is treated as a key for creating child RNGs.
+    /// The path is parsed using standard path semantics, with some restrictions.
+    /// 
+    /// # Path Semantics
+    /// 
+    /// - **Normal components**: Used as keys (e.g., "folder", "file.txt"), see below
+    /// - **Root directory (`/`)**: Ignored
+    /// - **Current directory (`.`)**: Not supported, will panic
+    /// - **Parent directory (`..`)**: Not supported, will panic, see [`TrackedRNG`](crate::tracked::TrackedRNG)
+    /// - **Windows prefixes**: Not supported, will panic
+    /// 
+    /// # Examples
+    ///
+    /// The following child objects are equivalent
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    /// 
+    /// let parent = RandomAccessRNG::new("root");
+    /// 
+    /// // Unix-style paths
+    /// let mut child1 = parent.path("world/enemy/color");
+    /// 
+    /// // Windows-style paths (forward slashes)
+    /// let mut child2 = parent.path("world\\enemy\\color");
+    /// 
+    /// // Paths with root directory are handled
     /// let mut child3 = parent.path("/world/enemy/color");
     /// ```
     /// 
+    /// # Component Encoding
+    ///
+    /// A component is hashed as its bytes followed by a `0xff` byte, which for valid UTF-8 is
+    /// exactly how the equivalent `&str` key hashes, so `path("a/b")` is `get("a").get("b")`.
+    /// Components that aren't valid UTF-8, as found on real filesystems, are hashed the same way
+    /// using the platform's encoded bytes ([`OsStr::as_encoded_bytes`]: the raw bytes on Unix,
+    /// WTF-8 on Windows). No byte sequence of this kind is valid UTF-8, so they never collide with
+    /// string keys.
+    ///
+    /// ```rust
+    /// # #[cfg(unix)] {
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    /// use std::ffi::OsStr;
+    /// use std::os::unix::ffi::OsStrExt;
+    /// use std::path::Path;
+    ///
+    /// let saves = RandomAccessRNG::new("root");
+    ///
+    /// // A Latin-1 file name, which is not valid UTF-8
+    /// let name = OsStr::from_bytes(b"caf\xe9");
+    /// let mut a = saves.path(Path::new("saves").join(name));
+    /// let mut b = saves.path(Path::new("saves").join(name));
+    ///
+    /// assert_eq!(a.next_u64(), b.next_u64());
+    /// # }
+    /// ```
+    ///
     /// # Panics
     /// 
     /// This method will panic if the path contains:
     /// - Current directory components (`.`)
     /// - Parent directory components (`..`)
     /// - Windows path prefixes
+    /// - From [`Scheme::V5`], invalid escapes
+    ///
+    /// Use [`try_path`](RandomAccessRNG::try_path) to handle these as errors instead.
     /// 
     /// # Use Cases
     /// 
     /// - **File-based procedural generation**: Use paths as RNG seeds
     /// - **Organized randomness**: Group related random generation by path structure
     pub fn path<P: AsRef<Path>>(&self, path: P) -> Self {
-        self.descendant(path
-            .as_ref()
-            .components()
-            .filter_map(|component| match component {
-                Component::Normal(c) => Some(c.to_str().expect("Invalid UTF-8 in component")),
-                Component::RootDir => None,
-                Component::Prefix(p) => panic!("Invalid windows path prefix - {:?}", p),
-                Component::CurDir | Component::ParentDir => panic!("Absolute paths not supported"),
-            }))
+        match self.try_path(path) {
+            Ok(rng) => rng,
+            Err(e) => panic!("{}", e),
+        }
     }
 
-    /// Internal helper used in `seek_u64` and `next_u64`
+    /// Create a descendant RNG from a path, returning an error instead of panicking for paths
+    /// [`path`](RandomAccessRNG::path) doesn't support.
+    ///
+    /// Use this when the path comes from user content, such as mod files or save data. Valid paths
+    /// give the same RNG as `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PathError`] for the first `.`, `..` or Windows prefix component of the path, or
+    /// from [`Scheme::V5`] for the first component with an invalid escape.
+    /// Components that aren't valid UTF-8 are not an error, see Component Encoding under
+    /// [`path`](RandomAccessRNG::path).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::{PathError, RandomAccessRNG};
+    ///
+    /// let mods = RandomAccessRNG::new("world_seed").get("mods");
+    ///
+    /// assert_eq!(mods.try_path("forest/trees").unwrap().digest(), mods.path("forest/trees").digest());
+    /// assert_eq!(mods.try_path("forest/../../secrets").err(), Some(PathError::ParentDir));
+    /// ```
+    pub fn try_path<P: AsRef<Path>>(&self, path: P) -> Result<Self, PathError> {
+        Ok(self.descend(path_keys(path.as_ref(), self.scheme >= Scheme::V5)?))
+    }
+
+    /// A stable 64-bit identifier for the content at `path` below this node.
+    ///
+    /// Generated entities often need a persistent ID (for save files, networking or lookups).
+    /// Using a drawn value such as `next_u64()` for this is tempting but fragile: the ID then
+    /// depends on the stream position and may coincide with values used for generation. Content
+    /// IDs are instead derived from the node alone and hashed under a separate domain, so they
+    /// never collide with the values produced by any stream.
+    ///
+    /// The same path always gives the same ID, and the ID does not depend on the position of this
+    /// RNG in its stream. Paths follow the same rules as [`path`](RandomAccessRNG::path).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut world = RandomAccessRNG::new("world_seed");
+    ///
+    /// let id = world.content_id("villages/12/npcs/3");
+    ///
+    /// // Stable regardless of how the RNG has been used
+    /// world.next_u64();
+    /// assert_eq!(id, world.content_id("villages/12/npcs/3"));
+    ///
+    /// // Distinct from the values generated at that node
+    /// let mut npc = world.path("villages/12/npcs/3");
+    /// assert_ne!(id, npc.next_u64());
+    /// ```
+    pub fn content_id<P: AsRef<Path>>(&self, path: P) -> u64 {
+        xxh3_64_with_seed(&self.path(path).digest().to_le_bytes(), CONTENT_ID_DOMAIN)
+    }
+
+    /// The 128-bit digest identifying this node.
+    ///
+    /// The digest depends only on the seed and the keys used to derive the node, never on its
+    /// position in the stream, and every value of the stream is computed from it. Two nodes with
+    /// the same digest produce the same values.
+    pub fn digest(&self) -> u128 {
+        self.hasher.digest128()
+    }
+
+    /// The digests of the children `self.get(prefix).get(i)` for every `i` in `range`.
+    ///
+    /// The prefix is hashed once and shared by every child, which makes this much cheaper than
+    /// deriving each child separately when identities are needed for thousands of entities. The
+    /// digests are returned in order in one contiguous buffer; materialise an RNG for any of them
+    /// with `self.get(prefix).get(i)` only when it is actually needed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let world = RandomAccessRNG::new("world_seed");
+    ///
+    /// let npcs = world.child_digests("npc", 0..10_000);
+    ///
+    /// assert_eq!(npcs.len(), 10_000);
+    /// assert_eq!(npcs[1234], world.get("npc").get(1234u64).digest());
+    /// ```
+    pub fn child_digests<H: Hash>(&self, prefix: H, range: Range<u64>) -> Vec<u128> {
+        let parent = self.get(prefix);
+
+        range
+            .map(|i| {
+                let mut hasher = (*parent.hasher).clone();
+                i.hash(&mut hasher);
+
+                self.scheme.finish_level(hasher).digest128()
+            })
+            .collect()
+    }
+
+    /// An unbounded family of children under `prefix`: `self.get(prefix).get(i)` for `i` = 0, 1,
+    /// 2 and so on, as `u64`s.
+    ///
+    /// Children are derived lazily as the iterator is advanced, and are numbered the same way as
+    /// [`child_digests`](RandomAccessRNG::child_digests), so the digest of each child matches the
+    /// digest at the same number there.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let world = RandomAccessRNG::new("world_seed");
+    ///
+    /// // One independent stream per wave of enemies, for as many waves as the game lasts
+    /// let mut waves = world.children("wave");
+    ///
+    /// let first = waves.next().unwrap();
+    /// let second = waves.next().unwrap();
+    ///
+    /// assert_eq!(second.digest(), world.get("wave").get(1u64).digest());
+    /// assert_ne!(first.digest(), second.digest());
+    /// ```
+    pub fn children<H: Hash>(&self, prefix: H) -> impl Iterator<Item = Self> + use<H> {
+        let parent = self.get(prefix);
+
+        (0u64..).map(move |i| parent.get(i))
+    }
+
+    /// The 128-bit value at `index`, without moving the stream
+    fn value_at(&self, index: u128) -> u128 {
+        crate::mix::hash_index(self.digest(), index)
+    }
+
+    /// Internal helper used in `next_u128` and `fill_bytes`
     fn next(& mut self) -> u128 {
-        //Simple way to generate next random number by combining self.seed and self.index
-        let result = xxh3_integer_hash(self.hasher.digest128() ^ self.index as u128);
+        let result = self.value_at(self.index);
 
-        self.index += 1;
+        self.index = self.index.wrapping_add(1);
+        self.draws = self.draws.wrapping_add(1);
 
         result
     }
 
+    /// The number of bytes [`fill_bytes`](RngCore::fill_bytes) takes from each position
+    pub(crate) fn bytes_per_position(&self) -> usize {
+        if self.scheme < Scheme::V3 { 8 } else { 16 }
+    }
+
+    /// The hash holding the value at `index` when `2^shift` positions share one hash, as from
+    /// [`Scheme::V4`] and [`Scheme::V6`], and its block number. Each `shift` has its own range of
+    /// blocks, `[2^(128 - shift), 2^(129 - shift))`.
+    fn block_of(index: u128, shift: u32) -> u128 {
+        (index >> shift) | 1 << (128 - shift)
+    }
+
+    /// The part of the shared hash holding the value at `index`, in the low bits
+    fn part_at(&self, index: u128, shift: u32) -> u128 {
+        let bits = 128 >> shift;
+
+        self.value_at(Self::block_of(index, shift)) >> (bits * (index & ((1 << shift) - 1)))
+    }
+
+    /// The 64-bit value at `index`, without moving the stream
+    fn word_at(&self, index: u128) -> u64 {
+        if self.scheme < Scheme::V4 {
+            return self.value_at(index) as u64;
+        }
+
+        self.part_at(index, 1) as u64
+    }
+
+    /// Internal helper for draws sharing a hash between `2^shift` positions, reusing the last hash
+    fn next_part(& mut self, shift: u32) -> u128 {
+        let bits = 128 >> shift;
+        let block = Self::block_of(self.index, shift);
+
+        let value = match self.buffer {
+            Some((buffered, value)) if buffered == block => value,
+            _ => {
+                let value = self.value_at(block);
+                self.buffer = Some((block, value));
+                value
+            }
+        };
+
+        let result = value >> (bits * (self.index & ((1 << shift) - 1)));
+
+        self.index = self.index.wrapping_add(1);
+        self.draws = self.draws.wrapping_add(1);
+
+        result
+    }
+
+    /// Internal helper used in `seek_u64` and `next_u64`
+    fn next_word(& mut self) -> u64 {
+        if self.scheme < Scheme::V4 {
+            return self.next() as u64;
+        }
+
+        self.next_part(1) as u64
+    }
+
+    /// Generate the next 8-bit value, consuming one position.
+    ///
+    /// Before [`Scheme::V6`] this is the low byte of [`next_u64`](RngCore::next_u64), so the rest
+    /// of the value is discarded. From `V6` sixteen consecutive positions share one hash.
+    ///
+    /// ```rust
+    /// use random_access_rng::{RandomAccessRNG, Scheme};
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    /// assert_eq!(rng.next_u8(), rng.peek_at(0) as u8);
+    ///
+    /// let mut packed = RandomAccessRNG::with_scheme("seed", Scheme::V6);
+    /// let bytes: Vec<u8> = (0..16).map(|_| packed.next_u8()).collect();
+    /// assert_eq!(bytes, packed.seek_raw(1 << 124).to_le_bytes());
+    /// ```
+    pub fn next_u8(& mut self) -> u8 {
+        if self.scheme < Scheme::V6 {
+            return self.next_word() as u8;
+        }
+
+        self.next_part(4) as u8
+    }
+
+    /// Seek to a specific position in the sequence and return an 8-bit value, as drawn by
+    /// [`next_u8`](RandomAccessRNG::next_u8). Afterwards the RNG continues from `index + 1`.
+    ///
+    /// ```rust
+    /// use random_access_rng::{RandomAccessRNG, Scheme};
+    ///
+    /// let mut rng = RandomAccessRNG::with_scheme("seed", Scheme::V6);
+    ///
+    /// let bytes: Vec<u8> = (0..20).map(|_| rng.next_u8()).collect();
+    ///
+    /// assert_eq!(rng.seek_u8(17), bytes[17]);
+    /// assert_eq!(rng.next_u8(), bytes[18]);
+    /// ```
+    pub fn seek_u8(& mut self, index: u64) -> u8 {
+        self.index = index as u128;
+
+        self.next_u8()
+    }
+
+    /// The value the next call to [`next_u64`](RngCore::next_u64) will return, without consuming it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// let peeked = rng.peek_u64();
+    /// assert_eq!(rng.peek_u64(), peeked);
+    /// assert_eq!(rng.next_u64(), peeked);
+    /// ```
+    pub fn peek_u64(&self) -> u64 {
+        self.word_at(self.index)
+    }
+
+    /// The value at `index`, as returned by [`seek_u64`](RandomAccessRNG::seek_u64), without
+    /// moving the stream.
+    ///
+    /// Useful for inspecting a stream, for example when looking for the point where two runs
+    /// diverge, without disturbing it.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// let at_1000 = rng.peek_at(1000);
+    ///
+    /// // The stream is still at the start
+    /// assert_eq!(rng.next_u64(), rng.peek_at(0));
+    /// assert_eq!(rng.seek_u64(1000), at_1000);
+    /// ```
+    pub fn peek_at(&self, index: u64) -> u64 {
+        self.word_at(index as u128)
+    }
+
+    /// The value at any 128-bit `index`, as returned by
+    /// [`seek_u64_at`](RandomAccessRNG::seek_u64_at), without moving the stream.
+    ///
+    /// Equal to [`peek_at`](RandomAccessRNG::peek_at) for indices up to `u64::MAX`, see
+    /// [Stream Length](RandomAccessRNG#stream-length).
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// assert_eq!(rng.peek_at_u128(1000), rng.peek_at(1000));
+    ///
+    /// let far = rng.peek_at_u128(1 << 100);
+    /// rng.set_position(1 << 100);
+    /// assert_eq!(rng.next_u64(), far);
+    /// ```
+    pub fn peek_at_u128(&self, index: u128) -> u64 {
+        self.word_at(index)
+    }
+
+    /// The position of the next value in the sequence.
+    ///
+    /// This is the number of values drawn since the RNG was created, or since the position was last
+    /// set. Together with [`set_position`](RandomAccessRNG::set_position) it allows saving and
+    /// restoring the stream without drawing any values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    /// assert_eq!(rng.position(), 0);
+    ///
+    /// rng.next_u64();
+    /// rng.next_u64();
+    /// assert_eq!(rng.position(), 2);
+    ///
+    /// let saved = rng.position();
+    /// let expected = rng.next_u64();
+    ///
+    /// rng.set_position(saved);
+    /// assert_eq!(rng.next_u64(), expected);
+    /// ```
+    pub fn position(&self) -> u128 {
+        self.index
+    }
+
+    /// Move to `position` without drawing a value, so the next value drawn is the one at `position`.
+    ///
+    /// Unlike [`seek_u64`](RandomAccessRNG::seek_u64), which returns the value at the new
+    /// position and moves past it, this only sets the position.
+    ///
+    /// Positions beyond `u64::MAX` are reached this way or with the `u128` seek methods, such as
+    /// [`seek_u64_at`](RandomAccessRNG::seek_u64_at), see [Stream Length](RandomAccessRNG#stream-length).
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// rng.set_position(u64::MAX as u128);
+    /// rng.next_u64();
+    /// assert_eq!(rng.position(), 1 << 64);
+    /// ```
+    pub fn set_position(& mut self, position: u128) {
+        self.index = position;
+    }
+
+    /// Skip `n` values without generating them.
+    ///
+    /// Equivalent to calling [`next_u64`](RngCore::next_u64) `n` times and discarding the
+    /// results, but O(1). Skipping is relative to the current position, so after
+    /// [`seek_u64(i)`](RandomAccessRNG::seek_u64), which leaves the RNG at `i + 1`, calling
+    /// `advance(n)` makes the next value the one at `i + 1 + n`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut burned = RandomAccessRNG::new("seed");
+    /// for _ in 0..100 {
+    ///     burned.next_u64();
+    /// }
+    ///
+    /// let mut skipped = RandomAccessRNG::new("seed");
+    /// skipped.advance(100);
+    ///
+    /// assert_eq!(skipped.next_u64(), burned.next_u64());
+    ///
+    /// skipped.seek_u64(10);
+    /// skipped.advance(5);
+    /// assert_eq!(skipped.position(), 16);
+    /// ```
+    pub fn advance(& mut self, n: u128) {
+        self.index = self.index.wrapping_add(n);
+    }
+
     /// Seek to a specific position in the random number sequence.
     /// 
     /// This method allows you to jump directly to any position in the sequence
@@ -329,31 +1493,406 @@ impl RandomAccessRNG {
     /// - **Caching**: Generate random numbers on-demand without storing the entire sequence
     /// - **Resumable generation**: Save the current position and resume later
     pub fn seek_u64(& mut self, index: u64) -> u64 {
+        self.index = index as u128;
+
+        self.next_word()
+    }
+
+    /// Seek to any 128-bit position in the sequence and return the 64-bit value there.
+    ///
+    /// Like [`seek_u64`](RandomAccessRNG::seek_u64), which it equals for indices up to
+    /// `u64::MAX`, but covering the whole stream, see [Stream Length](RandomAccessRNG#stream-length).
+    /// Afterwards the RNG continues from `index + 1`.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// let past_u64 = u64::MAX as u128 + 1;
+    /// let value = rng.seek_u64_at(past_u64);
+    /// assert_eq!(rng.position(), past_u64 + 1);
+    ///
+    /// rng.set_position(past_u64);
+    /// assert_eq!(rng.next_u64(), value);
+    /// assert_eq!(rng.seek_u64_at(1000), rng.seek_u64(1000));
+    /// ```
+    pub fn seek_u64_at(& mut self, index: u128) -> u64 {
         self.index = index;
 
-        self.next() as u64
+        self.next_word()
+    }
+
+    /// Seek to a specific position in the sequence and return a 32-bit value.
+    ///
+    /// Every call to [`next_u32`](RngCore::next_u32) consumes one position, just like
+    /// [`next_u64`](RngCore::next_u64), so a stream of 32-bit draws is random-accessed with the
+    /// same indices. Before [`Scheme::V6`] the value is the low 32 bits of
+    /// [`seek_u64`](RandomAccessRNG::seek_u64) at `index`. For the full 128-bit word at a
+    /// position, use [`seek_u128`](RandomAccessRNG::seek_u128).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// let values: Vec<u32> = (0..10).map(|_| rng.next_u32()).collect();
+    ///
+    /// assert_eq!(rng.seek_u32(7), values[7]);
+    /// assert_eq!(rng.next_u32(), values[8]);
+    /// ```
+    pub fn seek_u32(& mut self, index: u64) -> u32 {
+        self.index = index as u128;
+
+        self.next_u32()
+    }
+
+    /// Generate the next value of the sequence with the full 128 bits of the underlying hash.
+    ///
+    /// Consumes one position, like [`next_u64`](RngCore::next_u64). Before [`Scheme::V4`], the low
+    /// 64 bits are exactly the value [`next_u64`](RngCore::next_u64) would have returned, so the
+    /// two can be mixed freely.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut wide = RandomAccessRNG::new("seed");
+    /// let mut narrow = RandomAccessRNG::new("seed");
+    ///
+    /// let value = wide.next_u128();
+    /// assert_eq!(value as u64, narrow.next_u64());
+    ///
+    /// // Split one draw into several fields
+    /// let (x, y, z, w) = (value as u32, (value >> 32) as u32, (value >> 64) as u32, (value >> 96) as u32);
+    /// ```
+    pub fn next_u128(& mut self) -> u128 {
+        self.next()
+    }
+
+    /// Seek to a specific position and return the full 128-bit value there.
+    ///
+    /// This is the 128-bit counterpart of [`seek_u64`](RandomAccessRNG::seek_u64), with the same
+    /// positioning: afterwards the RNG continues from `index + 1`.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// let wide = rng.seek_u128(1000);
+    /// assert_eq!(wide as u64, rng.seek_u64(1000));
+    /// ```
+    pub fn seek_u128(& mut self, index: u64) -> u128 {
+        self.index = index as u128;
+
+        self.next()
+    }
+
+    /// The raw 128-bit hash at `index`, without moving the stream.
+    ///
+    /// Every value of the stream is cut from these hashes: before [`Scheme::V4`] the value at a
+    /// position is the hash at that position, and from `V4` onwards a 64-bit value is one half of
+    /// the hash of its block. The hashes themselves are the same for every scheme, and are part of
+    /// the reproducibility guarantee, so they can be checked against other implementations.
+    ///
+    /// ```rust
+    /// use random_access_rng::{RandomAccessRNG, Scheme};
+    ///
+    /// let rng = RandomAccessRNG::new("seed");
+    /// assert_eq!(rng.seek_raw(1000), 21079855626366989975691345281224923247);
+    ///
+    /// // From V4, positions 0 and 1 share the hash of block 0, in the upper half of the positions
+    /// let v4 = RandomAccessRNG::with_scheme("seed", Scheme::V4);
+    /// let block = v4.seek_raw(1 << 127);
+    /// assert_eq!(v4.peek_at(0), block as u64);
+    /// assert_eq!(v4.peek_at(1), (block >> 64) as u64);
+    /// ```
+    pub fn seek_raw(&self, index: u128) -> u128 {
+        self.value_at(index)
+    }
+
+    /// Seek to any 128-bit position and return the full 128-bit value there.
+    ///
+    /// The 128-bit counterpart of [`seek_u64_at`](RandomAccessRNG::seek_u64_at), equal to
+    /// [`seek_u128`](RandomAccessRNG::seek_u128) for indices up to `u64::MAX`.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// let index = u128::MAX - 5;
+    /// let wide = rng.seek_u128_at(index);
+    /// assert_eq!(wide as u64, rng.seek_u64_at(index));
+    /// assert_ne!(wide, rng.seek_u128(5));
+    /// ```
+    pub fn seek_u128_at(& mut self, index: u128) -> u128 {
+        self.index = index;
+
+        self.next()
+    }
+
+    /// Seek to the position of the 2D lattice point `(x, y)` and return the 64-bit value there.
+    ///
+    /// The position is [`mix128(x as u128, y as u128)`](crate::mix::mix128), a 128-bit hash of the
+    /// coordinates, so every cell of a lattice can be read from one RNG without deriving a child
+    /// per cell. Hashed positions are scattered over the whole 128-bit position space, so they
+    /// don't collide in practice, either with each other or with the first `u64::MAX` positions
+    /// read by [`seek_u64`](RandomAccessRNG::seek_u64) and sequential draws.
+    ///
+    /// Like `seek_u64`, the RNG continues from the position after the one read, here the hashed
+    /// position plus one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::mix::mix128;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut heights = RandomAccessRNG::new("world_seed").get("heights");
+    ///
+    /// let h = heights.seek2d(120, 45);
+    /// assert_eq!(heights.seek2d(120, 45), h);
+    /// assert_ne!(heights.seek2d(45, 120), h);
+    ///
+    /// // The documented mapping onto the position space
+    /// heights.set_position(mix128(120, 45));
+    /// assert_eq!(heights.next_u64(), h);
+    /// ```
+    pub fn seek2d(& mut self, x: u64, y: u64) -> u64 {
+        self.index = crate::mix::mix128(x as u128, y as u128);
+
+        self.next_word()
+    }
+
+    /// Seek to the position of the 3D lattice point `(x, y, z)` and return the 64-bit value there.
+    ///
+    /// The position is `mix128((x as u128) << 64 | y as u128, z as u128)`, see
+    /// [`seek2d`](RandomAccessRNG::seek2d).
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::mix::mix128;
+    /// use rand_core::RngCore;
+    ///
+    /// let mut density = RandomAccessRNG::new("world_seed").get("density");
+    ///
+    /// let d = density.seek3d(3, 70, 9);
+    ///
+    /// density.set_position(mix128(3 << 64 | 70, 9));
+    /// assert_eq!(density.next_u64(), d);
+    /// ```
+    pub fn seek3d(& mut self, x: u64, y: u64, z: u64) -> u64 {
+        self.index = crate::mix::mix128((x as u128) << 64 | y as u128, z as u128);
+
+        self.next_word()
+    }
+
+    /// Fill `buf` with the bytes starting at `byte_offset` of the stream viewed as a byte array,
+    /// without moving the stream.
+    ///
+    /// Byte `n` of the array is byte `n % 16` of the little endian 128-bit value at position
+    /// `n / 16`, so the result depends only on the offset, never on how a range is split into
+    /// calls. This is the layout [`fill_bytes`](RngCore::fill_bytes) produces from position 0
+    /// from [`Scheme::V3`] onwards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let asset = RandomAccessRNG::new("world_seed").get("texture");
+    ///
+    /// let mut whole = [0u8; 100];
+    /// asset.read_bytes_at(1000, &mut whole);
+    ///
+    /// // Any split of the range reads the same bytes
+    /// let (mut head, mut tail) = ([0u8; 7], [0u8; 93]);
+    /// asset.read_bytes_at(1000, &mut head);
+    /// asset.read_bytes_at(1007, &mut tail);
+    ///
+    /// assert_eq!(whole[..7], head);
+    /// assert_eq!(whole[7..], tail);
+    /// ```
+    pub fn read_bytes_at(&self, byte_offset: u64, buf: &mut [u8]) {
+        self.read_bytes_at_u128(byte_offset as u128, buf)
+    }
+
+    /// Fill `buf` with the bytes starting at any 128-bit `byte_offset` of the stream viewed as a
+    /// byte array, without moving the stream.
+    ///
+    /// Like [`read_bytes_at`](RandomAccessRNG::read_bytes_at), which only reaches the first 2^64
+    /// bytes. The byte array covers the 2^132 bytes of the whole stream and wraps around at its
+    /// end like the positions do, so offsets past `u128::MAX` are the start of the array again.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let asset = RandomAccessRNG::new("world_seed").get("texture");
+    ///
+    /// let mut far = [0u8; 20];
+    /// asset.read_bytes_at_u128((1 << 68) + 8, &mut far);
+    ///
+    /// // Bytes 8 to 15 of the value at position 2^64, then the first 12 of the next one
+    /// let mut rng = asset.clone();
+    /// rng.set_position(1 << 64);
+    /// assert_eq!(far[..8], rng.next_u128().to_le_bytes()[8..]);
+    /// assert_eq!(far[8..], rng.next_u128().to_le_bytes()[..12]);
+    ///
+    /// let mut near = [0u8; 20];
+    /// asset.read_bytes_at(1000, &mut near);
+    /// asset.read_bytes_at_u128(1000, &mut far);
+    /// assert_eq!(near, far);
+    /// ```
+    pub fn read_bytes_at_u128(&self, byte_offset: u128, buf: &mut [u8]) {
+        let mut offset = byte_offset;
+        let mut rest = buf;
+
+        while !rest.is_empty() {
+            let bytes = self.value_at(offset / 16).to_le_bytes();
+            let start = (offset % 16) as usize;
+            let len = rest.len().min(16 - start);
+
+            let (head, tail) = rest.split_at_mut(len);
+            head.copy_from_slice(&bytes[start..start + len]);
+
+            offset = offset.wrapping_add(len as u128);
+            rest = tail;
+        }
     }
 }
 
+/// Shows the node's digest in hex, its position and its scheme.
+///
+/// The digest identifies the node, so two RNGs printed with the same digest and position will
+/// produce the same values. [`TrackedRNG`](crate::tracked::TrackedRNG) also shows the path the
+/// node was derived with.
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use rand_core::RngCore;
+///
+/// let mut rng = RandomAccessRNG::new("seed").get("loot");
+/// rng.next_u64();
+///
+/// let printed = format!("{:?}", rng);
+/// assert_eq!(printed, format!("RandomAccessRNG {{ digest: {:#034x}, index: 1, scheme: V1 }}", rng.digest()));
+/// ```
+impl fmt::Debug for RandomAccessRNG {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RandomAccessRNG")
+            .field("digest", &format_args!("{:#034x}", self.digest()))
+            .field("index", &self.index)
+            .field("scheme", &self.scheme)
+            .finish()
+    }
+}
+
+/// Two RNGs are equal if they are at the same position of the same node, with the same scheme,
+/// and so will produce the same values.
+///
+/// Nodes are compared by [`digest`](RandomAccessRNG::digest), so the same node derived in
+/// different ways is equal, and no values are drawn. How the position was reached, and anything
+/// buffered on the way, is ignored.
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use rand_core::RngCore;
+///
+/// let world = RandomAccessRNG::new("world_seed");
+///
+/// assert_eq!(world.get("biomes").get("forest"), world.path("biomes/forest"));
+/// assert_ne!(world.get("biomes"), world.get("items"));
+///
+/// let mut drawn = world.get("biomes");
+/// drawn.next_u64();
+///
+/// let mut seeked = world.get("biomes");
+/// seeked.set_position(1);
+/// assert_eq!(drawn, seeked);
+/// ```
+impl PartialEq for RandomAccessRNG {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+            && self.scheme == other.scheme
+            && (Arc::ptr_eq(&self.hasher, &other.hasher) || self.digest() == other.digest())
+    }
+}
+
+impl Eq for RandomAccessRNG {}
+
+/// Hashes the [`digest`](RandomAccessRNG::digest) and the position, consistently with
+/// [`PartialEq`], so RNGs can key maps and sets.
+///
+/// The digest and position are written as two `u128`s, so an RNG is also a stable key for
+/// [`get`](RandomAccessRNG::get).
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use std::collections::{HashMap, HashSet};
+///
+/// let chunks = RandomAccessRNG::new("world_seed").get("chunks");
+///
+/// let mut cache = HashMap::new();
+/// cache.insert(chunks.get2(0, 0), "generated");
+/// assert_eq!(cache.get(&chunks.get2(0, 0)), Some(&"generated"));
+///
+/// let unique: HashSet<RandomAccessRNG> = [chunks.get2(1, 1), chunks.get2(1, 1), chunks.get2(1, 2)].into_iter().collect();
+/// assert_eq!(unique.len(), 2);
+/// ```
+impl Hash for RandomAccessRNG {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.digest().hash(state);
+        self.index.hash(state);
+    }
+}
 
 
 
 impl RngCore for RandomAccessRNG {
 
+    /// The low 32 bits of the value at the current position, consuming the whole position. From
+    /// [`Scheme::V6`], four consecutive positions share one hash instead.
     fn next_u32(&mut self) -> u32 {
-        self.next_u64() as u32
+        if self.scheme < Scheme::V6 {
+            return self.next_word() as u32;
+        }
+
+        self.next_part(2) as u32
     }
 
     fn next_u64(&mut self) -> u64 {
-        self.next() as u64
+        self.next_word()
     }
 
     fn fill_bytes(&mut self, b: &mut [u8]) {
-        impls::fill_bytes_via_next(self, b)
+        if self.scheme < Scheme::V3 {
+            return impls::fill_bytes_via_next(self, b);
+        }
+
+        // Each position supplies its full value in little endian order, the last one possibly in part
+        for chunk in b.chunks_mut(16) {
+            chunk.copy_from_slice(&self.next().to_le_bytes()[..chunk.len()]);
+        }
     }
 
 }
 
+/// Seeds roots through `rand_core`'s traits.
+///
+/// [`from_seed`](SeedableRng::from_seed) is `RandomAccessRNG::new(seed)` with the `[u8; 8]`
+/// array as the key, and [`seed_from_u64`](SeedableRng::seed_from_u64) is `rand_core`'s default,
+/// which expands the number into such an array first. Neither agrees with `new` for the same
+/// number, but both are covered by the reproducibility guarantee and won't change;
+/// [`SeededRNG`](crate::seeding::SeededRNG) implements the trait with seeding that agrees.
 impl SeedableRng for RandomAccessRNG {
 
     type Seed = [u8; 8]; //Low entropy for non-crypto RNGs
@@ -363,3 +1902,56 @@ impl SeedableRng for RandomAccessRNG {
     }
 
 }
+
+/// Fuzz targets receive RNGs built from fuzz input: a `u64` position, then the seed bytes.
+///
+/// The RNG is `RandomAccessRNG::new(seed)` positioned at that index, where `seed` is the `&[u8]`
+/// read from the input, so a crashing input can be turned back into an RNG by hand.
+///
+/// ```rust
+/// # #[cfg(feature = "arbitrary")] {
+/// use arbitrary::{Arbitrary, Unstructured};
+/// use random_access_rng::RandomAccessRNG;
+///
+/// let data = [7, 0, 0, 0, 0, 0, 0, 0, b'f', b'u', b'z', b'z'];
+/// let rng = RandomAccessRNG::arbitrary_take_rest(Unstructured::new(&data)).unwrap();
+///
+/// let mut expected = RandomAccessRNG::new(&b"fuzz"[..]);
+/// expected.set_position(7);
+///
+/// assert_eq!(rng.position(), 7);
+/// assert_eq!(rng.digest(), expected.digest());
+/// # }
+/// ```
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for RandomAccessRNG {
+
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let index = u64::arbitrary(u)?;
+        let seed = <&[u8]>::arbitrary(u)?;
+
+        Ok(Self::from_fuzz(seed, index))
+    }
+
+    fn arbitrary_take_rest(mut u: arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let index = u64::arbitrary(&mut u)?;
+        let seed = <&[u8]>::arbitrary_take_rest(u)?;
+
+        Ok(Self::from_fuzz(seed, index))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        arbitrary::size_hint::and(u64::size_hint(depth), <&[u8]>::size_hint(depth))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl RandomAccessRNG {
+
+    /// The RNG seeded with `seed` at position `index`
+    fn from_fuzz(seed: &[u8], index: u64) -> Self {
+        let mut rng = Self::new(seed);
+        rng.set_position(index as u128);
+        rng
+    }
+}
@@ -0,0 +1,199 @@
+//! Unbiased integers in a range.
+//!
+//! `rand`'s uniform integer sampling has changed algorithm between versions, silently changing the
+//! integers a seed produces. The methods here use an algorithm that is part of this crate's
+//! reproducibility guarantee and will never change.
+//!
+//! # Algorithm
+//!
+//! A range of `n` values is sampled with Lemire's multiply-and-reject method. A 64-bit draw `v` is
+//! multiplied by `n` as a 128-bit product. If the low 64 bits of the product are below
+//! `2^64 mod n`, the draw is rejected and another taken; otherwise the result is the low end of the
+//! range plus the high 64 bits of the product. Rejection makes every value exactly equally likely,
+//! and happens with probability below `n / 2^64`, so almost every call consumes one position.
+//! A range covering all 2^64 values uses the draw directly.
+//!
+//! Unlike [`dist::uniform`](crate::dist::uniform), which always consumes exactly one position and
+//! so can be slightly biased for very large ranges, the number of positions consumed here isn't
+//! fixed. Use `dist::uniform` where a fixed number of positions matters more than exactness.
+
+use std::ops::{Bound, RangeBounds};
+use rand_core::RngCore;
+use crate::RandomAccessRNG;
+
+/// The inclusive bounds of `range`, taking `min` and `max` for unbounded ends
+fn bounds<R: RangeBounds<i128>>(range: R, min: i128, max: i128) -> (i128, i128) {
+    let low = match range.start_bound() {
+        Bound::Included(&low) => low,
+        Bound::Excluded(&low) => low + 1,
+        Bound::Unbounded => min,
+    };
+
+    let high = match range.end_bound() {
+        Bound::Included(&high) => high,
+        Bound::Excluded(&high) => high - 1,
+        Bound::Unbounded => max,
+    };
+
+    assert!(low <= high, "cannot sample from an empty range");
+
+    (low, high)
+}
+
+/// Widen the bounds of a range of any integer type to `i128`
+fn widen<T: Copy + Into<i128>, R: RangeBounds<T>>(range: &R) -> (Bound<i128>, Bound<i128>) {
+    let widen = |bound: Bound<&T>| match bound {
+        Bound::Included(&v) => Bound::Included(v.into()),
+        Bound::Excluded(&v) => Bound::Excluded(v.into()),
+        Bound::Unbounded => Bound::Unbounded,
+    };
+
+    (widen(range.start_bound()), widen(range.end_bound()))
+}
+
+/// Integer types with a ranged draw, see [`RandomAccessRNG::range_at`].
+pub trait RangeInt: Sized {
+    /// Draw a uniform value in `range` from `rng`, as the `range_*` method for this type does.
+    fn sample_range<R: RangeBounds<Self>>(rng: &mut RandomAccessRNG, range: R) -> Self;
+}
+
+macro_rules! range_methods {
+    ($($name:ident: $t:ty),*) => {
+        $(
+            impl RangeInt for $t {
+                fn sample_range<R: RangeBounds<$t>>(rng: &mut RandomAccessRNG, range: R) -> $t {
+                    rng.$name(range)
+                }
+            }
+        )*
+
+        impl RandomAccessRNG {
+            $(
+                #[doc = concat!("A uniform `", stringify!($t), "` in `range`, see the [module documentation](crate::range).")]
+                ///
+                /// Accepts any range, such as `a..b`, `a..=b` or `..`.
+                ///
+                /// # Panics
+                ///
+                /// Panics if the range is empty.
+                pub fn $name<R: RangeBounds<$t>>(&mut self, range: R) -> $t {
+                    let (low, high) = bounds(widen(&range), <$t>::MIN as i128, <$t>::MAX as i128);
+
+                    (low + self.below_unbiased((high - low) as u128 + 1) as i128) as $t
+                }
+            )*
+        }
+    };
+}
+
+range_methods!(range_u32: u32, range_u64: u64, range_i32: i32, range_i64: i64);
+
+impl RangeInt for usize {
+    fn sample_range<R: RangeBounds<usize>>(rng: &mut RandomAccessRNG, range: R) -> usize {
+        rng.range_usize(range)
+    }
+}
+
+impl RandomAccessRNG {
+
+    /// A uniform value in `0..n` for `n` up to 2^64, by Lemire's method
+    fn below_unbiased(&mut self, n: u128) -> u64 {
+        if n > u64::MAX as u128 {
+            return self.next_u64();
+        }
+
+        let n = n as u64;
+        let threshold = n.wrapping_neg() % n;
+
+        loop {
+            let product = self.next_u64() as u128 * n as u128;
+
+            if product as u64 >= threshold {
+                return (product >> 64) as u64;
+            }
+        }
+    }
+
+    /// A uniform `usize` in `range`, see the [module documentation](crate::range).
+    ///
+    /// Accepts any range, such as `a..b`, `a..=b` or `..`, and gives the same value as
+    /// [`range_u64`](RandomAccessRNG::range_u64) with the same bounds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let mut rng = RandomAccessRNG::new("seed");
+    ///
+    /// let items = ["sword", "shield", "potion"];
+    /// let item = items[rng.range_usize(0..items.len())];
+    ///
+    /// let damage = rng.range_i64(-5..=5);
+    /// assert!((-5..=5).contains(&damage));
+    ///
+    /// let mut again = RandomAccessRNG::new("seed");
+    /// assert_eq!(again.range_u64(0..3), items.iter().position(|&i| i == item).unwrap() as u64);
+    /// ```
+    pub fn range_usize<R: RangeBounds<usize>>(&mut self, range: R) -> usize {
+        let widen = |bound: Bound<&usize>| match bound {
+            Bound::Included(&v) => Bound::Included(v as u64),
+            Bound::Excluded(&v) => Bound::Excluded(v as u64),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
+        let low = widen(range.start_bound());
+        let high = match range.end_bound() {
+            Bound::Unbounded => Bound::Included(usize::MAX as u64),
+            bound => widen(bound),
+        };
+
+        self.range_u64((low, high)) as usize
+    }
+
+    /// The value the `range_*` method for `T` gives when drawn at position `index`, without moving
+    /// the stream.
+    ///
+    /// The draw starts at `index`, and in the rare case of a rejection continues at `index + 1` and
+    /// on, exactly as it would on a stream positioned at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use std::thread;
+    ///
+    /// let dice = RandomAccessRNG::new("match_seed").get("dice");
+    ///
+    /// // Workers each roll their own slots, without sharing a stream
+    /// let rolls: Vec<u32> = thread::scope(|scope| {
+    ///     let handles: Vec<_> = (0..4u64)
+    ///         .map(|worker| {
+    ///             let dice = &dice;
+    ///             scope.spawn(move || (worker * 25..(worker + 1) * 25).map(|slot| dice.range_at(slot, 1..=6u32)).collect::<Vec<_>>())
+    ///         })
+    ///         .collect();
+    ///
+    ///     handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+    /// });
+    ///
+    /// // The same rolls as drawing them in order
+    /// let mut stream = dice.clone();
+    /// let sequential: Vec<u32> = (0..100).map(|_| stream.range_u32(1..=6)).collect();
+    /// assert_eq!(rolls, sequential);
+    /// ```
+    pub fn range_at<T: RangeInt, R: RangeBounds<T>>(&self, index: u64, range: R) -> T {
+        let mut at = self.clone();
+        at.set_position(index as u128);
+
+        T::sample_range(&mut at, range)
+    }
+}
@@ -0,0 +1,155 @@
+//! Reports for confirming two people see the same generated world.
+//!
+//! When a user reports a bug in generated content, the first question is whether the maintainer
+//! sees the same content for the same seed. A [`ReproReport`] answers it: it records the crate
+//! version, the scheme, and the digest and first values of a set of paths, and both sides can
+//! compare their reports field by field. [`to_json`](ReproReport::to_json) gives a form suitable
+//! for attaching to an issue.
+//!
+//! ```rust
+//! use random_access_rng::report::report;
+//!
+//! let mine = report("world_seed", &["terrain", "dungeon/3/loot"], 4);
+//! let theirs = report("world_seed", &["terrain", "dungeon/3/loot"], 4);
+//!
+//! assert_eq!(mine, theirs);
+//! println!("{}", mine.to_json());
+//! ```
+//!
+//! # JSON Format
+//!
+//! ```json
+//! {
+//!   "crate_version": "0.1.0",
+//!   "scheme": "V1",
+//!   "root_digest": "<32 hex digits>",
+//!   "paths": [
+//!     { "path": "terrain", "digest": "<32 hex digits>", "values": [<first values, as integers>] }
+//!   ]
+//! }
+//! ```
+//!
+//! Digests are written as hexadecimal strings because they don't fit in a JSON number. Values are
+//! the first `depth` 64-bit draws of each path's stream, written as decimal integers.
+
+use std::fmt::Write;
+use std::hash::Hash;
+use crate::{RandomAccessRNG, Scheme};
+
+/// The digest and first values of one path, see [`ReproReport`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PathReport {
+    /// The path below the root
+    pub path: String,
+    /// The [`digest`](RandomAccessRNG::digest) of the node at the path
+    pub digest: u128,
+    /// The first values drawn with `next_u64` from the node at the path
+    pub values: Vec<u64>,
+}
+
+/// A summary of the values a seed generates, see the [module documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReproReport {
+    /// The version of this crate that generated the report
+    pub crate_version: String,
+    /// The [`Scheme`] of the root, as its variant name
+    pub scheme: String,
+    /// The digest of the root
+    pub root_digest: u128,
+    /// Each path, in the order they were given
+    pub paths: Vec<PathReport>,
+}
+
+/// A report of the first `depth` values at each of `paths` below the root seeded with `seed`,
+/// using the default [`Scheme`].
+pub fn report<H: Hash, S: AsRef<str>>(seed: H, paths: &[S], depth: usize) -> ReproReport {
+    report_for(&RandomAccessRNG::new(seed), paths, depth)
+}
+
+/// A report of the first `depth` values at each of `paths` below `root`.
+///
+/// `root` is reported as its digest and scheme, whatever its position; each path starts from
+/// the beginning of its own stream.
+pub fn report_for<S: AsRef<str>>(root: &RandomAccessRNG, paths: &[S], depth: usize) -> ReproReport {
+    let paths = paths
+        .iter()
+        .map(|path| {
+            let mut node = root.path(path.as_ref());
+
+            PathReport {
+                path: path.as_ref().to_string(),
+                digest: node.digest(),
+                values: (0..depth).map(|i| node.seek_u64(i as u64)).collect(),
+            }
+        })
+        .collect();
+
+    ReproReport {
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        scheme: scheme_name(root.scheme()),
+        root_digest: root.digest(),
+        paths,
+    }
+}
+
+/// The variant name of `scheme`
+fn scheme_name(scheme: Scheme) -> String {
+    format!("{:?}", scheme)
+}
+
+/// Append `s` to `out` as a JSON string literal
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
+
+impl ReproReport {
+
+    /// The report as JSON, in the format described in the [module documentation](self).
+    ///
+    /// ```rust
+    /// use random_access_rng::report::report;
+    ///
+    /// let json = report("seed", &["a/b"], 1).to_json();
+    ///
+    /// assert!(json.contains(r#""path": "a/b""#));
+    /// assert!(json.contains(r#""scheme": "V1""#));
+    ///
+    /// // Any JSON parser can read it
+    /// let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    /// assert_eq!(parsed["paths"][0]["values"].as_array().unwrap().len(), 1);
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\n  \"crate_version\": ");
+        write_json_string(&mut out, &self.crate_version);
+        out.push_str(",\n  \"scheme\": ");
+        write_json_string(&mut out, &self.scheme);
+        write!(out, ",\n  \"root_digest\": \"{:032x}\",\n  \"paths\": [", self.root_digest).unwrap();
+
+        for (i, path) in self.paths.iter().enumerate() {
+            out.push_str(if i == 0 { "\n    { \"path\": " } else { ",\n    { \"path\": " });
+            write_json_string(&mut out, &path.path);
+
+            let values: Vec<String> = path.values.iter().map(|v| v.to_string()).collect();
+            write!(out, ", \"digest\": \"{:032x}\", \"values\": [{}] }}", path.digest, values.join(", ")).unwrap();
+        }
+
+        out.push_str(if self.paths.is_empty() { "]\n}" } else { "\n  ]\n}" });
+        out
+    }
+}
@@ -0,0 +1,74 @@
+//! Deterministic rerolls of past draws.
+//!
+//! "Reroll" mechanics let a player replace the outcome of an event, such as the loot in a chest,
+//! with a fresh one. Drawing the replacement from the main stream would shift every later draw,
+//! and drawing it from a thread-local RNG would make it unreproducible. Here each reroll attempt
+//! gets its own node derived from the event's position and the attempt number, so rerolls are as
+//! reproducible and auditable as the original outcome and leave the main stream untouched.
+
+use xxhash_rust::xxh3::xxh3_128;
+use crate::RandomAccessRNG;
+
+/// Domain separating reroll nodes from other prehashed keys, "reroll__" in ASCII
+const REROLL_DOMAIN: u64 = 0x7265726f6c6c5f5f;
+
+impl RandomAccessRNG {
+
+    /// An RNG for attempt number `attempt` at the event that started at position `original_index`.
+    ///
+    /// Attempt 0 is the original outcome: a copy of this RNG positioned at `original_index`.
+    /// Every later attempt is an independent node derived from this one, the index and the
+    /// attempt number, so sampling the same distribution from it gives a new outcome with exactly
+    /// the same odds as the original. Neither this RNG nor any other attempt is affected.
+    ///
+    /// `original_index` is a stream position as given by [`position`](RandomAccessRNG::position).
+    /// The nodes of positions below 2^64 are the same as when this took a `u64`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::dist::{self, Dist};
+    ///
+    /// let loot = dist::weighted([("common", 90), ("rare", 9), ("legendary", 1)]);
+    ///
+    /// let mut chests = RandomAccessRNG::new("world_seed").get("chests");
+    ///
+    /// // Open three chests, remembering where the second one's draw started
+    /// loot.sample(&mut chests);
+    /// let position = chests.position();
+    /// let original = loot.sample(&mut chests);
+    /// let third = loot.sample(&mut chests);
+    ///
+    /// // The player rerolls the second chest twice
+    /// let first_reroll = loot.sample(&mut chests.reroll(position, 1));
+    /// let second_reroll = loot.sample(&mut chests.reroll(position, 2));
+    ///
+    /// // Attempt 0 reproduces the original, and rerolls are themselves reproducible
+    /// assert_eq!(loot.sample(&mut chests.reroll(position, 0)), original);
+    /// assert_eq!(loot.sample(&mut chests.reroll(position, 1)), first_reroll);
+    ///
+    /// // Later draws are unaffected
+    /// chests.set_position(position + 1);
+    /// assert_eq!(loot.sample(&mut chests), third);
+    /// ```
+    pub fn reroll(&self, original_index: u128, attempt: u64) -> Self {
+        if attempt == 0 {
+            let mut original = self.clone();
+            original.set_position(original_index);
+
+            return original;
+        }
+
+        // Positions that fit in a u64 keep their original 24-byte key, wider ones get a 40-byte key
+        let index = original_index.to_le_bytes();
+        let index = if original_index <= u64::MAX as u128 { &index[..8] } else { &index[..] };
+
+        let mut key = Vec::with_capacity(40);
+        key.extend_from_slice(&REROLL_DOMAIN.to_le_bytes());
+        key.extend_from_slice(index);
+        key.extend_from_slice(&attempt.to_le_bytes());
+
+        self.get_prehashed(xxh3_128(&key))
+    }
+}
@@ -0,0 +1,178 @@
+//! Weighted sampling from streams too large to hold in memory.
+//!
+//! [`dist::weighted`](crate::dist::weighted) needs every choice up front. For millions of
+//! candidates, such as every valid placement in a large region, [`weighted_reservoir`] instead
+//! makes one pass over them and keeps only the `k` chosen so far.
+//!
+//! # Algorithm
+//!
+//! This is the A-Res algorithm of Efraimidis and Spirakis. Candidate `i`, counting from 0, gets
+//! the uniform value `u` in `(0, 1]` from the 64-bit value `v` at position `i` of the RNG,
+//! `u = ((v >> 11) + 1) * 2^-53`, and the key `ln(u) / weight`. The `k` candidates with the largest
+//! keys are the sample. This is a weighted sample without replacement: the candidate with the
+//! largest key is distributed as a single weighted draw, the second as a weighted draw from the
+//! rest, and so on.
+//!
+//! Because each candidate's key depends only on its index, weight and the RNG, the sample is the
+//! same however the stream is produced. The variant A-ExpJ, which skips ahead between
+//! insertions to save random draws, isn't used: with random access a draw costs no more than a
+//! skip, and skipping would tie the result to the order of processing.
+//!
+//! The logarithm is computed with basic floating-point operations only, which IEEE 754 requires
+//! to be correctly rounded, so keys are identical on every platform, unlike `f64::ln`.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use crate::RandomAccessRNG;
+
+/// `ln(2)` as an `f64`
+const LN_2: f64 = std::f64::consts::LN_2;
+
+/// The number of terms of the series in [`ln`]
+const LN_TERMS: u32 = 20;
+
+/// The natural logarithm of a positive, finite `x` using only basic arithmetic.
+///
+/// Writes `x = m * 2^e` with `m` in `[1, 2)` and sums `ln(m) = 2 * atanh((m - 1) / (m + 1))` as a
+/// series, whose terms fall by at least a factor of 9 each.
+fn ln(x: f64) -> f64 {
+    let bits = x.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let m = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+
+    let s = (m - 1.0) / (m + 1.0);
+    let s2 = s * s;
+
+    let mut power = s;
+    let mut sum = 0.0;
+
+    for n in 0..LN_TERMS {
+        sum += power / (2 * n + 1) as f64;
+        power *= s2;
+    }
+
+    2.0 * sum + exponent as f64 * LN_2
+}
+
+/// A candidate held in the reservoir, ordered by key and then by index, earlier first
+struct Held<T> {
+    key: f64,
+    index: u64,
+    item: T,
+}
+
+impl<T> PartialEq for Held<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Held<T> {}
+
+impl<T> PartialOrd for Held<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Held<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.total_cmp(&other.key).then(other.index.cmp(&self.index))
+    }
+}
+
+/// A reservoir being filled one candidate at a time, see the [module documentation](self).
+///
+/// Memory use is proportional to `k`, however many candidates are offered.
+pub struct WeightedReservoir<'a, T> {
+    rng: &'a RandomAccessRNG,
+    k: usize,
+    offered: u64,
+    heap: BinaryHeap<Reverse<Held<T>>>,
+}
+
+impl<'a, T> WeightedReservoir<'a, T> {
+
+    /// An empty reservoir keeping `k` candidates, drawing keys from `rng`.
+    pub fn new(rng: &'a RandomAccessRNG, k: usize) -> Self {
+        Self { rng, k, offered: 0, heap: BinaryHeap::with_capacity(k) }
+    }
+
+    /// Offer the next candidate with the given weight.
+    ///
+    /// Candidates with zero weight are never chosen, but still take an index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is negative, infinite or NaN.
+    pub fn offer(&mut self, item: T, weight: f64) {
+        assert!(weight.is_finite() && weight >= 0.0, "invalid weight {}", weight);
+
+        let index = self.offered;
+        self.offered += 1;
+
+        if weight == 0.0 || self.k == 0 {
+            return;
+        }
+
+        let u = ((self.rng.peek_at(index) >> 11) + 1) as f64 * (1.0 / (1u64 << 53) as f64);
+        let held = Held { key: ln(u) / weight, index, item };
+
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(held));
+        } else if let Some(mut smallest) = self.heap.peek_mut()
+            && held > smallest.0
+        {
+            *smallest = Reverse(held);
+        }
+    }
+
+    /// The number of candidates offered so far.
+    pub fn offered(&self) -> u64 {
+        self.offered
+    }
+
+    /// The chosen candidates, largest key first, so the first is a weighted draw from every
+    /// candidate, the second a weighted draw from the others, and so on.
+    pub fn into_sample(self) -> Vec<T> {
+        self.heap.into_sorted_vec().into_iter().map(|Reverse(held)| held.item).collect()
+    }
+}
+
+/// A weighted sample of `k` candidates without replacement, from a single pass over `candidates`,
+/// see the [module documentation](self).
+///
+/// The sample is in the order described by [`WeightedReservoir::into_sample`]. If fewer than `k`
+/// candidates have a non-zero weight, all of them are returned.
+///
+/// # Panics
+///
+/// Panics if a weight is negative, infinite or NaN.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::reservoir::weighted_reservoir;
+///
+/// let placement = RandomAccessRNG::new("world_seed").get("trees");
+///
+/// // Ten trees among a million candidate sites, favouring sites near water
+/// let sites = (0..1_000_000u32).map(|site| (site, if site % 100 < 10 { 5.0 } else { 1.0 }));
+/// let trees = weighted_reservoir(&placement, sites, 10);
+///
+/// assert_eq!(trees.len(), 10);
+///
+/// // Reproducible from the same candidates
+/// let again = weighted_reservoir(&placement, (0..1_000_000u32).map(|site| (site, if site % 100 < 10 { 5.0 } else { 1.0 })), 10);
+/// assert_eq!(trees, again);
+/// ```
+pub fn weighted_reservoir<T, I: IntoIterator<Item = (T, f64)>>(rng: &RandomAccessRNG, candidates: I, k: usize) -> Vec<T> {
+    let mut reservoir = WeightedReservoir::new(rng, k);
+
+    for (item, weight) in candidates {
+        reservoir.offer(item, weight);
+    }
+
+    reservoir.into_sample()
+}
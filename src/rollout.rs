@@ -0,0 +1,46 @@
+//! Percentage rollouts for feature flags.
+//!
+//! Rolling a feature out to a percentage of users needs a decision that is stable per user, so a
+//! user doesn't flicker in and out of the feature between sessions, and monotonic in the
+//! percentage, so raising it from 10% to 20% keeps everyone who was already enrolled. Deriving the
+//! decision from this crate gives both, and because derived values never change between versions,
+//! enrolment survives upgrades too.
+
+use std::hash::Hash;
+use crate::RandomAccessRNG;
+
+/// Whether the user identified by `user_key` is enrolled in `feature_key` at `percent`, from 0 to
+/// 100.
+///
+/// Each user has a fixed position for each feature, derived from the digest of
+/// `rng.get(feature_key).get(user_key)`, and is enrolled when that position falls below the
+/// threshold set by `percent`. Raising `percent` therefore only ever enrols more users. Positions
+/// for different features are independent, so the same users aren't always the first to receive
+/// every feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::rollout::rollout;
+///
+/// let flags = RandomAccessRNG::new("live_ops");
+///
+/// let enrolled = |percent| (0..10_000u32).filter(|&user| rollout(&flags, user, "new_shop", percent)).collect::<Vec<_>>();
+///
+/// let at_10 = enrolled(10.0);
+/// let at_25 = enrolled(25.0);
+///
+/// // Everyone enrolled at 10% is still enrolled at 25%
+/// assert!(at_10.iter().all(|user| at_25.contains(user)));
+/// assert!((900..1100).contains(&at_10.len()));
+///
+/// assert!(!rollout(&flags, 7u32, "new_shop", 0.0));
+/// assert!(rollout(&flags, 7u32, "new_shop", 100.0));
+/// ```
+pub fn rollout<U: Hash, F: Hash>(rng: &RandomAccessRNG, user_key: U, feature_key: F, percent: f64) -> bool {
+    let threshold = (percent.clamp(0.0, 100.0) / 100.0 * 18446744073709551616.0) as u128;
+    let position = rng.get(feature_key).get(user_key).digest() as u64;
+
+    (position as u128) < threshold
+}
@@ -0,0 +1,368 @@
+//! Samplers that give design guarantees pure probability tables can't.
+//!
+//! Drawing each item independently from a probability table only makes outcomes correct *on
+//! average*: a 3% drop chance can still produce ten rares in a row, or none in a thousand draws.
+//! The samplers here keep the random ordering while guaranteeing exact counts, and like everything
+//! else in this crate they support random access by draw index.
+
+use std::fmt;
+use crate::RandomAccessRNG;
+use crate::permutation::Permutation;
+use crate::random_access_rng::xxh3_integer_hash;
+
+/// A sampler that hands out categories with exact counts per period.
+///
+/// The draws are split into consecutive periods whose length is the sum of the quotas. Within each
+/// period every category appears exactly as many times as its quota, in an order given by a
+/// [`Permutation`] derived from the RNG node and the period number. Any draw can be looked up
+/// directly with [`category_at`](QuotaSampler::category_at).
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::sampling::QuotaSampler;
+///
+/// let loot = RandomAccessRNG::new("world_seed").get("chest_loot");
+///
+/// // Exactly 3 rares, 17 uncommons and 80 commons in every 100 draws
+/// let sampler = QuotaSampler::new(&loot, &[80, 17, 3]);
+/// assert_eq!(sampler.period(), 100);
+///
+/// for period in 0..5 {
+///     let rares = (period * 100..(period + 1) * 100)
+///         .filter(|&i| sampler.category_at(i) == 2)
+///         .count();
+///
+///     assert_eq!(rares, 3);
+/// }
+///
+/// // The order is still random, and the same for everyone
+/// let again = QuotaSampler::new(&loot, &[80, 17, 3]);
+/// assert_eq!(sampler.category_at(12345), again.category_at(12345));
+/// ```
+#[derive(Clone, Debug)]
+pub struct QuotaSampler {
+    key: u128,
+    cumulative: Vec<u64>,
+}
+
+impl QuotaSampler {
+
+    /// Create a sampler giving category `i` exactly `quotas[i]` draws per period.
+    ///
+    /// The sampler is determined by the RNG node, not its stream position. Use a dedicated child
+    /// node (for example `rng.get("loot")`) so the sampler is independent of other values drawn
+    /// from the same node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the quotas sum to zero or overflow a `u64`.
+    pub fn new(rng: &RandomAccessRNG, quotas: &[u64]) -> Self {
+        let cumulative: Vec<u64> = quotas
+            .iter()
+            .scan(0u64, |total, &quota| {
+                *total = total.checked_add(quota).expect("Quotas overflow u64");
+                Some(*total)
+            })
+            .collect();
+
+        assert!(cumulative.last().is_some_and(|&total| total > 0), "Quotas must sum to a non-zero value");
+
+        Self {
+            key: rng.digest(),
+            cumulative,
+        }
+    }
+
+    /// The number of draws in each period, equal to the sum of the quotas.
+    pub fn period(&self) -> u64 {
+        *self.cumulative.last().unwrap()
+    }
+
+    /// The category produced by draw number `index`.
+    pub fn category_at(&self, index: u64) -> usize {
+        let period = self.period();
+        let block = index / period;
+
+        let permutation = Permutation::from_key(xxh3_integer_hash(self.key ^ block as u128), period);
+        let slot = permutation.get(index % period);
+
+        // First category whose cumulative count exceeds the slot
+        self.cumulative.partition_point(|&end| end <= slot)
+    }
+
+    /// Iterate over the categories produced by draws `0, 1, 2, ...`.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..).map(|i| self.category_at(i))
+    }
+}
+
+/// Serializable state of a [`NoRepeatSampler`], see [`NoRepeatSampler::state`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NoRepeatState {
+    /// Number of picks made so far
+    pub index: u64,
+    /// The most recent picks, oldest first, at most `window` long
+    pub recent: Vec<u64>,
+}
+
+impl NoRepeatState {
+
+    /// Encode the state as little-endian bytes: the index followed by each recent pick.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        std::iter::once(self.index)
+            .chain(self.recent.iter().copied())
+            .flat_map(u64::to_le_bytes)
+            .collect()
+    }
+
+    /// Decode a state produced by [`to_bytes`](NoRepeatState::to_bytes).
+    ///
+    /// Returns [`None`] if the length of `bytes` is not a non-zero multiple of 8.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.is_empty() || !bytes.len().is_multiple_of(8) {
+            return None;
+        }
+
+        let mut words = bytes
+            .chunks_exact(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()));
+
+        Some(Self {
+            index: words.next()?,
+            recent: words.collect(),
+        })
+    }
+}
+
+/// Error returned by [`NoRepeatSampler::restore`] for a state the sampler could not have produced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidNoRepeatState;
+
+impl fmt::Display for InvalidNoRepeatState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "recent picks must be distinct and below the number of items")
+    }
+}
+
+impl std::error::Error for InvalidNoRepeatState {}
+
+/// A sampler over `0..count` that never repeats any of the last `window` picks.
+///
+/// Intended for things like music shuffle and voice line variety, where a pure uniform choice
+/// repeats itself far too often for players' liking. Two modes are provided, which produce
+/// different sequences:
+///
+/// - **Stateful** ([`pick`](NoRepeatSampler::pick)): each pick is uniform over the items not in
+///   the recent window. Works for any `window < count`, and its state can be saved and restored
+///   with [`state`](NoRepeatSampler::state) and [`restore`](NoRepeatSampler::restore).
+/// - **Pure** ([`nth_pick`](NoRepeatSampler::nth_pick)): a function of the pick index alone,
+///   built from one [`Permutation`] per block of `count` picks with the start of each block
+///   reordered to avoid the end of the previous one. Needs `3 * window <= count`.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::sampling::NoRepeatSampler;
+///
+/// let barks = RandomAccessRNG::new("world_seed").get("guard_barks");
+///
+/// // Six voice lines, never repeating any of the last four
+/// let mut sampler = NoRepeatSampler::new(&barks, 6, 4);
+/// let picks: Vec<u64> = (0..100).map(|_| sampler.pick()).collect();
+///
+/// for window in picks.windows(5) {
+///     assert!(!window[..4].contains(&window[4]));
+/// }
+///
+/// // Save the state and resume later
+/// let saved = sampler.state().to_bytes();
+/// let expected = sampler.pick();
+///
+/// let mut resumed = NoRepeatSampler::new(&barks, 6, 4);
+/// resumed.restore(random_access_rng::sampling::NoRepeatState::from_bytes(&saved).unwrap()).unwrap();
+/// assert_eq!(resumed.pick(), expected);
+/// ```
+///
+/// ## Pure mode
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::sampling::NoRepeatSampler;
+///
+/// let playlist = RandomAccessRNG::new("user_42").get("shuffle");
+///
+/// // Twenty songs, never repeating any of the last five
+/// let sampler = NoRepeatSampler::new(&playlist, 20, 5);
+///
+/// // Any pick can be computed directly, in any order
+/// let picks: Vec<u64> = (0..200).map(|i| sampler.nth_pick(i)).collect();
+///
+/// for window in picks.windows(6) {
+///     assert!(!window[..5].contains(&window[5]));
+/// }
+///
+/// // Every song is played once per block of twenty picks
+/// let mut block: Vec<u64> = picks[40..60].to_vec();
+/// block.sort();
+/// assert_eq!(block, (0..20).collect::<Vec<_>>());
+/// ```
+#[derive(Clone)]
+pub struct NoRepeatSampler {
+    rng: RandomAccessRNG,
+    key: u128,
+    count: u64,
+    window: usize,
+    state: NoRepeatState,
+}
+
+impl NoRepeatSampler {
+
+    /// Create a sampler over `0..count` avoiding the last `window` picks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is not less than `count`.
+    pub fn new(rng: &RandomAccessRNG, count: u64, window: usize) -> Self {
+        assert!((window as u64) < count, "window must be smaller than the number of items");
+
+        Self {
+            rng: rng.clone(),
+            key: rng.digest(),
+            count,
+            window,
+            state: NoRepeatState {
+                index: 0,
+                recent: Vec::with_capacity(window),
+            },
+        }
+    }
+
+    /// Make the next pick in stateful mode.
+    pub fn pick(&mut self) -> u64 {
+        let mut recent = self.state.recent.clone();
+        recent.sort_unstable();
+
+        // Uniform rank among the items not in the recent window
+        let available = self.count - recent.len() as u64;
+        let mut pick = ((self.rng.seek_u64(self.state.index) as u128 * available as u128) >> 64) as u64;
+
+        // Skip over recent items to turn the rank into an item
+        for &r in &recent {
+            if r <= pick {
+                pick += 1;
+            }
+        }
+
+        if self.window > 0 {
+            if self.state.recent.len() == self.window {
+                self.state.recent.remove(0);
+            }
+
+            self.state.recent.push(pick);
+        }
+
+        self.state.index += 1;
+
+        pick
+    }
+
+    /// The current state of the stateful mode.
+    pub fn state(&self) -> &NoRepeatState {
+        &self.state
+    }
+
+    /// Restore a state previously returned by [`state`](NoRepeatSampler::state).
+    ///
+    /// Only the last `window` entries of `state.recent` are kept. Fails, leaving the sampler
+    /// unchanged, if those entries aren't distinct items below `count`, which would bias the
+    /// following picks.
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::sampling::{InvalidNoRepeatState, NoRepeatSampler, NoRepeatState};
+    ///
+    /// let mut sampler = NoRepeatSampler::new(&RandomAccessRNG::new("seed"), 5, 2);
+    ///
+    /// let out_of_range = NoRepeatState { index: 3, recent: vec![10] };
+    /// assert_eq!(sampler.restore(out_of_range), Err(InvalidNoRepeatState));
+    ///
+    /// let repeated = NoRepeatState { index: 3, recent: vec![1, 1] };
+    /// assert_eq!(sampler.restore(repeated), Err(InvalidNoRepeatState));
+    ///
+    /// // Entries beyond the window are dropped before checking
+    /// let long = NoRepeatState { index: 3, recent: vec![10, 4, 0] };
+    /// assert_eq!(sampler.restore(long), Ok(()));
+    /// assert_eq!(sampler.state().recent, [4, 0]);
+    /// ```
+    pub fn restore(&mut self, mut state: NoRepeatState) -> Result<(), InvalidNoRepeatState> {
+        let excess = state.recent.len().saturating_sub(self.window);
+        state.recent.drain(..excess);
+
+        let mut recent = state.recent.clone();
+        recent.sort_unstable();
+
+        if recent.last().is_some_and(|&last| last >= self.count) || recent.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(InvalidNoRepeatState);
+        }
+
+        self.state = state;
+
+        Ok(())
+    }
+
+    /// Permutation used for a block of `count` picks in pure mode
+    fn block(&self, block: u64) -> Permutation {
+        Permutation::from_key(xxh3_integer_hash(self.key ^ block as u128), self.count)
+    }
+
+    /// The pick at position `index` in pure mode.
+    ///
+    /// Takes `O(window)` time, independently of `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `3 * window` is greater than `count`.
+    pub fn nth_pick(&self, index: u64) -> u64 {
+        let window = self.window as u64;
+        assert!(window.saturating_mul(3) <= self.count, "pure mode requires 3 * window <= count");
+
+        let (block, position) = (index / self.count, index % self.count);
+        let current = self.block(block);
+
+        // The tail of every block is never reordered, and the first block has nothing to avoid
+        if block == 0 || position >= self.count - window {
+            return current.get(position);
+        }
+
+        let previous = self.block(block - 1);
+        let avoid: Vec<u64> = (self.count - window..self.count).map(|i| previous.get(i)).collect();
+
+        // The head is filled with the first `window` items not in the previous tail, followed by
+        // any skipped items. Beyond that point the block is left unchanged.
+        let mut head = Vec::with_capacity(2 * self.window);
+        let mut deferred = Vec::with_capacity(self.window);
+        let mut scanned = 0;
+
+        while (head.len() as u64) < window {
+            let item = current.get(scanned);
+
+            if avoid.contains(&item) {
+                deferred.push(item);
+            } else {
+                head.push(item);
+            }
+
+            scanned += 1;
+        }
+
+        if position >= scanned {
+            return current.get(position);
+        }
+
+        head.extend(deferred);
+        head[position as usize]
+    }
+}
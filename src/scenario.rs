@@ -0,0 +1,193 @@
+//! Reproducible starting conditions for simulations.
+//!
+//! A [`ScenarioBuilder`] declares named parameters, each with the distribution it is drawn from.
+//! Instantiating it against an RNG node resolves every parameter and records the results in a
+//! [`Scenario`], whose [`Display`](fmt::Display) output is a manifest listing the node's digest
+//! and every resolved value. Storing the manifest next to a simulation's output documents exactly
+//! which initial conditions produced it, and anyone with the same node can regenerate them.
+//!
+//! Each parameter is drawn from the child of the node named after it, so adding, removing or
+//! reordering parameters never changes the values of the others.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::dist::{self, Dist};
+//! use random_access_rng::scenario::{ScenarioBuilder, Value};
+//!
+//! let epidemic = ScenarioBuilder::new()
+//!     .parameter("population", dist::uniform(10_000..=50_000u32))
+//!     .parameter("initial_infected", dist::uniform(1..=20u32))
+//!     .parameter("strain", dist::weighted([("alpha", 3), ("beta", 1)]))
+//!     .parameter("lockdown", dist::bernoulli(1, 4));
+//!
+//! let run = epidemic.instantiate(&RandomAccessRNG::new("trial_17"));
+//!
+//! assert!(matches!(run.get("population"), Some(Value::Int(10_000..=50_000))));
+//!
+//! // The manifest lists every value, and the same node always resolves to the same scenario
+//! println!("{}", run);
+//! assert_eq!(epidemic.instantiate(&RandomAccessRNG::new("trial_17")), run);
+//! ```
+//!
+//! # Manifest Format
+//!
+//! The first line is `# scenario` followed by the node's [`digest`](RandomAccessRNG::digest) in
+//! hexadecimal. Every following line is `name = value`, in the order the parameters were declared,
+//! with text values in double quotes.
+
+use std::fmt;
+use crate::RandomAccessRNG;
+use crate::dist::Dist;
+
+/// A resolved parameter value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// A boolean
+    Bool(bool),
+    /// An integer of any width
+    Int(i128),
+    /// A floating point number
+    Float(f64),
+    /// Text
+    Text(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Float(value) => write!(f, "{:?}", value),
+            Value::Text(value) => write!(f, "{:?}", value),
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+macro_rules! impl_value_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(value: $t) -> Self {
+                    Value::Int(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_value_from_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, i128);
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::Float(value as f64)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Text(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Text(value)
+    }
+}
+
+/// A parameter's name and how to resolve it
+type Parameter = (String, Box<dyn Fn(&RandomAccessRNG) -> Value>);
+
+/// A set of named parameters and their distributions, see the [module documentation](self).
+#[derive(Default)]
+pub struct ScenarioBuilder {
+    parameters: Vec<Parameter>,
+}
+
+impl ScenarioBuilder {
+
+    /// A scenario with no parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare a parameter named `name`, drawn from `dist`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a parameter named `name` has already been declared.
+    pub fn parameter<D: Dist + 'static>(mut self, name: &str, dist: D) -> Self where D::Output: Into<Value> {
+        assert!(self.parameters.iter().all(|(existing, _)| existing != name), "parameter {:?} is declared twice", name);
+
+        let key = name.to_string();
+        self.parameters.push((name.to_string(), Box::new(move |rng| dist.sample_at(rng, key.as_str()).into())));
+        self
+    }
+
+    /// The names of the declared parameters, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.parameters.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Resolve every parameter against `rng`.
+    pub fn instantiate(&self, rng: &RandomAccessRNG) -> Scenario {
+        Scenario {
+            digest: rng.digest(),
+            values: self.parameters
+                .iter()
+                .map(|(name, resolve)| (name.clone(), resolve(rng)))
+                .collect(),
+        }
+    }
+}
+
+/// A resolved scenario, created by [`ScenarioBuilder::instantiate`].
+///
+/// Displays as a manifest, see [Manifest Format](self#manifest-format).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scenario {
+    digest: u128,
+    values: Vec<(String, Value)>,
+}
+
+impl Scenario {
+
+    /// The digest of the node the scenario was resolved against.
+    pub fn digest(&self) -> u128 {
+        self.digest
+    }
+
+    /// The value of the parameter named `name`, if there is one.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.iter().find(|(n, _)| n == name).map(|(_, value)| value)
+    }
+
+    /// Every parameter's name and value, in the order they were declared.
+    pub fn values(&self) -> &[(String, Value)] {
+        &self.values
+    }
+}
+
+impl fmt::Display for Scenario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# scenario {:032x}", self.digest)?;
+
+        for (name, value) in &self.values {
+            writeln!(f, "{} = {}", name, value)?;
+        }
+
+        Ok(())
+    }
+}
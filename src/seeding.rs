@@ -0,0 +1,176 @@
+//! `SeedableRng` constructors that agree with the native ones.
+//!
+//! [`RandomAccessRNG`]'s own [`SeedableRng`] implementation predates the native constructors'
+//! conventions: [`from_seed`](SeedableRng::from_seed) hashes its `[u8; 8]` as an array, length
+//! included, and [`seed_from_u64`](SeedableRng::seed_from_u64) expands the number with
+//! `rand_core`'s default PCG step first. Neither gives the stream of `RandomAccessRNG::new(n)` for
+//! the same number `n`. Both are covered by the reproducibility guarantee, so instead of changing
+//! them, [`SeededRNG`] implements the trait with seeding that matches.
+//!
+//! Overriding the constructors on `RandomAccessRNG` itself behind a new [`Scheme`](crate::Scheme)
+//! was considered and deliberately rejected. The trait's constructors take no scheme, so there is
+//! no way to opt into the new seeding through them, and changing what they return for every caller
+//! would break the guarantee. A separate type is opted into by naming it instead:
+//!
+//! | Constructor                             | Same RNG as                                       |
+//! |-----------------------------------------|---------------------------------------------------|
+//! | `SeededRNG::seed_from_u64(n)`           | `RandomAccessRNG::new(n)`                         |
+//! | `SeededRNG::from_seed(n.to_le_bytes())` | `RandomAccessRNG::new(n)`                         |
+//! | `WideSeededRNG::seed_from_u64(n)`       | `RandomAccessRNG::new(n)`                         |
+//! | `WideSeededRNG::from_seed(bytes)`       | `RandomAccessRNG::with_hasher` of the 32 bytes    |
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::seeding::SeededRNG;
+//! use rand_core::{RngCore, SeedableRng};
+//!
+//! // Generic code that only knows SeedableRng
+//! fn first_value<R: RngCore + SeedableRng>(seed: u64) -> u64 {
+//!     R::seed_from_u64(seed).next_u64()
+//! }
+//!
+//! assert_eq!(first_value::<SeededRNG>(42), RandomAccessRNG::new(42u64).next_u64());
+//!
+//! // The original implementation gives a different stream
+//! assert_ne!(first_value::<RandomAccessRNG>(42), RandomAccessRNG::new(42u64).next_u64());
+//! ```
+//!
+//! # Seed Width
+//!
+//! The `[u8; 8]` seeds of [`RandomAccessRNG`] and [`SeededRNG`] hold only 64 bits, so generic
+//! code that seeds them from another RNG, with [`from_rng`](SeedableRng::from_rng), uses a
+//! fraction of the keyspace. [`WideSeededRNG`] takes a `[u8; 32]` seed and hashes all of it:
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::seeding::WideSeededRNG;
+//! use rand_core::{RngCore, SeedableRng};
+//! use xxhash_rust::xxh3::Xxh3;
+//!
+//! let mut parent = RandomAccessRNG::new("world_seed");
+//!
+//! let mut seed = [0u8; 32];
+//! parent.clone().fill_bytes(&mut seed);
+//!
+//! // All 32 bytes drawn from the parent seed the child
+//! let child = WideSeededRNG::from_rng(&mut parent);
+//!
+//! let mut hasher = Xxh3::new();
+//! hasher.update(&seed);
+//! assert_eq!(*child, RandomAccessRNG::with_hasher(hasher));
+//! ```
+
+use std::ops::{Deref, DerefMut};
+use rand_core::SeedableRng;
+use xxhash_rust::xxh3::Xxh3;
+use crate::RandomAccessRNG;
+
+/// A [`RandomAccessRNG`] whose [`SeedableRng`] constructors agree with
+/// [`RandomAccessRNG::new`], see the [module documentation](self).
+///
+/// Dereferences to the wrapped RNG, and so implements [`RngCore`](rand_core::RngCore) and can
+/// derive children like any RNG.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SeededRNG {
+    rng: RandomAccessRNG,
+}
+
+impl SeededRNG {
+
+    /// The wrapped RNG, at its current position.
+    pub fn into_inner(self) -> RandomAccessRNG {
+        self.rng
+    }
+}
+
+impl From<RandomAccessRNG> for SeededRNG {
+    fn from(rng: RandomAccessRNG) -> Self {
+        Self { rng }
+    }
+}
+
+impl SeedableRng for SeededRNG {
+
+    type Seed = [u8; 8];
+
+    /// The RNG of `RandomAccessRNG::new(u64::from_le_bytes(seed))`.
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::seed_from_u64(u64::from_le_bytes(seed))
+    }
+
+    /// The RNG of `RandomAccessRNG::new(state)`.
+    fn seed_from_u64(state: u64) -> Self {
+        Self { rng: RandomAccessRNG::new(state) }
+    }
+}
+
+/// A [`RandomAccessRNG`] with a 32-byte [`SeedableRng`] seed, see
+/// [Seed Width](self#seed-width).
+///
+/// Dereferences to the wrapped RNG, and so implements [`RngCore`](rand_core::RngCore) and can
+/// derive children like any RNG.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WideSeededRNG {
+    rng: RandomAccessRNG,
+}
+
+impl WideSeededRNG {
+
+    /// The wrapped RNG, at its current position.
+    pub fn into_inner(self) -> RandomAccessRNG {
+        self.rng
+    }
+}
+
+impl From<RandomAccessRNG> for WideSeededRNG {
+    fn from(rng: RandomAccessRNG) -> Self {
+        Self { rng }
+    }
+}
+
+impl SeedableRng for WideSeededRNG {
+
+    type Seed = [u8; 32];
+
+    /// The RNG of [`RandomAccessRNG::with_hasher`] with all 32 bytes of `seed` written to the
+    /// hasher.
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut hasher = Xxh3::new();
+        hasher.update(&seed);
+
+        Self { rng: RandomAccessRNG::with_hasher(hasher) }
+    }
+
+    /// The RNG of `RandomAccessRNG::new(state)`, as for [`SeededRNG`].
+    fn seed_from_u64(state: u64) -> Self {
+        Self { rng: RandomAccessRNG::new(state) }
+    }
+}
+
+impl Deref for SeededRNG {
+    type Target = RandomAccessRNG;
+
+    fn deref(&self) -> &RandomAccessRNG {
+        &self.rng
+    }
+}
+
+impl DerefMut for SeededRNG {
+    fn deref_mut(&mut self) -> &mut RandomAccessRNG {
+        &mut self.rng
+    }
+}
+
+impl Deref for WideSeededRNG {
+    type Target = RandomAccessRNG;
+
+    fn deref(&self) -> &RandomAccessRNG {
+        &self.rng
+    }
+}
+
+impl DerefMut for WideSeededRNG {
+    fn deref_mut(&mut self) -> &mut RandomAccessRNG {
+        &mut self.rng
+    }
+}
@@ -0,0 +1,286 @@
+//! Shader source for generating values on the GPU.
+//!
+//! Terrain and effects generated on the GPU often need to match values the CPU uses for gameplay,
+//! such as where ore appears or which tiles are walkable. [`WGSL`] and [`GLSL`] hold shader
+//! source for `rarng_hash_index(digest, index)`, which computes the 128-bit value at `index` of
+//! the node with `digest` exactly like [`hash_index`]. Digests are
+//! computed on the CPU, for example with [`child_digests`](RandomAccessRNG::child_digests), and
+//! passed to the shader.
+//!
+//! Neither language has a portable 64-bit integer type, so 128-bit values are `vec4<u32>` in
+//! WGSL and `uvec4` in GLSL, with the least significant 32 bits first. The 64-bit value drawn by
+//! [`next_u64`](rand_core::RngCore::next_u64) is the first two components up to
+//! [`Scheme::V3`](crate::Scheme::V3).
+//!
+//! # Parity
+//!
+//! [`reference_hash`] is a transliteration of the shaders into Rust using the same 32-bit
+//! operations, and is checked against the CPU implementation on [`parity_vectors`]. Running the
+//! same vectors through the shader on a given device and comparing with
+//! [`expected`](ParityVector::expected) checks that device.
+//!
+//! ```rust
+//! use random_access_rng::shader::{parity_vectors, reference_hash, to_limbs};
+//!
+//! for vector in parity_vectors() {
+//!     assert_eq!(reference_hash(to_limbs(vector.digest), to_limbs(vector.index)), to_limbs(vector.expected));
+//! }
+//! ```
+
+use crate::RandomAccessRNG;
+use crate::mix::hash_index;
+
+/// WGSL source defining `fn rarng_hash_index(digest: vec4<u32>, index: vec4<u32>) -> vec4<u32>`.
+///
+/// All other items it defines are prefixed with `rarng_`.
+pub const WGSL: &str = r#"// Index hash of random_access_rng, see the shader module of the crate
+const RARNG_PRIME64_1: vec2<u32> = vec2<u32>(0x85ebca87u, 0x9e3779b1u);
+const RARNG_PRIME64_2: vec2<u32> = vec2<u32>(0x27d4eb4fu, 0xc2b2ae3du);
+const RARNG_AVALANCHE: vec2<u32> = vec2<u32>(0x9e3779f9u, 0x16566791u);
+const RARNG_FLIP_LO: vec2<u32> = vec2<u32>(0x33362349u, 0x59973f00u);
+const RARNG_FLIP_HI: vec2<u32> = vec2<u32>(0x92d63d58u, 0xc2027976u);
+const RARNG_LENGTH: vec2<u32> = vec2<u32>(0x00000000u, 0x03c00000u);
+const RARNG_PRIME32_2_MINUS_1: u32 = 0x85ebca76u;
+
+fn rarng_mul32(a: u32, b: u32) -> vec2<u32> {
+    let a0 = a & 0xffffu;
+    let a1 = a >> 16u;
+    let b0 = b & 0xffffu;
+    let b1 = b >> 16u;
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+    let mid = (p00 >> 16u) + (p01 & 0xffffu) + (p10 & 0xffffu);
+    return vec2<u32>((p00 & 0xffffu) | (mid << 16u), p11 + (p01 >> 16u) + (p10 >> 16u) + (mid >> 16u));
+}
+
+fn rarng_add64(a: vec2<u32>, b: vec2<u32>) -> vec2<u32> {
+    let lo = a.x + b.x;
+    return vec2<u32>(lo, a.y + b.y + select(0u, 1u, lo < a.x));
+}
+
+fn rarng_mul64(a: vec2<u32>, b: vec2<u32>) -> vec2<u32> {
+    let lo = rarng_mul32(a.x, b.x);
+    return vec2<u32>(lo.x, lo.y + a.x * b.y + a.y * b.x);
+}
+
+fn rarng_mul64_to128(a: vec2<u32>, b: vec2<u32>) -> vec4<u32> {
+    let lo_lo = rarng_mul32(a.x, b.x);
+    let hi_lo = rarng_mul32(a.y, b.x);
+    let lo_hi = rarng_mul32(a.x, b.y);
+    let hi_hi = rarng_mul32(a.y, b.y);
+    let middle = rarng_add64(rarng_add64(vec2<u32>(lo_lo.y, 0u), vec2<u32>(hi_lo.x, 0u)), lo_hi);
+    let upper = rarng_add64(rarng_add64(vec2<u32>(hi_lo.y, 0u), vec2<u32>(middle.y, 0u)), hi_hi);
+    return vec4<u32>(lo_lo.x, middle.x, upper.x, upper.y);
+}
+
+fn rarng_swap32(x: u32) -> u32 {
+    return (x >> 24u) | ((x >> 8u) & 0xff00u) | ((x << 8u) & 0xff0000u) | (x << 24u);
+}
+
+fn rarng_avalanche(v: vec2<u32>) -> vec2<u32> {
+    let a = v ^ vec2<u32>(v.y >> 5u, 0u);
+    let b = rarng_mul64(a, RARNG_AVALANCHE);
+    return b ^ vec2<u32>(b.y, 0u);
+}
+
+fn rarng_hash_index(digest: vec4<u32>, index: vec4<u32>) -> vec4<u32> {
+    let n = digest ^ index;
+    let input_lo = n.xy;
+    let input_hi = n.zw ^ RARNG_FLIP_HI;
+
+    let m = rarng_mul64_to128(input_lo ^ n.zw ^ RARNG_FLIP_LO, RARNG_PRIME64_1);
+    let mul_high = rarng_add64(m.zw, rarng_add64(input_hi, rarng_mul32(input_hi.x, RARNG_PRIME32_2_MINUS_1)));
+    let mul_low = rarng_add64(m.xy, RARNG_LENGTH) ^ vec2<u32>(rarng_swap32(mul_high.y), rarng_swap32(mul_high.x));
+
+    let r = rarng_mul64_to128(mul_low, RARNG_PRIME64_2);
+    let result_hi = rarng_add64(r.zw, rarng_mul64(mul_high, RARNG_PRIME64_2));
+
+    return vec4<u32>(rarng_avalanche(r.xy), rarng_avalanche(result_hi));
+}
+"#;
+
+/// GLSL source defining `uvec4 rarng_hash_index(uvec4 digest, uvec4 index)`.
+///
+/// Requires GLSL 3.30 or GLSL ES 3.00. All other items it defines are prefixed with `rarng_`.
+pub const GLSL: &str = r#"// Index hash of random_access_rng, see the shader module of the crate
+const uvec2 RARNG_PRIME64_1 = uvec2(0x85ebca87u, 0x9e3779b1u);
+const uvec2 RARNG_PRIME64_2 = uvec2(0x27d4eb4fu, 0xc2b2ae3du);
+const uvec2 RARNG_AVALANCHE = uvec2(0x9e3779f9u, 0x16566791u);
+const uvec2 RARNG_FLIP_LO = uvec2(0x33362349u, 0x59973f00u);
+const uvec2 RARNG_FLIP_HI = uvec2(0x92d63d58u, 0xc2027976u);
+const uvec2 RARNG_LENGTH = uvec2(0x00000000u, 0x03c00000u);
+const uint RARNG_PRIME32_2_MINUS_1 = 0x85ebca76u;
+
+uvec2 rarng_mul32(uint a, uint b) {
+    uint a0 = a & 0xffffu;
+    uint a1 = a >> 16u;
+    uint b0 = b & 0xffffu;
+    uint b1 = b >> 16u;
+    uint p00 = a0 * b0;
+    uint p01 = a0 * b1;
+    uint p10 = a1 * b0;
+    uint p11 = a1 * b1;
+    uint mid = (p00 >> 16u) + (p01 & 0xffffu) + (p10 & 0xffffu);
+    return uvec2((p00 & 0xffffu) | (mid << 16u), p11 + (p01 >> 16u) + (p10 >> 16u) + (mid >> 16u));
+}
+
+uvec2 rarng_add64(uvec2 a, uvec2 b) {
+    uint lo = a.x + b.x;
+    return uvec2(lo, a.y + b.y + uint(lo < a.x));
+}
+
+uvec2 rarng_mul64(uvec2 a, uvec2 b) {
+    uvec2 lo = rarng_mul32(a.x, b.x);
+    return uvec2(lo.x, lo.y + a.x * b.y + a.y * b.x);
+}
+
+uvec4 rarng_mul64_to128(uvec2 a, uvec2 b) {
+    uvec2 lo_lo = rarng_mul32(a.x, b.x);
+    uvec2 hi_lo = rarng_mul32(a.y, b.x);
+    uvec2 lo_hi = rarng_mul32(a.x, b.y);
+    uvec2 hi_hi = rarng_mul32(a.y, b.y);
+    uvec2 middle = rarng_add64(rarng_add64(uvec2(lo_lo.y, 0u), uvec2(hi_lo.x, 0u)), lo_hi);
+    uvec2 upper = rarng_add64(rarng_add64(uvec2(hi_lo.y, 0u), uvec2(middle.y, 0u)), hi_hi);
+    return uvec4(lo_lo.x, middle.x, upper.x, upper.y);
+}
+
+uint rarng_swap32(uint x) {
+    return (x >> 24u) | ((x >> 8u) & 0xff00u) | ((x << 8u) & 0xff0000u) | (x << 24u);
+}
+
+uvec2 rarng_avalanche(uvec2 v) {
+    uvec2 a = v ^ uvec2(v.y >> 5u, 0u);
+    uvec2 b = rarng_mul64(a, RARNG_AVALANCHE);
+    return b ^ uvec2(b.y, 0u);
+}
+
+uvec4 rarng_hash_index(uvec4 digest, uvec4 index) {
+    uvec4 n = digest ^ index;
+    uvec2 input_lo = n.xy;
+    uvec2 input_hi = n.zw ^ RARNG_FLIP_HI;
+
+    uvec4 m = rarng_mul64_to128(input_lo ^ n.zw ^ RARNG_FLIP_LO, RARNG_PRIME64_1);
+    uvec2 mul_high = rarng_add64(m.zw, rarng_add64(input_hi, rarng_mul32(input_hi.x, RARNG_PRIME32_2_MINUS_1)));
+    uvec2 mul_low = rarng_add64(m.xy, RARNG_LENGTH) ^ uvec2(rarng_swap32(mul_high.y), rarng_swap32(mul_high.x));
+
+    uvec4 r = rarng_mul64_to128(mul_low, RARNG_PRIME64_2);
+    uvec2 result_hi = rarng_add64(r.zw, rarng_mul64(mul_high, RARNG_PRIME64_2));
+
+    return uvec4(rarng_avalanche(r.xy), rarng_avalanche(result_hi));
+}
+"#;
+
+/// A 64-bit value as `[low, high]` 32-bit limbs
+type Limbs64 = [u32; 2];
+
+const PRIME64_1: Limbs64 = [0x85ebca87, 0x9e3779b1];
+const PRIME64_2: Limbs64 = [0x27d4eb4f, 0xc2b2ae3d];
+const AVALANCHE: Limbs64 = [0x9e3779f9, 0x16566791];
+const FLIP_LO: Limbs64 = [0x33362349, 0x59973f00];
+const FLIP_HI: Limbs64 = [0x92d63d58, 0xc2027976];
+const LENGTH: Limbs64 = [0x00000000, 0x03c00000];
+const PRIME32_2_MINUS_1: u32 = 0x85ebca76;
+
+fn mul32(a: u32, b: u32) -> Limbs64 {
+    let (a0, a1, b0, b1) = (a & 0xffff, a >> 16, b & 0xffff, b >> 16);
+    let (p00, p01, p10, p11) = (a0 * b0, a0 * b1, a1 * b0, a1 * b1);
+    let mid = (p00 >> 16) + (p01 & 0xffff) + (p10 & 0xffff);
+
+    [(p00 & 0xffff) | (mid << 16), p11 + (p01 >> 16) + (p10 >> 16) + (mid >> 16)]
+}
+
+fn add64(a: Limbs64, b: Limbs64) -> Limbs64 {
+    let lo = a[0].wrapping_add(b[0]);
+
+    [lo, a[1].wrapping_add(b[1]).wrapping_add((lo < a[0]) as u32)]
+}
+
+fn mul64(a: Limbs64, b: Limbs64) -> Limbs64 {
+    let lo = mul32(a[0], b[0]);
+
+    [lo[0], lo[1].wrapping_add(a[0].wrapping_mul(b[1])).wrapping_add(a[1].wrapping_mul(b[0]))]
+}
+
+fn mul64_to128(a: Limbs64, b: Limbs64) -> [u32; 4] {
+    let lo_lo = mul32(a[0], b[0]);
+    let hi_lo = mul32(a[1], b[0]);
+    let lo_hi = mul32(a[0], b[1]);
+    let hi_hi = mul32(a[1], b[1]);
+    let middle = add64(add64([lo_lo[1], 0], [hi_lo[0], 0]), lo_hi);
+    let upper = add64(add64([hi_lo[1], 0], [middle[1], 0]), hi_hi);
+
+    [lo_lo[0], middle[0], upper[0], upper[1]]
+}
+
+fn xor64(a: Limbs64, b: Limbs64) -> Limbs64 {
+    [a[0] ^ b[0], a[1] ^ b[1]]
+}
+
+fn avalanche(v: Limbs64) -> Limbs64 {
+    let a = xor64(v, [v[1] >> 5, 0]);
+    let b = mul64(a, AVALANCHE);
+
+    xor64(b, [b[1], 0])
+}
+
+/// Split a 128-bit value into 32-bit limbs, least significant first, as the shaders expect.
+pub fn to_limbs(value: u128) -> [u32; 4] {
+    [value as u32, (value >> 32) as u32, (value >> 64) as u32, (value >> 96) as u32]
+}
+
+/// Join 32-bit limbs, least significant first, into a 128-bit value.
+pub fn from_limbs(limbs: [u32; 4]) -> u128 {
+    limbs.iter().rev().fold(0, |value, &limb| value << 32 | limb as u128)
+}
+
+/// `rarng_hash_index` of the shaders, transliterated into Rust with the same 32-bit operations.
+///
+/// Its output always equals [`hash_index`]; it exists to show that the
+/// shader algorithm does, and to debug ports to other languages step by step.
+pub fn reference_hash(digest: [u32; 4], index: [u32; 4]) -> [u32; 4] {
+    let n = [digest[0] ^ index[0], digest[1] ^ index[1], digest[2] ^ index[2], digest[3] ^ index[3]];
+    let input_lo = [n[0], n[1]];
+    let input_hi = xor64([n[2], n[3]], FLIP_HI);
+
+    let m = mul64_to128(xor64(xor64(input_lo, [n[2], n[3]]), FLIP_LO), PRIME64_1);
+    let mul_high = add64([m[2], m[3]], add64(input_hi, mul32(input_hi[0], PRIME32_2_MINUS_1)));
+    let mul_low = xor64(add64([m[0], m[1]], LENGTH), [mul_high[1].swap_bytes(), mul_high[0].swap_bytes()]);
+
+    let r = mul64_to128(mul_low, PRIME64_2);
+    let result_hi = add64([r[2], r[3]], mul64(mul_high, PRIME64_2));
+
+    let (lo, hi) = (avalanche([r[0], r[1]]), avalanche(result_hi));
+
+    [lo[0], lo[1], hi[0], hi[1]]
+}
+
+/// An input to the index hash and its expected output, see [Parity](self#parity).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ParityVector {
+    /// The digest of the node
+    pub digest: u128,
+    /// The position in the node's stream
+    pub index: u128,
+    /// The value at that position, as computed on the CPU
+    pub expected: u128,
+}
+
+/// A fixed set of 64 inputs to the index hash, with their values computed on the CPU.
+///
+/// The inputs cover zero, all ones, single bits in every limb, carries between limbs and the
+/// digests of a few real nodes, so they exercise every step of the shader arithmetic.
+pub fn parity_vectors() -> Vec<ParityVector> {
+    let root = RandomAccessRNG::new("parity");
+
+    let mut inputs = vec![(0, 0), (u128::MAX, 0), (0, u128::MAX), (u128::MAX, 1)];
+    inputs.extend((0..4).map(|limb| (1u128 << (32 * limb), 0)));
+    inputs.extend((0..4).map(|limb| (0xffff_ffff << (32 * limb), 1u128 << (32 * limb + 31))));
+    inputs.extend((0..52).map(|i| (root.get(i as u64).digest(), (i * 0x9e3779b97f4a7c15_u128) << (i % 64))));
+
+    inputs
+        .into_iter()
+        .map(|(digest, index)| ParityVector { digest, index, expected: hash_index(digest, index) })
+        .collect()
+}
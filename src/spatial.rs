@@ -0,0 +1,93 @@
+//! Cells of continuous space.
+//!
+//! Mapping a position such as `(-3.2, 17.9)` to the integer cell containing it is easy to get
+//! subtly wrong: `as i64` truncates towards zero, so `-0.5` and `0.5` land in the same cell, and
+//! ad hoc rounding differs between call sites. [`spatial_key`] fixes one rule and
+//! [`cell_at`](RandomAccessRNG::cell_at) derives the cell's RNG in the same call, so every system
+//! agrees on which cell a position is in.
+//!
+//! # Rounding Rules
+//!
+//! Cell `k` along an axis covers `[k * cell_size, (k + 1) * cell_size)`, so a position exactly on a
+//! boundary belongs to the cell above it and negative positions round down, not towards zero. The
+//! cell is `floor(v / cell_size)`, with the division rounded as IEEE 754 requires. Both operations
+//! are exact or correctly rounded, so the cell is the same on every platform, although the
+//! rounded quotient can put a position that is a decimal multiple of the cell size, like `0.3` with
+//! cells of `0.1`, in the cell below. Cells beyond the range of `i64` saturate.
+
+use crate::RandomAccessRNG;
+
+/// The cell containing `v` along one axis
+fn cell(v: f64, cell_size: f64) -> i64 {
+    assert!(cell_size.is_finite() && cell_size > 0.0, "cell size must be positive and finite, got {}", cell_size);
+    assert!(v.is_finite(), "coordinate must be finite, got {}", v);
+
+    (v / cell_size).floor() as i64
+}
+
+/// The integer key of the 2D cell containing `(x, y)`, see [Rounding Rules](self#rounding-rules).
+///
+/// # Panics
+///
+/// Panics if `cell_size` is not positive and finite, or a coordinate is not finite.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::spatial::spatial_key;
+///
+/// assert_eq!(spatial_key(0.5, -0.5, 1.0), (0, -1));
+/// assert_eq!(spatial_key(32.0, 31.99, 32.0), (1, 0));
+/// assert_eq!(spatial_key(-64.0, -64.1, 32.0), (-2, -3));
+/// ```
+pub fn spatial_key(x: f64, y: f64, cell_size: f64) -> (i64, i64) {
+    (cell(x, cell_size), cell(y, cell_size))
+}
+
+/// The integer key of the 3D cell containing `(x, y, z)`, see [Rounding Rules](self#rounding-rules).
+///
+/// # Panics
+///
+/// Panics if `cell_size` is not positive and finite, or a coordinate is not finite.
+pub fn spatial_key_3d(x: f64, y: f64, z: f64, cell_size: f64) -> (i64, i64, i64) {
+    (cell(x, cell_size), cell(y, cell_size), cell(z, cell_size))
+}
+
+impl RandomAccessRNG {
+
+    /// The child for the 2D cell containing `(x, y)`: [`get2`](RandomAccessRNG::get2) with the
+    /// cell's [`spatial_key`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell_size` is not positive and finite, or a coordinate is not finite.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let weather = RandomAccessRNG::new("world_seed").get("weather");
+    ///
+    /// // Any two positions in the same 16 unit cell share its RNG
+    /// assert_eq!(weather.cell_at(-0.25, 3.5, 16.0).digest(), weather.cell_at(-15.75, 0.0, 16.0).digest());
+    /// assert_eq!(weather.cell_at(-0.25, 3.5, 16.0).digest(), weather.get2(-1, 0).digest());
+    /// ```
+    pub fn cell_at(&self, x: f64, y: f64, cell_size: f64) -> Self {
+        let (cx, cy) = spatial_key(x, y, cell_size);
+
+        self.get2(cx, cy)
+    }
+
+    /// The child for the 3D cell containing `(x, y, z)`: [`get3`](RandomAccessRNG::get3) with the
+    /// cell's [`spatial_key_3d`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell_size` is not positive and finite, or a coordinate is not finite.
+    pub fn cell_at_3d(&self, x: f64, y: f64, z: f64, cell_size: f64) -> Self {
+        let (cx, cy, cz) = spatial_key_3d(x, y, z, cell_size);
+
+        self.get3(cx, cy, cz)
+    }
+}
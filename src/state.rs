@@ -0,0 +1,148 @@
+//! Weighted state machines keyed by entity and step.
+//!
+//! Stepping every entity's state machine from one shared stream ties each entity's behaviour to
+//! the order entities are updated in: spawning, removing or reordering one entity changes what
+//! every later entity does. A [`StateTable`] instead draws each transition from the child keyed by
+//! `(entity, step)`, so an entity's trajectory depends only on its key, its state and the step,
+//! and replays identically however the other entities are processed.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::state::StateTable;
+//!
+//! #[derive(Clone, Copy, Debug, PartialEq)]
+//! enum Mood { Calm, Alert, Hostile }
+//!
+//! let guard = StateTable::new()
+//!     .transition(Mood::Calm, Mood::Calm, 8)
+//!     .transition(Mood::Calm, Mood::Alert, 2)
+//!     .transition(Mood::Alert, Mood::Calm, 5)
+//!     .transition(Mood::Alert, Mood::Hostile, 1)
+//!     .transition(Mood::Hostile, Mood::Hostile, 1);
+//!
+//! let ai = RandomAccessRNG::new("level_3").get("guards");
+//!
+//! // Updating the guards in any order gives each the same trajectory
+//! let forwards: Vec<_> = (0..10u32).map(|id| guard.run(&ai, Mood::Calm, id, 50)).collect();
+//! let mut backwards: Vec<_> = (0..10u32).rev().map(|id| guard.run(&ai, Mood::Calm, id, 50)).collect();
+//! backwards.reverse();
+//!
+//! assert_eq!(forwards, backwards);
+//! ```
+//!
+//! With the `serde` feature, tables can be loaded from data files. A table serializes as a list of
+//! transitions, each with `from`, `to` and `weight` fields.
+//!
+//! ```rust
+//! # #[cfg(feature = "serde")] {
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::state::StateTable;
+//!
+//! let weather: StateTable<String> = serde_json::from_str(r#"[
+//!     { "from": "sun", "to": "sun", "weight": 3 },
+//!     { "from": "sun", "to": "rain", "weight": 1 },
+//!     { "from": "rain", "to": "sun", "weight": 1 }
+//! ]"#).unwrap();
+//!
+//! let sky = RandomAccessRNG::new("world_seed").get("weather");
+//! let tomorrow = weather.next_state(&sky, &"rain".to_string(), "valley", 12);
+//! assert_eq!(tomorrow, Some(&"sun".to_string()));
+//! # }
+//! ```
+
+use std::hash::Hash;
+use crate::RandomAccessRNG;
+use crate::dist::Draws;
+
+/// A weighted transition between two states of a [`StateTable`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transition<S> {
+    /// The state the transition leaves
+    pub from: S,
+    /// The state the transition enters
+    pub to: S,
+    /// The relative weight of the transition among those leaving `from`
+    pub weight: u64,
+}
+
+/// A state machine whose transitions are chosen by weight, keyed by entity and step.
+///
+/// The transitions leaving a state are considered in the order they were declared, so reordering
+/// the declarations of one state's transitions changes its outcomes, while declaring other states'
+/// transitions in between does not.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct StateTable<S> {
+    transitions: Vec<Transition<S>>,
+}
+
+impl<S: PartialEq> Default for StateTable<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: PartialEq> StateTable<S> {
+
+    /// A table with no transitions.
+    pub fn new() -> Self {
+        Self { transitions: Vec::new() }
+    }
+
+    /// Add a transition from `from` to `to` with the given relative `weight`.
+    pub fn transition(mut self, from: S, to: S, weight: u64) -> Self {
+        self.transitions.push(Transition { from, to, weight });
+        self
+    }
+
+    /// Every transition, in the order they were declared.
+    pub fn transitions(&self) -> &[Transition<S>] {
+        &self.transitions
+    }
+
+    /// The state `entity` moves to from `state` at `step`, or `None` if no transition with a
+    /// non-zero weight leaves `state`.
+    ///
+    /// The transition is drawn from `rng.get((entity, step))` with
+    /// [`Draws::weighted_index`](crate::dist::Draws::weighted_index), so it is independent of
+    /// every other entity and step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the weights leaving `state` overflow a `u64`.
+    pub fn next_state<E: Hash>(&self, rng: &RandomAccessRNG, state: &S, entity: E, step: u64) -> Option<&S> {
+        let (targets, weights): (Vec<&S>, Vec<u64>) = self
+            .transitions
+            .iter()
+            .filter(|t| t.from == *state)
+            .map(|t| (&t.to, t.weight))
+            .unzip();
+
+        if weights.iter().all(|&w| w == 0) {
+            return None;
+        }
+
+        Some(targets[Draws::new(&mut rng.get((entity, step))).weighted_index(&weights)])
+    }
+
+    /// The states `entity` passes through over `steps` steps from `start`, beginning with `start`.
+    ///
+    /// Step `i` moves from the `i`th state of the trajectory to the next. The trajectory ends early
+    /// if it reaches a state with no transitions leaving it.
+    pub fn run<E: Hash>(&self, rng: &RandomAccessRNG, start: S, entity: E, steps: u64) -> Vec<S>
+    where
+        S: Clone,
+    {
+        let mut trajectory = vec![start];
+
+        for step in 0..steps {
+            match self.next_state(rng, trajectory.last().unwrap(), &entity, step) {
+                Some(next) => trajectory.push(next.clone()),
+                None => break,
+            }
+        }
+
+        trajectory
+    }
+}
@@ -0,0 +1,86 @@
+//! Values as pure functions of a seed and an address.
+//!
+//! Data-parallel code, such as a `rayon` pass over every tile of a map, or code being ported to a
+//! GPU, is easiest to write against a pure function of `(seed, path, index)` with no generator to
+//! create, share or clone. The functions here compute a single value that way, without allocating
+//! or constructing an RNG, and give exactly the value of
+//! `RandomAccessRNG::new(seed).path(path)` at `index` under the default [`Scheme`](crate::Scheme).
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::stateless::{f64_at, u64_at};
+//!
+//! // Every tile's height, computed independently
+//! let heights: Vec<f64> = (0..1024).map(|tile| f64_at("world_seed", "terrain/height", tile)).collect();
+//!
+//! let terrain = RandomAccessRNG::new("world_seed").path("terrain/height");
+//! assert_eq!(heights[700], terrain.f64_at(700));
+//! assert_eq!(u64_at("world_seed", "terrain/height", 700), terrain.peek_at(700));
+//! ```
+//!
+//! Paths follow the rules of [`RandomAccessRNG::path`](crate::RandomAccessRNG::path), and every function panics where it would.
+
+use std::hash::Hash;
+use std::path::Path;
+use xxhash_rust::xxh3::Xxh3;
+use crate::float::to_f64;
+use crate::mix::hash_index;
+use crate::random_access_rng::path_keys;
+
+/// The [`digest`](crate::RandomAccessRNG::digest) of the node at `path` below the root seeded
+/// with `seed`.
+///
+/// # Panics
+///
+/// Panics if `path` can't be used as a key, see [`RandomAccessRNG::try_path`](crate::RandomAccessRNG::try_path).
+pub fn digest_at<H: Hash, P: AsRef<Path>>(seed: H, path: P) -> u128 {
+    let mut hasher = Xxh3::new();
+    seed.hash(&mut hasher);
+
+    match path_keys(path.as_ref(), false) {
+        Ok(keys) => keys.for_each(|key| key.hash(&mut hasher)),
+        Err(e) => panic!("{}", e),
+    }
+
+    hasher.digest128()
+}
+
+/// The 128-bit value at position `index` of the node at `path` below the root seeded with
+/// `seed`, as given by [`seek_u128`](crate::RandomAccessRNG::seek_u128).
+///
+/// # Panics
+///
+/// Panics if `path` can't be used as a key.
+pub fn u128_at<H: Hash, P: AsRef<Path>>(seed: H, path: P, index: u128) -> u128 {
+    hash_index(digest_at(seed, path), index)
+}
+
+/// The 64-bit value at position `index` of the node at `path` below the root seeded with `seed`,
+/// as given by [`peek_at`](crate::RandomAccessRNG::peek_at).
+///
+/// # Panics
+///
+/// Panics if `path` can't be used as a key.
+pub fn u64_at<H: Hash, P: AsRef<Path>>(seed: H, path: P, index: u64) -> u64 {
+    u128_at(seed, path, index as u128) as u64
+}
+
+/// The 32-bit value at position `index` of the node at `path` below the root seeded with `seed`,
+/// the low 32 bits of [`u64_at`] as [`next_u32`](rand_core::RngCore::next_u32) takes them.
+///
+/// # Panics
+///
+/// Panics if `path` can't be used as a key.
+pub fn u32_at<H: Hash, P: AsRef<Path>>(seed: H, path: P, index: u64) -> u32 {
+    u64_at(seed, path, index) as u32
+}
+
+/// The uniform `f64` in `[0, 1)` at position `index` of the node at `path` below the root seeded
+/// with `seed`, as given by [`f64_at`](crate::RandomAccessRNG::f64_at).
+///
+/// # Panics
+///
+/// Panics if `path` can't be used as a key.
+pub fn f64_at<H: Hash, P: AsRef<Path>>(seed: H, path: P, index: u64) -> f64 {
+    to_f64(u64_at(seed, path, index))
+}
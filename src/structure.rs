@@ -0,0 +1,143 @@
+//! Structures spanning several chunks.
+//!
+//! A structure such as a village often covers more than one chunk, but chunks are generated
+//! independently and in any order. Every chunk a structure touches has to agree on where the
+//! structure is, which way it faces and which chunk owns it, without generating the others.
+//!
+//! Here the world is divided into square regions of [`StructureLayout::spacing`] chunks, each
+//! holding exactly one structure of a given kind. [`structure_anchor`] derives that structure's
+//! owner chunk and orientation purely from hashing the structure key and region, and
+//! [`structures_at`] finds every structure covering a chunk by checking only the few regions
+//! that could reach it.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::structure::{StructureLayout, structure_anchor, structures_at};
+//!
+//! let world = RandomAccessRNG::new("world_seed");
+//!
+//! // One village per 8x8 chunk region, each 3x2 chunks when facing north
+//! let villages = StructureLayout { spacing: 8, size: (3, 2) };
+//!
+//! let village = structure_anchor(&world, "village", (0, 0), &villages);
+//!
+//! // Every chunk covered by the village finds the same anchor, without generating the owner
+//! for chunk in village.chunks() {
+//!     assert_eq!(structures_at(&world, "village", chunk, &villages), vec![village]);
+//! }
+//! ```
+
+use std::hash::Hash;
+use crate::RandomAccessRNG;
+use crate::dist::Draws;
+use crate::polyline::Point;
+
+/// The direction a structure faces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// Unrotated
+    North,
+    /// Rotated a quarter turn clockwise
+    East,
+    /// Rotated half a turn
+    South,
+    /// Rotated a quarter turn anticlockwise
+    West,
+}
+
+impl Orientation {
+
+    /// The `(width, depth)` of a footprint of unrotated `size` facing this way
+    fn rotate(self, (width, depth): (i64, i64)) -> (i64, i64) {
+        match self {
+            Orientation::North | Orientation::South => (width, depth),
+            Orientation::East | Orientation::West => (depth, width),
+        }
+    }
+}
+
+/// How a kind of structure is laid out, in chunks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StructureLayout {
+    /// Side of the square regions that each hold one structure
+    pub spacing: i64,
+    /// The `(width, depth)` of a structure facing [`Orientation::North`]
+    pub size: (i64, i64),
+}
+
+/// The placement of one structure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Anchor {
+    /// The region holding the structure
+    pub region: Point,
+    /// The chunk owning the structure, at the lowest corner of its footprint
+    pub owner: Point,
+    /// The direction the structure faces
+    pub orientation: Orientation,
+    /// The `(width, depth)` of the footprint, after rotation
+    pub size: (i64, i64),
+}
+
+impl Anchor {
+
+    /// Whether the structure covers `chunk`.
+    pub fn contains(&self, chunk: Point) -> bool {
+        (self.owner.0..self.owner.0 + self.size.0).contains(&chunk.0)
+            && (self.owner.1..self.owner.1 + self.size.1).contains(&chunk.1)
+    }
+
+    /// Every chunk covered by the structure.
+    pub fn chunks(&self) -> impl Iterator<Item = Point> + '_ {
+        (self.owner.0..self.owner.0 + self.size.0)
+            .flat_map(move |x| (self.owner.1..self.owner.1 + self.size.1).map(move |y| (x, y)))
+    }
+}
+
+/// The structure identified by `structure_key` in `region`.
+///
+/// The owner chunk is uniform within the region and the orientation uniform over the four
+/// directions, drawn from positions 0 to 2 of `rng.get(structure_key).get(region)`. Footprints may
+/// extend past the edge of their region, so structures in neighbouring regions can overlap;
+/// [`structures_at`] reports all of them and leaves resolving overlaps to the caller.
+///
+/// # Panics
+///
+/// Panics if the spacing or either dimension of the size is not positive.
+pub fn structure_anchor<H: Hash>(rng: &RandomAccessRNG, structure_key: H, region: Point, layout: &StructureLayout) -> Anchor {
+    assert!(layout.spacing > 0 && layout.size.0 > 0 && layout.size.1 > 0, "spacing and size must be positive");
+
+    let mut node = rng.get(structure_key).get(region);
+    let mut draws = Draws::new(&mut node);
+
+    let x = draws.below(layout.spacing as u64) as i64;
+    let y = draws.below(layout.spacing as u64) as i64;
+    let orientation = [Orientation::North, Orientation::East, Orientation::South, Orientation::West][draws.below(4) as usize];
+
+    Anchor {
+        region,
+        owner: (region.0 * layout.spacing + x, region.1 * layout.spacing + y),
+        orientation,
+        size: orientation.rotate(layout.size),
+    }
+}
+
+/// Every structure identified by `structure_key` that covers `chunk`.
+///
+/// Only the regions whose structures could reach `chunk` are checked, so this is cheap enough to
+/// call while generating every chunk. Anchors are returned in order of region.
+///
+/// # Panics
+///
+/// Panics if the spacing or either dimension of the size is not positive.
+pub fn structures_at<H: Hash>(rng: &RandomAccessRNG, structure_key: H, chunk: Point, layout: &StructureLayout) -> Vec<Anchor> {
+    assert!(layout.spacing > 0 && layout.size.0 > 0 && layout.size.1 > 0, "spacing and size must be positive");
+
+    let reach = layout.size.0.max(layout.size.1) - 1;
+    let regions = |c: i64| (c - reach).div_euclid(layout.spacing)..=c.div_euclid(layout.spacing);
+
+    regions(chunk.0)
+        .flat_map(|rx| regions(chunk.1).map(move |ry| (rx, ry)))
+        .map(|region| structure_anchor(rng, &structure_key, region, layout))
+        .filter(|anchor| anchor.contains(chunk))
+        .collect()
+}
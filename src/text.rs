@@ -0,0 +1,48 @@
+//! Choosing text such as barks and flavour text.
+
+use std::hash::Hash;
+use crate::RandomAccessRNG;
+use crate::dist::Draws;
+
+/// Choose one of `lines` for `key`, independently of any stream position.
+///
+/// Picking a line with `lines[rng.next_u64() as usize % lines.len()]` ties the choice to how many
+/// values were drawn before it, so an NPC's greeting changes whenever something unrelated draws
+/// one more value. Keying the choice instead, typically by `(entity, context)`, gives the same line
+/// for the same entity in the same context however the game got there.
+///
+/// The choice depends only on `key` and the *number* of lines, never their content, so the same
+/// line index is chosen for every translation of a line table. Returns [`None`] if `lines` is
+/// empty.
+///
+/// The index is `dist::uniform(0..lines.len()).sample_at(rng, key)`.
+///
+/// # Examples
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::text::choose_line;
+///
+/// let barks = RandomAccessRNG::new("world_seed").get("barks");
+///
+/// let english = ["Well met!", "Good day.", "What do you want?"];
+/// let french = ["Bien le bonjour !", "Bonne journée.", "Que voulez-vous ?"];
+///
+/// let key = ("guard_17", "greeting");
+///
+/// // Same line every time, in every language
+/// let line = choose_line(&barks, key, &english).unwrap();
+/// assert_eq!(choose_line(&barks, key, &english), Some(line));
+///
+/// let index = english.iter().position(|l| l == line).unwrap();
+/// assert_eq!(choose_line(&barks, key, &french), Some(&french[index]));
+/// ```
+pub fn choose_line<'a, H: Hash, S>(rng: &RandomAccessRNG, key: H, lines: &'a [S]) -> Option<&'a S> {
+    if lines.is_empty() {
+        return None;
+    }
+
+    let index = Draws::new(&mut rng.get(key)).below(lines.len() as u64);
+
+    Some(&lines[index as usize])
+}
@@ -0,0 +1,231 @@
+//! Relative navigation through the hierarchy.
+//!
+//! A node only knows its own digest, not how it was derived, so [`RandomAccessRNG::path`] can't
+//! resolve `..`. A [`TrackedRNG`] remembers the chain of nodes from its root, which makes paths
+//! written relative to a node, as designers do in data files, resolvable: `..` steps back to the
+//! parent and `.` stays put. A path starting with `/` resolves from the root, so any tracked node
+//! can reach every other node in its hierarchy.
+//!
+//! ```rust
+//! use random_access_rng::{PathError, RandomAccessRNG};
+//! use random_access_rng::tracked::TrackedRNG;
+//!
+//! let world = TrackedRNG::new(RandomAccessRNG::new("world_seed"));
+//!
+//! // A data file for the forest biome refers to its sibling
+//! let forest = world.path("biomes/forest");
+//! let swamp = forest.path("../swamp");
+//!
+//! assert_eq!(swamp.digest(), RandomAccessRNG::new("world_seed").path("biomes/swamp").digest());
+//! assert_eq!(forest.path("./trees/.").digest(), forest.path("trees").digest());
+//!
+//! // However deep a node is, an absolute path starts from the root
+//! let tree = forest.path("trees/oak/17");
+//! assert_eq!(tree.path("/biomes/swamp").digest(), swamp.digest());
+//! assert_eq!(tree.path("/").depth(), 0);
+//!
+//! // The root has no parent
+//! assert_eq!(world.try_path("biomes/../..").err(), Some(PathError::AboveRoot));
+//! ```
+//!
+//! # Lineage
+//!
+//! A tracked node also records the key each level was derived with, so when a replay diverges
+//! the node that produced a value can be named. [`full_path`](TrackedRNG::full_path) lists them
+//! from the root. Path components are recorded as written, and keys given to
+//! [`get`](TrackedRNG::get) with their [`Debug`] representation, so a string key appears quoted:
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::tracked::TrackedRNG;
+//!
+//! let world = TrackedRNG::new(RandomAccessRNG::new("world_seed"));
+//!
+//! let oak = world.path("biomes/forest/../swamp").get("trees").get(17u64);
+//!
+//! assert_eq!(oak.full_path(), "/biomes/swamp/\"trees\"/17");
+//! assert_eq!(oak.lineage(), ["biomes", "swamp", "\"trees\"", "17"]);
+//! assert_eq!(world.full_path(), "/");
+//! ```
+
+use std::ffi::OsStr;
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+use std::ops::{Deref, DerefMut};
+use std::path::{Component, Path};
+use crate::{PathError, RandomAccessRNG};
+
+/// An RNG that remembers its ancestors, see the [module documentation](self).
+///
+/// Dereferences to the current node, so values can be drawn from it like any RNG. Every node is
+/// derived exactly as by [`RandomAccessRNG`], so tracking never changes the values produced.
+#[derive(Clone)]
+pub struct TrackedRNG {
+    /// Every node from the root to the current one
+    chain: Vec<RandomAccessRNG>,
+    /// The key each node after the root was derived with
+    keys: Vec<String>,
+}
+
+impl TrackedRNG {
+
+    /// Start tracking from `root`, which has no parent.
+    pub fn new(root: RandomAccessRNG) -> Self {
+        Self { chain: vec![root], keys: Vec::new() }
+    }
+
+    /// The root the chain starts from.
+    pub fn root(&self) -> &RandomAccessRNG {
+        &self.chain[0]
+    }
+
+    /// The number of levels below the root, 0 for the root itself.
+    pub fn depth(&self) -> usize {
+        self.chain.len() - 1
+    }
+
+    /// The parent of the current node, or `None` at the root.
+    pub fn parent(&self) -> Option<Self> {
+        let mut parent = self.clone();
+
+        parent.up().ok()?;
+        Some(parent)
+    }
+
+    /// The child at `key`, see [`RandomAccessRNG::get`]. The key is recorded in the
+    /// [lineage](self#lineage) with its [`Debug`] representation.
+    pub fn get<H: Hash + Debug>(&self, key: H) -> Self {
+        let mut child = self.clone();
+        child.keys.push(format!("{:?}", key));
+        child.chain.push(self.current().get(key));
+        child
+    }
+
+    /// The key each level below the root was derived with, from the root down, see
+    /// [Lineage](self#lineage).
+    pub fn lineage(&self) -> &[String] {
+        &self.keys
+    }
+
+    /// The [lineage](self#lineage) as an absolute path, such as `/biomes/swamp/"trees"/17`, or
+    /// `/` for the root.
+    pub fn full_path(&self) -> String {
+        match self.keys.is_empty() {
+            true => "/".to_string(),
+            false => self.keys.iter().map(|key| format!("/{}", key)).collect(),
+        }
+    }
+
+    /// The node at `path`, panicking where [`try_path`](TrackedRNG::try_path) would
+    /// return an error.
+    pub fn path<P: AsRef<Path>>(&self, path: P) -> Self {
+        match self.try_path(path) {
+            Ok(tracked) => tracked,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// The node at `path`, relative to the current node unless it starts with `/`.
+    ///
+    /// Normal components derive children exactly as [`RandomAccessRNG::try_path`], `.` is
+    /// ignored and `..` returns to the parent. Unlike `RandomAccessRNG::path`, which ignores a
+    /// leading `/`, an absolute path resolves from the root, so `tracked.path("/a/b")` is
+    /// `tracked.root().path("a/b")` however deep `tracked` is. Ancestors reached with `..` or `/`
+    /// start from position 0, like newly derived nodes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PathError::AboveRoot`] if `..` would leave the root, and the errors of
+    /// `RandomAccessRNG::try_path` for Windows prefixes and invalid escapes.
+    pub fn try_path<P: AsRef<Path>>(&self, path: P) -> Result<Self, PathError> {
+        let mut tracked = self.clone();
+
+        for component in path.as_ref().components() {
+            match component {
+                Component::Normal(c) => tracked.down(c)?,
+                Component::ParentDir => tracked.up()?,
+                Component::RootDir => tracked.top(),
+                Component::CurDir => {},
+                Component::Prefix(p) => return Err(PathError::Prefix(p.as_os_str().to_string_lossy().into_owned())),
+            }
+        }
+
+        Ok(tracked)
+    }
+
+    /// The current node, at its current position.
+    pub fn into_inner(mut self) -> RandomAccessRNG {
+        self.chain.pop().unwrap()
+    }
+
+    /// The current node
+    fn current(&self) -> &RandomAccessRNG {
+        self.chain.last().unwrap()
+    }
+
+    /// Move to the child at one path component
+    fn down(&mut self, component: &OsStr) -> Result<(), PathError> {
+        let child = self.current().try_path(Path::new(component))?;
+        self.keys.push(component.to_string_lossy().into_owned());
+        self.chain.push(child);
+        Ok(())
+    }
+
+    /// Move to the root, resetting its position
+    fn top(&mut self) {
+        self.chain.truncate(1);
+        self.keys.clear();
+        self.chain[0].set_position(0);
+    }
+
+    /// Move to the parent, resetting its position
+    fn up(&mut self) -> Result<(), PathError> {
+        if self.chain.len() == 1 {
+            return Err(PathError::AboveRoot);
+        }
+
+        self.chain.pop();
+        self.keys.pop();
+        self.chain.last_mut().unwrap().set_position(0);
+        Ok(())
+    }
+}
+
+/// Shows the [`full_path`](TrackedRNG::full_path) and the current node.
+///
+/// ```rust
+/// use random_access_rng::RandomAccessRNG;
+/// use random_access_rng::tracked::TrackedRNG;
+///
+/// let forest = TrackedRNG::new(RandomAccessRNG::new("world_seed")).path("biomes/forest");
+///
+/// assert!(format!("{:?}", forest).starts_with("TrackedRNG { path: \"/biomes/forest\", node: RandomAccessRNG { digest: 0x"));
+/// ```
+impl fmt::Debug for TrackedRNG {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrackedRNG")
+            .field("path", &self.full_path())
+            .field("node", self.current())
+            .finish()
+    }
+}
+
+impl From<RandomAccessRNG> for TrackedRNG {
+    fn from(root: RandomAccessRNG) -> Self {
+        Self::new(root)
+    }
+}
+
+impl Deref for TrackedRNG {
+    type Target = RandomAccessRNG;
+
+    fn deref(&self) -> &RandomAccessRNG {
+        self.current()
+    }
+}
+
+impl DerefMut for TrackedRNG {
+    fn deref_mut(&mut self) -> &mut RandomAccessRNG {
+        self.chain.last_mut().unwrap()
+    }
+}
@@ -0,0 +1,144 @@
+//! Evolving how a namespace is keyed.
+//!
+//! Sometimes a game has to change how paths below a namespace are formed, for example when the
+//! chunk size changes and `chunks/3/7` no longer means the same area. Reusing the namespace would
+//! silently give old saves new content, and renaming it loses track of what the old paths were.
+//! [`versioned_path`](RandomAccessRNG::versioned_path) keeps every keying of a namespace
+//! side by side, and [`Namespace`] records which one is current so old saves can resolve their
+//! paths under the version they were created with.
+//!
+//! Version 0 is the namespace as it was before versioning was introduced, so adopting versioning
+//! doesn't change any existing content.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::versioning::Namespace;
+//!
+//! let world = RandomAccessRNG::new("world_seed");
+//!
+//! // Version 1 switched from 16 to 32 block chunks
+//! let chunks = Namespace::new("chunks", 1);
+//!
+//! // New saves use the current keying, old saves keep the keying they were created with
+//! let new_chunk = chunks.path(&world, "1/3");
+//! let old_chunk = chunks.path_at(&world, 0, "3/7");
+//!
+//! assert_eq!(old_chunk.digest(), world.path("chunks/3/7").digest());
+//! assert_ne!(new_chunk.digest(), world.path("chunks/1/3").digest());
+//! ```
+
+use std::path::Path;
+use xxhash_rust::xxh3::xxh3_128;
+use crate::RandomAccessRNG;
+
+/// Domain separating version nodes from other prehashed keys, "version_" in ASCII
+const VERSION_DOMAIN: u64 = 0x76657273696f6e5f;
+
+impl RandomAccessRNG {
+
+    /// The node at `rest` below version `version` of the namespace at `ns`.
+    ///
+    /// Version 0 is the unversioned namespace, `self.path(ns).path(rest)`. Every later version
+    /// inserts a node derived from the version number between the namespace and `rest`, so each
+    /// version's paths are independent of every other's. Both `ns` and `rest` follow the rules of
+    /// [`path`](RandomAccessRNG::path).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    ///
+    /// let world = RandomAccessRNG::new("world_seed");
+    ///
+    /// let legacy = world.versioned_path("loot", 0, "chest/12");
+    /// let current = world.versioned_path("loot", 2, "chest/12");
+    ///
+    /// assert_eq!(legacy.digest(), world.path("loot/chest/12").digest());
+    /// assert_ne!(current.digest(), legacy.digest());
+    /// assert_ne!(current.digest(), world.versioned_path("loot", 1, "chest/12").digest());
+    /// ```
+    pub fn versioned_path<N: AsRef<Path>, P: AsRef<Path>>(&self, ns: N, version: u32, rest: P) -> Self {
+        let namespace = self.path(ns);
+
+        if version == 0 {
+            return namespace.path(rest);
+        }
+
+        let mut key = [0u8; 12];
+        key[..8].copy_from_slice(&VERSION_DOMAIN.to_le_bytes());
+        key[8..].copy_from_slice(&version.to_le_bytes());
+
+        namespace.get_prehashed(xxh3_128(&key)).path(rest)
+    }
+}
+
+/// A namespace and the version of its keying currently in use.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Namespace {
+    name: String,
+    current: u32,
+}
+
+impl Namespace {
+
+    /// The namespace at path `name`, currently at version `current`.
+    pub fn new<N: Into<String>>(name: N, current: u32) -> Self {
+        Self { name: name.into(), current }
+    }
+
+    /// The path of the namespace.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The version new content is keyed with.
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// The node at `rest` below the current version of the namespace.
+    pub fn path<P: AsRef<Path>>(&self, rng: &RandomAccessRNG, rest: P) -> RandomAccessRNG {
+        rng.versioned_path(&self.name, self.current, rest)
+    }
+
+    /// The node at `rest` below an earlier `version` of the namespace, for resolving the paths of
+    /// content created before the keying changed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `version` is newer than the current version.
+    pub fn path_at<P: AsRef<Path>>(&self, rng: &RandomAccessRNG, version: u32, rest: P) -> RandomAccessRNG {
+        assert!(version <= self.current, "version {} of {} is newer than the current version {}", version, self.name, self.current);
+
+        rng.versioned_path(&self.name, version, rest)
+    }
+
+    /// The nodes for migrating content from `version` to the current version: the node at
+    /// `old_rest` under `version` and the node at `new_rest` under the current version.
+    ///
+    /// Generating from both lets a save be upgraded explicitly, for example by keeping whatever the
+    /// player has already seen from the old node and generating the rest from the new one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `version` is newer than the current version.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use random_access_rng::RandomAccessRNG;
+    /// use random_access_rng::versioning::Namespace;
+    ///
+    /// let world = RandomAccessRNG::new("world_seed");
+    /// let chunks = Namespace::new("chunks", 1);
+    ///
+    /// // Chunk (3, 7) of 16 blocks lies in chunk (1, 3) of 32 blocks
+    /// let (old, new) = chunks.migrate(&world, 0, "3/7", "1/3");
+    ///
+    /// assert_eq!(old.digest(), chunks.path_at(&world, 0, "3/7").digest());
+    /// assert_eq!(new.digest(), chunks.path(&world, "1/3").digest());
+    /// ```
+    pub fn migrate<P: AsRef<Path>, Q: AsRef<Path>>(&self, rng: &RandomAccessRNG, version: u32, old_rest: P, new_rest: Q) -> (RandomAccessRNG, RandomAccessRNG) {
+        (self.path_at(rng, version, old_rest), self.path(rng, new_rest))
+    }
+}
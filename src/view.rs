@@ -0,0 +1,94 @@
+//! Indexing syntax for exploring a hierarchy.
+//!
+//! In exploratory code and tests, chains of [`get`](RandomAccessRNG::get) and
+//! [`peek_at`](RandomAccessRNG::peek_at) calls get in the way of what is being looked at. An
+//! [`RngView`] is an immutable view of a node that can be indexed instead: a string gives the
+//! child at that key and a `u64` gives the value at that position.
+//!
+//! Requires the `index` feature.
+//!
+//! ```rust
+//! use random_access_rng::RandomAccessRNG;
+//! use random_access_rng::view::RngView;
+//!
+//! let world = RngView::new(RandomAccessRNG::new("world_seed"));
+//!
+//! let trees = world["biomes"]["forest"][12];
+//!
+//! assert_eq!(trees, RandomAccessRNG::new("world_seed").get("biomes").get("forest").peek_at(12));
+//! ```
+//!
+//! Indexing returns a reference, so every child and value is kept by the view that produced it
+//! until that view is dropped. This makes repeated lookups cheap, but a view indexed with many
+//! different keys grows without bound; use the RNG methods directly in long-running code.
+
+use std::ops::{Deref, Index};
+use elsa::FrozenMap;
+use crate::RandomAccessRNG;
+
+/// An immutable view of a node, indexable by child key and by position, see the
+/// [module documentation](self).
+///
+/// Dereferences to the node, so any method that doesn't move the stream can be called on it.
+pub struct RngView {
+    rng: RandomAccessRNG,
+    /// The children indexed so far
+    children: FrozenMap<String, Box<RngView>>,
+    /// The values indexed so far
+    values: FrozenMap<u64, Box<u64>>,
+}
+
+impl RngView {
+
+    /// View `rng`.
+    pub fn new(rng: RandomAccessRNG) -> Self {
+        Self {
+            rng,
+            children: FrozenMap::new(),
+            values: FrozenMap::new(),
+        }
+    }
+
+    /// The viewed node.
+    pub fn into_inner(self) -> RandomAccessRNG {
+        self.rng
+    }
+}
+
+impl From<RandomAccessRNG> for RngView {
+    fn from(rng: RandomAccessRNG) -> Self {
+        Self::new(rng)
+    }
+}
+
+/// The child at a string key, as [`RandomAccessRNG::get`] derives it.
+impl Index<&str> for RngView {
+    type Output = RngView;
+
+    fn index(&self, key: &str) -> &RngView {
+        match self.children.get(key) {
+            Some(child) => child,
+            None => self.children.insert(key.to_string(), Box::new(RngView::new(self.rng.get(key)))),
+        }
+    }
+}
+
+/// The value at a position, as [`RandomAccessRNG::peek_at`] gives it.
+impl Index<u64> for RngView {
+    type Output = u64;
+
+    fn index(&self, index: u64) -> &u64 {
+        match self.values.get(&index) {
+            Some(value) => value,
+            None => self.values.insert(index, Box::new(self.rng.peek_at(index))),
+        }
+    }
+}
+
+impl Deref for RngView {
+    type Target = RandomAccessRNG;
+
+    fn deref(&self) -> &RandomAccessRNG {
+        &self.rng
+    }
+}